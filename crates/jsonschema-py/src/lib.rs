@@ -145,23 +145,23 @@ impl ValidationErrorKind {
             jsonschema::error::ValidationErrorKind::AdditionalItems { limit } => {
                 ValidationErrorKind::AdditionalItems { limit }
             }
-            jsonschema::error::ValidationErrorKind::AdditionalProperties { unexpected } => {
+            jsonschema::error::ValidationErrorKind::AdditionalProperties { unexpected, .. } => {
                 ValidationErrorKind::AdditionalProperties {
                     unexpected: PyList::new(py, unexpected)?.unbind(),
                 }
             }
-            jsonschema::error::ValidationErrorKind::AnyOf => ValidationErrorKind::AnyOf {},
+            jsonschema::error::ValidationErrorKind::AnyOf { .. } => ValidationErrorKind::AnyOf {},
             jsonschema::error::ValidationErrorKind::BacktrackLimitExceeded { error } => {
                 ValidationErrorKind::BacktrackLimitExceeded {
                     error: error.to_string(),
                 }
             }
-            jsonschema::error::ValidationErrorKind::Constant { expected_value } => {
+            jsonschema::error::ValidationErrorKind::Constant { expected_value, .. } => {
                 ValidationErrorKind::Constant {
                     expected_value: pythonize::pythonize(py, &expected_value)?.unbind(),
                 }
             }
-            jsonschema::error::ValidationErrorKind::Contains => ValidationErrorKind::Contains {},
+            jsonschema::error::ValidationErrorKind::Contains { .. } => ValidationErrorKind::Contains {},
             jsonschema::error::ValidationErrorKind::ContentEncoding { content_encoding } => {
                 ValidationErrorKind::ContentEncoding { content_encoding }
             }
@@ -171,7 +171,10 @@ impl ValidationErrorKind {
             jsonschema::error::ValidationErrorKind::Custom { message } => {
                 ValidationErrorKind::Custom { message }
             }
-            jsonschema::error::ValidationErrorKind::Enum { options } => ValidationErrorKind::Enum {
+            jsonschema::error::ValidationErrorKind::ErrorMessage { kind, .. } => {
+                ValidationErrorKind::try_new(py, *kind, mask)?
+            }
+            jsonschema::error::ValidationErrorKind::Enum { options, .. } => ValidationErrorKind::Enum {
                 options: pythonize::pythonize(py, &options)?.unbind(),
             },
             jsonschema::error::ValidationErrorKind::ExclusiveMaximum { limit } => {
@@ -229,10 +232,10 @@ impl ValidationErrorKind {
             jsonschema::error::ValidationErrorKind::Not { schema } => ValidationErrorKind::Not {
                 schema: pythonize::pythonize(py, &schema)?.unbind(),
             },
-            jsonschema::error::ValidationErrorKind::OneOfMultipleValid => {
+            jsonschema::error::ValidationErrorKind::OneOfMultipleValid { .. } => {
                 ValidationErrorKind::OneOfMultipleValid {}
             }
-            jsonschema::error::ValidationErrorKind::OneOfNotValid => {
+            jsonschema::error::ValidationErrorKind::OneOfNotValid { .. } => {
                 ValidationErrorKind::OneOfNotValid {}
             }
             jsonschema::error::ValidationErrorKind::Pattern { pattern } => {
@@ -257,7 +260,7 @@ impl ValidationErrorKind {
                     },
                 }
             }
-            jsonschema::error::ValidationErrorKind::Required { property } => {
+            jsonschema::error::ValidationErrorKind::Required { property, .. } => {
                 ValidationErrorKind::Required {
                     property: pythonize::pythonize(py, &property)?.unbind(),
                 }
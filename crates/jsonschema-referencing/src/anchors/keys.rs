@@ -26,6 +26,9 @@ impl AnchorKey {
     pub(crate) fn new(uri: Uri<String>, name: String) -> Self {
         Self { uri, name }
     }
+    pub(crate) fn uri(&self) -> &Uri<String> {
+        &self.uri
+    }
 }
 
 #[derive(Copy, Clone, Hash, PartialEq, Eq)]
@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use fluent_uri::Uri;
 use serde_json::Value;
 
 mod keys;
@@ -7,6 +8,65 @@ mod keys;
 use crate::{Draft, Error, Resolved, Resolver, Resource};
 pub(crate) use keys::{AnchorKey, AnchorKeyRef};
 
+/// Kind of an anchor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AnchorKind {
+    /// `$anchor`, or a plain name fragment in `$id` / `id` in older drafts.
+    Default,
+    /// `$dynamicAnchor` from Draft 2020-12.
+    Dynamic,
+}
+
+/// An anchor registered in a [`Registry`](crate::Registry).
+#[derive(Debug, Clone, Copy)]
+pub struct AnchorEntry<'r> {
+    uri: &'r Uri<String>,
+    anchor: &'r Anchor,
+}
+
+impl<'r> AnchorEntry<'r> {
+    pub(crate) fn new(key: &'r AnchorKey, anchor: &'r Anchor) -> Self {
+        AnchorEntry {
+            uri: key.uri(),
+            anchor,
+        }
+    }
+    /// URI of the resource the anchor is defined in.
+    #[must_use]
+    pub fn uri(&self) -> &'r Uri<String> {
+        self.uri
+    }
+    /// Anchor's name.
+    #[must_use]
+    pub fn name(&self) -> &'r str {
+        self.anchor.name()
+    }
+    /// Whether this is a regular or a dynamic anchor.
+    #[must_use]
+    pub fn kind(&self) -> AnchorKind {
+        match self.anchor {
+            Anchor::Default { .. } => AnchorKind::Default,
+            Anchor::Dynamic { .. } => AnchorKind::Dynamic,
+        }
+    }
+    /// The subschema the anchor points to.
+    #[must_use]
+    pub fn contents(&self) -> &'r Value {
+        match self.anchor {
+            Anchor::Default { resource, .. } | Anchor::Dynamic { resource, .. } => {
+                resource.contents()
+            }
+        }
+    }
+    /// JSON Schema draft under which the subschema is interpreted.
+    #[must_use]
+    pub fn draft(&self) -> Draft {
+        match self.anchor {
+            Anchor::Default { draft, .. } | Anchor::Dynamic { draft, .. } => *draft,
+        }
+    }
+}
+
 /// An anchor within a resource.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) enum Anchor {
@@ -138,6 +198,7 @@ pub(crate) fn legacy_anchor_in_id<'a>(
 
 #[cfg(test)]
 mod tests {
+    use super::AnchorKind;
     use crate::{Draft, Registry};
     use serde_json::json;
 
@@ -240,6 +301,37 @@ mod tests {
         assert_eq!(fourth.contents(), two.contents());
     }
 
+    #[test]
+    fn test_list_anchors() {
+        let schema = Draft::Draft202012.create_resource(json!({
+            "$dynamicAnchor": "meta",
+            "$defs": {
+                "foo": {"$anchor": "foo", "type": "string"},
+                "bar": {"$id": "bar", "$anchor": "bar"}
+            }
+        }));
+        let registry = Registry::try_new("http://example.com", schema).expect("Invalid resources");
+        let mut anchors: Vec<_> = registry
+            .anchors()
+            .map(|anchor| (anchor.uri().as_str(), anchor.name(), anchor.kind()))
+            .collect();
+        anchors.sort_unstable_by_key(|(_, name, _)| *name);
+        assert_eq!(
+            anchors,
+            [
+                ("http://example.com/bar", "bar", AnchorKind::Default),
+                ("http://example.com", "foo", AnchorKind::Default),
+                ("http://example.com", "meta", AnchorKind::Dynamic),
+            ]
+        );
+        let foo = registry
+            .anchors()
+            .find(|anchor| anchor.name() == "foo")
+            .expect("Anchor exists");
+        assert_eq!(foo.contents(), &json!({"$anchor": "foo", "type": "string"}));
+        assert_eq!(foo.draft(), Draft::Draft202012);
+    }
+
     #[test]
     fn test_unknown_anchor() {
         let schema = Draft::Draft202012.create_resource(json!({
@@ -0,0 +1,417 @@
+//! Building blocks for composing retrievers.
+use core::fmt;
+use std::{collections::HashMap, error::Error, hash::BuildHasher};
+
+use ahash::AHashMap;
+use fluent_uri::Uri;
+use serde_json::Value;
+
+use crate::Retrieve;
+
+type BoxedError = Box<dyn Error + Send + Sync>;
+
+/// Retriever that dispatches to other retrievers by URI scheme.
+///
+/// # Examples
+///
+/// ```rust
+/// use referencing::{FnRetriever, MapRetriever, SchemeRouter};
+///
+/// let retriever = SchemeRouter::new()
+///     .route("urn", MapRetriever::new())
+///     .route("file", FnRetriever::new(|uri| Err(format!("Not implemented: {uri}").into())));
+/// ```
+#[derive(Default)]
+pub struct SchemeRouter {
+    routes: AHashMap<String, Box<dyn Retrieve>>,
+    fallback: Option<Box<dyn Retrieve>>,
+}
+
+impl SchemeRouter {
+    /// Create a router without any routes.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Retrieve URIs with the given scheme with `retriever`.
+    ///
+    /// Replaces an earlier route for the same scheme. Schemes are case-insensitive.
+    #[must_use]
+    pub fn route(mut self, scheme: impl Into<String>, retriever: impl Retrieve + 'static) -> Self {
+        self.routes
+            .insert(scheme.into().to_ascii_lowercase(), Box::new(retriever));
+        self
+    }
+    /// Retrieve URIs with schemes that have no route with `retriever`.
+    #[must_use]
+    pub fn fallback(mut self, retriever: impl Retrieve + 'static) -> Self {
+        self.fallback = Some(Box::new(retriever));
+        self
+    }
+}
+
+impl fmt::Debug for SchemeRouter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut schemes: Vec<_> = self.routes.keys().collect();
+        schemes.sort();
+        f.debug_struct("SchemeRouter")
+            .field("schemes", &schemes)
+            .field("fallback", &self.fallback.is_some())
+            .finish()
+    }
+}
+
+impl Retrieve for SchemeRouter {
+    fn retrieve(&self, uri: &Uri<&str>) -> Result<Value, BoxedError> {
+        let scheme = uri.scheme().as_str().to_ascii_lowercase();
+        match self.routes.get(&scheme).or(self.fallback.as_ref()) {
+            Some(retriever) => retriever.retrieve(uri),
+            None => Err(format!("No retriever for the '{scheme}' scheme").into()),
+        }
+    }
+}
+
+/// Retriever that tries another retriever if the first one fails.
+///
+/// If both fail, the error is a [`AttemptsError`] with the errors of all attempts in order.
+/// Nested fallback retrievers contribute each of their attempts.
+///
+/// # Examples
+///
+/// ```rust
+/// use referencing::{FallbackRetriever, MapRetriever};
+///
+/// let retriever = FallbackRetriever::new(MapRetriever::new(), MapRetriever::new());
+/// ```
+#[derive(Debug, Clone)]
+pub struct FallbackRetriever<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A: Retrieve, B: Retrieve> FallbackRetriever<A, B> {
+    /// Create a retriever that tries `first` and then `second`.
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+}
+
+impl<A: Retrieve, B: Retrieve> Retrieve for FallbackRetriever<A, B> {
+    fn retrieve(&self, uri: &Uri<&str>) -> Result<Value, BoxedError> {
+        let first = match self.first.retrieve(uri) {
+            Ok(value) => return Ok(value),
+            Err(error) => error,
+        };
+        let second = match self.second.retrieve(uri) {
+            Ok(value) => return Ok(value),
+            Err(error) => error,
+        };
+        let mut attempts = Vec::with_capacity(2);
+        for error in [first, second] {
+            match error.downcast::<AttemptsError>() {
+                Ok(nested) => attempts.extend(nested.attempts),
+                Err(error) => attempts.push(error),
+            }
+        }
+        Err(Box::new(AttemptsError { attempts }))
+    }
+}
+
+/// All retrievers of a [`FallbackRetriever`] failed.
+#[derive(Debug)]
+pub struct AttemptsError {
+    attempts: Vec<BoxedError>,
+}
+
+impl AttemptsError {
+    /// Errors of the attempts in the order they were made.
+    #[must_use]
+    pub fn attempts(&self) -> &[BoxedError] {
+        &self.attempts
+    }
+}
+
+impl fmt::Display for AttemptsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_fmt(format_args!(
+            "All {} retrieval attempts failed",
+            self.attempts.len()
+        ))?;
+        for (idx, error) in self.attempts.iter().enumerate() {
+            f.write_fmt(format_args!(
+                "{} {}. {error}",
+                if idx == 0 { ":" } else { ";" },
+                idx + 1
+            ))?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for AttemptsError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.attempts
+            .last()
+            .map(|error| &**error as &(dyn Error + 'static))
+    }
+}
+
+/// Retriever that serves resources from memory.
+///
+/// Resources are looked up by their URI without a fragment.
+///
+/// # Examples
+///
+/// ```rust
+/// use referencing::{uri, MapRetriever};
+/// use serde_json::json;
+///
+/// # fn main() -> Result<(), referencing::Error> {
+/// let retriever = MapRetriever::new()
+///     .with_resource(uri::from_str("urn:example:person")?, json!({"type": "object"}));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MapRetriever {
+    resources: AHashMap<String, Value>,
+}
+
+impl MapRetriever {
+    /// Create a retriever without any resources.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Add a resource, replacing an earlier one with the same URI.
+    pub fn insert(&mut self, mut uri: Uri<String>, contents: Value) -> Option<Value> {
+        uri.set_fragment(None);
+        self.resources.insert(uri.into(), contents)
+    }
+    /// Add a resource, replacing an earlier one with the same URI.
+    #[must_use]
+    pub fn with_resource(mut self, uri: Uri<String>, contents: Value) -> Self {
+        self.insert(uri, contents);
+        self
+    }
+}
+
+impl Retrieve for MapRetriever {
+    fn retrieve(&self, uri: &Uri<&str>) -> Result<Value, BoxedError> {
+        self.resources
+            .get(without_fragment(uri.as_str()))
+            .cloned()
+            .ok_or_else(|| format!("Resource '{uri}' is not in the map").into())
+    }
+}
+
+impl FromIterator<(Uri<String>, Value)> for MapRetriever {
+    fn from_iter<I: IntoIterator<Item = (Uri<String>, Value)>>(iter: I) -> Self {
+        let mut retriever = MapRetriever::new();
+        for (uri, contents) in iter {
+            retriever.insert(uri, contents);
+        }
+        retriever
+    }
+}
+
+impl<S: BuildHasher> From<HashMap<Uri<String>, Value, S>> for MapRetriever {
+    fn from(resources: HashMap<Uri<String>, Value, S>) -> Self {
+        resources.into_iter().collect()
+    }
+}
+
+fn without_fragment(uri: &str) -> &str {
+    uri.split_once('#').map_or(uri, |(uri, _)| uri)
+}
+
+/// Retriever that calls a closure.
+///
+/// # Examples
+///
+/// ```rust
+/// use referencing::FnRetriever;
+/// use serde_json::json;
+///
+/// let retriever = FnRetriever::new(|uri| match uri.as_str() {
+///     "urn:example:string" => Ok(json!({"type": "string"})),
+///     _ => Err(format!("Unknown resource: {uri}").into()),
+/// });
+/// ```
+#[derive(Clone)]
+pub struct FnRetriever<F> {
+    function: F,
+}
+
+impl<F> FnRetriever<F>
+where
+    F: Fn(&Uri<&str>) -> Result<Value, BoxedError> + Send + Sync,
+{
+    /// Create a retriever that calls `function` with the URI of each resource.
+    pub fn new(function: F) -> Self {
+        Self { function }
+    }
+}
+
+impl<F> fmt::Debug for FnRetriever<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FnRetriever").finish_non_exhaustive()
+    }
+}
+
+impl<F> Retrieve for FnRetriever<F>
+where
+    F: Fn(&Uri<&str>) -> Result<Value, BoxedError> + Send + Sync,
+{
+    fn retrieve(&self, uri: &Uri<&str>) -> Result<Value, BoxedError> {
+        (self.function)(uri)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    use super::{AttemptsError, FallbackRetriever, FnRetriever, MapRetriever, SchemeRouter};
+    use crate::{uri, Draft, Registry, Retrieve};
+
+    fn retrieve(retriever: &impl Retrieve, uri: &str) -> Result<Value, String> {
+        let uri = uri::from_str(uri).expect("Invalid URI");
+        retriever
+            .retrieve(&uri.borrow())
+            .map_err(|error| error.to_string())
+    }
+
+    fn map(resources: &[(&str, Value)]) -> MapRetriever {
+        resources
+            .iter()
+            .map(|(uri, contents)| (uri::from_str(uri).expect("Invalid URI"), contents.clone()))
+            .collect()
+    }
+
+    fn failing(message: &'static str) -> impl Retrieve + Clone {
+        FnRetriever::new(move |_| Err(message.into()))
+    }
+
+    #[test_case("urn:example:a", &Ok(json!({"const": "urn"})))]
+    #[test_case("URN:example:a", &Ok(json!({"const": "urn"})); "case insensitive")]
+    #[test_case("https://example.com/a", &Ok(json!({"const": "https"})))]
+    #[test_case("ftp://example.com/a", &Err("No retriever for the 'ftp' scheme".to_string()))]
+    fn scheme_router(uri: &str, expected: &Result<Value, String>) {
+        let router = SchemeRouter::new()
+            .route("urn", FnRetriever::new(|_| Ok(json!({"const": "urn"}))))
+            .route("https", FnRetriever::new(|_| Ok(json!({"const": "https"}))));
+        assert_eq!(&retrieve(&router, uri), expected);
+    }
+
+    #[test]
+    fn scheme_router_fallback() {
+        let router = SchemeRouter::new()
+            .route("urn", failing("Not found"))
+            .fallback(FnRetriever::new(|_| Ok(json!({"const": "fallback"}))));
+        assert_eq!(
+            retrieve(&router, "ftp://example.com/a"),
+            Ok(json!({"const": "fallback"}))
+        );
+        assert_eq!(
+            retrieve(&router, "urn:example:a"),
+            Err("Not found".to_string())
+        );
+    }
+
+    #[test]
+    fn fallback() {
+        let retriever = FallbackRetriever::new(
+            map(&[("https://example.com/a", json!({"const": "first"}))]),
+            map(&[
+                ("https://example.com/a", json!({"const": "second"})),
+                ("https://example.com/b", json!({"const": "second"})),
+            ]),
+        );
+        assert_eq!(
+            retrieve(&retriever, "https://example.com/a"),
+            Ok(json!({"const": "first"}))
+        );
+        assert_eq!(
+            retrieve(&retriever, "https://example.com/b"),
+            Ok(json!({"const": "second"}))
+        );
+    }
+
+    #[test]
+    fn fallback_attempts() {
+        let retriever = FallbackRetriever::new(
+            failing("Connection refused"),
+            FallbackRetriever::new(failing("Mirror is down"), map(&[])),
+        );
+        let uri = uri::from_str("https://example.com/a").expect("Invalid URI");
+        let error = retriever.retrieve(&uri.borrow()).expect_err("Should fail");
+        assert_eq!(
+            error.to_string(),
+            "All 3 retrieval attempts failed: 1. Connection refused; 2. Mirror is down; 3. Resource 'https://example.com/a' is not in the map"
+        );
+        let attempts = error
+            .downcast_ref::<AttemptsError>()
+            .expect("Should be an AttemptsError")
+            .attempts();
+        assert_eq!(attempts.len(), 3);
+        assert_eq!(attempts[1].to_string(), "Mirror is down");
+    }
+
+    #[test]
+    fn map_retriever() {
+        let mut resources = HashMap::new();
+        resources.insert(
+            uri::from_str("urn:example:a").expect("Invalid URI"),
+            json!({"type": "string"}),
+        );
+        let mut retriever = MapRetriever::from(resources);
+        assert_eq!(
+            retriever.insert(
+                uri::from_str("https://example.com/b#").expect("Invalid URI"),
+                json!({"type": "integer"})
+            ),
+            None
+        );
+        assert_eq!(
+            retrieve(&retriever, "urn:example:a"),
+            Ok(json!({"type": "string"}))
+        );
+        assert_eq!(
+            retrieve(&retriever, "https://example.com/b"),
+            Ok(json!({"type": "integer"}))
+        );
+        assert_eq!(
+            retrieve(&retriever, "urn:example:missing"),
+            Err("Resource 'urn:example:missing' is not in the map".to_string())
+        );
+    }
+
+    #[test]
+    fn registry() {
+        let retriever = SchemeRouter::new()
+            .route(
+                "https",
+                FallbackRetriever::new(
+                    failing("Connection refused"),
+                    map(&[("https://example.com/b", json!({"$ref": "urn:example:c"}))]),
+                ),
+            )
+            .route("urn", map(&[("urn:example:c", json!({"type": "string"}))]));
+        let registry = Registry::options()
+            .retriever(Box::new(retriever))
+            .try_new(
+                "https://example.com/a",
+                Draft::Draft202012.create_resource(json!({"$ref": "b"})),
+            )
+            .expect("Invalid resources");
+        let resolver = registry
+            .try_resolver("urn:example:c")
+            .expect("Invalid base URI");
+        let resolved = resolver.lookup("").expect("Lookup failed");
+        assert_eq!(resolved.contents(), &json!({"type": "string"}));
+    }
+}
@@ -0,0 +1,384 @@
+//! Loading resources from a directory tree.
+use core::fmt;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use ahash::AHashMap;
+use fluent_uri::Uri;
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use serde_json::Value;
+
+use crate::{uri, Draft, Error, Resource};
+
+/// Characters that are percent-encoded in the path of a `file:` URI.
+const PATH: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'[')
+    .add(b'\\')
+    .add(b']')
+    .add(b'^')
+    .add(b'`')
+    .add(b'{')
+    .add(b'|')
+    .add(b'}');
+
+/// Find all `.json` files under `root` and turn them into resources under their `file:` URIs.
+///
+/// All problems are collected rather than stopping at the first one.
+pub(crate) fn load(
+    root: &Path,
+    default_draft: Draft,
+) -> Result<Vec<(Uri<String>, Resource)>, DirectoryError> {
+    let mut errors = Vec::new();
+    let mut paths = Vec::new();
+    match fs::canonicalize(root) {
+        Ok(root) => collect_files(root, &mut paths, &mut errors),
+        Err(source) => errors.push(LoadError::Io {
+            path: root.to_path_buf(),
+            source,
+        }),
+    }
+
+    let mut resources = Vec::with_capacity(paths.len());
+    // Resolved `$id` -> the file that declared it first
+    let mut ids: AHashMap<Uri<String>, usize> = AHashMap::new();
+    for path in paths {
+        let contents = match fs::read(&path) {
+            Ok(contents) => contents,
+            Err(source) => {
+                errors.push(LoadError::Io { path, source });
+                continue;
+            }
+        };
+        let contents: Value = match serde_json::from_slice(&contents) {
+            Ok(contents) => contents,
+            Err(source) => {
+                errors.push(LoadError::Json { path, source });
+                continue;
+            }
+        };
+        let loaded = uri::from_str(&file_uri(&path)).and_then(|file_uri| {
+            let resource = Resource::from_contents_and_specification(contents, default_draft)?;
+            let id = resource
+                .id()
+                .map(|id| uri::resolve_against(&file_uri.borrow(), id))
+                .transpose()?;
+            Ok((file_uri, resource, id))
+        });
+        let (file_uri, resource, id) = match loaded {
+            Ok(loaded) => loaded,
+            Err(source) => {
+                errors.push(LoadError::Resource { path, source });
+                continue;
+            }
+        };
+        if let Some(mut id) = id {
+            id.set_fragment(None);
+            if let Some(&first) = ids.get(&id) {
+                let (first, _): &(PathBuf, _) = &resources[first];
+                errors.push(LoadError::DuplicateId {
+                    id: id.to_string(),
+                    first: first.clone(),
+                    second: path,
+                });
+                continue;
+            }
+            ids.insert(id, resources.len());
+        }
+        resources.push((path, (file_uri, resource)));
+    }
+
+    if errors.is_empty() {
+        Ok(resources.into_iter().map(|(_, pair)| pair).collect())
+    } else {
+        Err(DirectoryError { errors })
+    }
+}
+
+/// Collect paths of all `.json` files under `directory` in a stable order.
+///
+/// Symbolic links to directories are not followed, so there are no cycles.
+fn collect_files(directory: PathBuf, paths: &mut Vec<PathBuf>, errors: &mut Vec<LoadError>) {
+    let entries = match fs::read_dir(&directory).and_then(Iterator::collect::<io::Result<Vec<_>>>) {
+        Ok(entries) => entries,
+        Err(source) => {
+            errors.push(LoadError::Io {
+                path: directory,
+                source,
+            });
+            return;
+        }
+    };
+    let mut entries: Vec<_> = entries.into_iter().map(|entry| entry.path()).collect();
+    entries.sort();
+    for path in entries {
+        match fs::symlink_metadata(&path) {
+            Ok(metadata) if metadata.is_dir() => collect_files(path, paths, errors),
+            Ok(_) => {
+                let is_json = path
+                    .extension()
+                    .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+                if is_json && path.is_file() {
+                    paths.push(path);
+                }
+            }
+            Err(source) => errors.push(LoadError::Io { path, source }),
+        }
+    }
+}
+
+/// The `file:` URI of an absolute path.
+///
+/// On Windows, `fs::canonicalize` returns verbatim paths, like `\\?\C:\dir` or
+/// `\\?\UNC\server\share`, which are turned into `C:\dir` and `\\server\share` first.
+fn file_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let path = if let Some(unc) = path.strip_prefix("//?/UNC/") {
+        format!("//{unc}")
+    } else if let Some(local) = path.strip_prefix("//?/") {
+        local.to_string()
+    } else {
+        path
+    };
+    // UNC paths already start with the authority
+    let scheme = if path.starts_with("//") {
+        "file:"
+    } else if path.starts_with('/') {
+        "file://"
+    } else {
+        "file:///"
+    };
+    format!("{scheme}{}", utf8_percent_encode(&path, PATH))
+}
+
+/// Problems found while loading resources from a directory.
+#[derive(Debug)]
+pub struct DirectoryError {
+    errors: Vec<LoadError>,
+}
+
+impl DirectoryError {
+    pub(crate) fn registry(error: Error) -> Self {
+        DirectoryError {
+            errors: vec![LoadError::Registry(error)],
+        }
+    }
+    /// All problems, in the order of the files they were found in.
+    #[must_use]
+    pub fn errors(&self) -> &[LoadError] {
+        &self.errors
+    }
+}
+
+impl fmt::Display for DirectoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_fmt(format_args!(
+            "Failed to load resources from a directory ({} errors)",
+            self.errors.len()
+        ))?;
+        for error in &self.errors {
+            f.write_fmt(format_args!("\n  - {error}"))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for DirectoryError {}
+
+/// A problem with a single file or directory, or with the loaded resources as a whole.
+#[derive(Debug)]
+pub enum LoadError {
+    /// A file or directory could not be read.
+    Io { path: PathBuf, source: io::Error },
+    /// A file is not valid JSON.
+    Json {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    /// A file is not a valid resource, e.g. because its `$id` is not a valid URI.
+    Resource { path: PathBuf, source: Error },
+    /// Two files declare the same `$id`.
+    DuplicateId {
+        id: String,
+        first: PathBuf,
+        second: PathBuf,
+    },
+    /// Building a registry from the loaded resources failed.
+    Registry(Error),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Io { path, source } => f.write_fmt(format_args!(
+                "Failed to read '{}': {source}",
+                path.display()
+            )),
+            LoadError::Json { path, source } => f.write_fmt(format_args!(
+                "Failed to parse '{}': {source}",
+                path.display()
+            )),
+            LoadError::Resource { path, source } => f.write_fmt(format_args!(
+                "Invalid resource in '{}': {source}",
+                path.display()
+            )),
+            LoadError::DuplicateId { id, first, second } => f.write_fmt(format_args!(
+                "Duplicate ID '{id}' in '{}' and '{}'",
+                first.display(),
+                second.display()
+            )),
+            LoadError::Registry(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoadError::Io { source, .. } => Some(source),
+            LoadError::Json { source, .. } => Some(source),
+            LoadError::Resource { source, .. } | LoadError::Registry(source) => Some(source),
+            LoadError::DuplicateId { .. } => None,
+        }
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use std::{fs, path::Path};
+
+    use serde_json::json;
+    use test_case::test_case;
+
+    use super::{file_uri, LoadError};
+    use crate::{Registry, Retrieve};
+
+    fn write(root: &Path, path: &str, contents: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().expect("Has a parent")).expect("Failed to create");
+        fs::write(path, contents).expect("Failed to write");
+    }
+
+    #[test]
+    fn load() {
+        let directory = tempfile::tempdir().expect("Failed to create a directory");
+        let root = directory.path();
+        write(
+            root,
+            "person.json",
+            r#"{"$id": "https://example.com/person.json", "properties": {"address": {"$ref": "common/address.json"}}}"#,
+        );
+        write(
+            root,
+            "nested/address.json",
+            r#"{"$id": "https://example.com/common/address.json", "type": "object"}"#,
+        );
+        write(root, "nested/deeper/no id.json", r#"{"type": "string"}"#);
+        write(root, "notes.txt", "not a schema");
+
+        let registry = Registry::try_from_directory(root).expect("Invalid directory");
+        let resolver = registry
+            .try_resolver("https://example.com/person.json")
+            .expect("Invalid base URI");
+        let resolved = resolver
+            .lookup("common/address.json")
+            .expect("Lookup failed");
+        assert_eq!(
+            resolved.contents(),
+            &json!({"$id": "https://example.com/common/address.json", "type": "object"})
+        );
+
+        // Every file is registered under its `file:` URI too
+        let root = fs::canonicalize(root).expect("Failed to canonicalize");
+        let uri = file_uri(&root.join("nested").join("deeper").join("no id.json"));
+        #[cfg(unix)]
+        assert_eq!(
+            uri,
+            format!("file://{}/nested/deeper/no%20id.json", root.display())
+        );
+        let resolver = registry.try_resolver(&uri).expect("Invalid base URI");
+        let resolved = resolver.lookup("").expect("Lookup failed");
+        assert_eq!(resolved.contents(), &json!({"type": "string"}));
+    }
+
+    #[test_case("/home/user/a b.json", "file:///home/user/a%20b.json"; "unix")]
+    #[test_case(r"C:\dir\a b.json", "file:///C:/dir/a%20b.json"; "windows")]
+    #[test_case(r"\\?\C:\dir\a.json", "file:///C:/dir/a.json"; "windows verbatim")]
+    #[test_case(r"\\server\share\a.json", "file://server/share/a.json"; "unc")]
+    #[test_case(r"\\?\UNC\server\share\a.json", "file://server/share/a.json"; "unc verbatim")]
+    fn file_uris(path: &str, expected: &str) {
+        assert_eq!(file_uri(Path::new(path)), expected);
+    }
+
+    #[test]
+    fn errors() {
+        let directory = tempfile::tempdir().expect("Failed to create a directory");
+        let root = directory.path();
+        write(root, "a.json", r#"{"$id": "https://example.com/a.json"}"#);
+        write(
+            root,
+            "b/a.json",
+            r#"{"$id": "https://example.com/a.json#"}"#,
+        );
+        write(root, "c.json", r#"{"type": "#);
+        write(root, "d.json", r#"{"$id": "http://a b"}"#);
+
+        let error = Registry::try_from_directory(root).expect_err("Should fail");
+        let errors = error.errors();
+        assert_eq!(errors.len(), 3, "{error}");
+        assert!(matches!(
+            &errors[0],
+            LoadError::DuplicateId { id, first, second }
+                if id == "https://example.com/a.json"
+                    && first.ends_with("a.json")
+                    && second.ends_with(Path::new("b").join("a.json"))
+        ));
+        assert!(matches!(&errors[1], LoadError::Json { path, .. } if path.ends_with("c.json")));
+        assert!(matches!(&errors[2], LoadError::Resource { path, .. } if path.ends_with("d.json")));
+        assert!(error
+            .to_string()
+            .starts_with("Failed to load resources from a directory (3 errors)\n  - Duplicate ID"));
+    }
+
+    #[test]
+    fn unretrievable() {
+        struct Unreachable;
+
+        impl Retrieve for Unreachable {
+            fn retrieve(
+                &self,
+                uri: &crate::Uri<&str>,
+            ) -> Result<serde_json::Value, Box<dyn std::error::Error + Send + Sync>> {
+                Err(format!("Failed to retrieve {uri}").into())
+            }
+        }
+
+        let directory = tempfile::tempdir().expect("Failed to create a directory");
+        write(
+            directory.path(),
+            "a.json",
+            r#"{"$ref": "https://example.com/missing.json"}"#,
+        );
+        let error = Registry::options()
+            .retriever(Box::new(Unreachable))
+            .try_from_directory(directory.path())
+            .expect_err("Should fail");
+        assert!(matches!(error.errors(), [LoadError::Registry(_)]));
+    }
+
+    #[test]
+    fn missing_directory() {
+        let directory = tempfile::tempdir().expect("Failed to create a directory");
+        let error = Registry::try_from_directory(directory.path().join("missing"))
+            .expect_err("Should fail");
+        assert!(matches!(error.errors(), [LoadError::Io { .. }]));
+    }
+}
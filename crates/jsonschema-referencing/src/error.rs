@@ -6,6 +6,8 @@ use fluent_uri::{
     Uri,
 };
 
+use crate::PolicyViolation;
+
 /// Errors that can occur during reference resolution and resource handling.
 #[derive(Debug)]
 pub enum Error {
@@ -14,6 +16,11 @@ pub enum Error {
         uri: String,
         source: Box<dyn std::error::Error + Send + Sync>,
     },
+    /// Retrieving a resource is not allowed by the [`crate::RetrievalPolicy`] in use.
+    PolicyViolation {
+        uri: String,
+        violation: PolicyViolation,
+    },
     /// A JSON Pointer leads to a part of a document that does not exist.
     PointerToNowhere { pointer: String },
     /// JSON Pointer contains invalid percent-encoded data.
@@ -83,6 +90,13 @@ impl Error {
         }
     }
 
+    pub(crate) fn policy_violation(uri: impl Into<String>, violation: PolicyViolation) -> Error {
+        Error::PolicyViolation {
+            uri: uri.into(),
+            violation,
+        }
+    }
+
     pub(crate) fn uri_parsing_error(uri: impl Into<String>, error: ParseError) -> Error {
         Error::InvalidUri(UriError::Parse {
             uri: uri.into(),
@@ -118,6 +132,9 @@ impl fmt::Display for Error {
             Error::Unretrievable { uri, source } => {
                 f.write_fmt(format_args!("Resource '{uri}' is not present in a registry and retrieving it failed: {source}"))
             },
+            Error::PolicyViolation { uri, violation } => {
+                f.write_fmt(format_args!("Retrieving '{uri}' is not allowed by the retrieval policy: {violation}"))
+            }
             Error::PointerToNowhere { pointer } => {
                 f.write_fmt(format_args!("Pointer '{pointer}' does not exist"))
             }
@@ -145,6 +162,7 @@ impl std::error::Error for Error {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
             Error::Unretrievable { source, .. } => Some(&**source),
+            Error::PolicyViolation { violation, .. } => Some(violation),
             Error::InvalidUri(error) => Some(error),
             Error::InvalidPercentEncoding { source, .. } => Some(source),
             Error::InvalidArrayIndex { source, .. } => Some(source),
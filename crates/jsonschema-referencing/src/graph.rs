@@ -0,0 +1,558 @@
+//! References between the resources of a registry.
+use ahash::AHashMap;
+use fluent_uri::Uri;
+use serde_json::Value;
+
+use crate::{uri, Draft, Error, Registry, Resource};
+
+/// Keyword a reference is made with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReferenceKind {
+    /// `$ref`.
+    Ref,
+    /// `$dynamicRef` from Draft 2020-12.
+    DynamicRef,
+    /// `$recursiveRef` from Draft 2019-09.
+    RecursiveRef,
+}
+
+impl ReferenceKind {
+    const ALL: [ReferenceKind; 3] = [
+        ReferenceKind::Ref,
+        ReferenceKind::DynamicRef,
+        ReferenceKind::RecursiveRef,
+    ];
+
+    /// The keyword, e.g. `$ref`.
+    #[must_use]
+    pub fn keyword(self) -> &'static str {
+        match self {
+            ReferenceKind::Ref => "$ref",
+            ReferenceKind::DynamicRef => "$dynamicRef",
+            ReferenceKind::RecursiveRef => "$recursiveRef",
+        }
+    }
+
+    fn applies_to(self, draft: Draft) -> bool {
+        match self {
+            ReferenceKind::Ref => true,
+            ReferenceKind::DynamicRef => draft == Draft::Draft202012,
+            ReferenceKind::RecursiveRef => draft == Draft::Draft201909,
+        }
+    }
+}
+
+/// A reference made by one resource.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    source: Uri<String>,
+    kind: ReferenceKind,
+    value: String,
+    target: Option<Uri<String>>,
+}
+
+impl Reference {
+    /// URI of the resource the reference is made in.
+    #[must_use]
+    pub fn source(&self) -> &Uri<String> {
+        &self.source
+    }
+    /// Keyword the reference is made with.
+    #[must_use]
+    pub fn kind(&self) -> ReferenceKind {
+        self.kind
+    }
+    /// The reference as written.
+    #[must_use]
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+    /// The reference resolved against the URI of its resource, or `None` if it is not a valid
+    /// URI reference.
+    ///
+    /// For `$dynamicRef` and `$recursiveRef` this is where the reference points initially, the
+    /// actual target may change depending on the dynamic scope during validation.
+    #[must_use]
+    pub fn target(&self) -> Option<&Uri<String>> {
+        self.target.as_ref()
+    }
+}
+
+/// A reference that does not point to anything in the registry.
+#[derive(Debug)]
+pub struct UnresolvedReference {
+    reference: Reference,
+    error: Error,
+}
+
+impl UnresolvedReference {
+    /// The reference that could not be resolved.
+    #[must_use]
+    pub fn reference(&self) -> &Reference {
+        &self.reference
+    }
+    /// Why the reference could not be resolved.
+    #[must_use]
+    pub fn error(&self) -> &Error {
+        &self.error
+    }
+}
+
+/// References between the resources of a [`Registry`].
+///
+/// Each resource is a node, listed once under its `$id` if it has one. References in
+/// subschemas without an `$id` belong to the resource they are in, subschemas with an `$id`
+/// are resources of their own.
+#[derive(Debug)]
+pub struct ReferenceGraph {
+    /// Resource URIs, sorted.
+    resources: Vec<Uri<String>>,
+    references: Vec<Reference>,
+    /// Indices of the source and target resources of each reference.
+    edges: Vec<(usize, Option<usize>)>,
+    unresolved: Vec<UnresolvedReference>,
+}
+
+impl ReferenceGraph {
+    pub(crate) fn new(registry: &Registry) -> ReferenceGraph {
+        // A resource is registered under several URIs if it has an `$id`
+        let mut aliases: AHashMap<*const Resource, Vec<&Uri<String>>> = AHashMap::new();
+        for (uri, resource) in registry.resources() {
+            aliases
+                .entry(resource as *const Resource)
+                .or_default()
+                .push(uri);
+        }
+        let mut nodes: Vec<(&Uri<String>, &Resource)> = aliases
+            .values()
+            .map(|uris| {
+                let resource = registry
+                    .resource(uris[0])
+                    .expect("Resource is in the registry");
+                (canonical(uris, resource), resource)
+            })
+            .collect();
+        nodes.sort_by(|(left, _), (right, _)| left.as_str().cmp(right.as_str()));
+        let indices: AHashMap<*const Resource, usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(index, (_, resource))| (*resource as *const Resource, index))
+            .collect();
+
+        let mut references = Vec::new();
+        let mut edges = Vec::new();
+        let mut unresolved = Vec::new();
+        let mut found = Vec::new();
+        for (source, (uri, resource)) in nodes.iter().enumerate() {
+            collect_references(resource.contents(), resource.draft(), &mut found);
+            let resolver = registry.resolver((*uri).clone());
+            for (kind, value) in found.drain(..) {
+                let (target, error) = match uri::resolve_against(&uri.borrow(), value) {
+                    Ok(target) => (Some(target), resolver.lookup(value).err()),
+                    Err(error) => (None, Some(error)),
+                };
+                let target_index = target.as_ref().and_then(|target| {
+                    let mut fragmentless = target.clone();
+                    fragmentless.set_fragment(None);
+                    let resource = registry.resource(&fragmentless)?;
+                    indices.get(&(resource as *const Resource)).copied()
+                });
+                let reference = Reference {
+                    source: (*uri).clone(),
+                    kind,
+                    value: value.to_string(),
+                    target,
+                };
+                if let Some(error) = error {
+                    unresolved.push(UnresolvedReference {
+                        reference: reference.clone(),
+                        error,
+                    });
+                }
+                references.push(reference);
+                edges.push((source, target_index));
+            }
+        }
+        ReferenceGraph {
+            resources: nodes.into_iter().map(|(uri, _)| uri.clone()).collect(),
+            references,
+            edges,
+            unresolved,
+        }
+    }
+    /// URIs of all resources, sorted.
+    #[must_use]
+    pub fn resources(&self) -> &[Uri<String>] {
+        &self.resources
+    }
+    /// All references, grouped by the resource they are made in.
+    #[must_use]
+    pub fn references(&self) -> &[Reference] {
+        &self.references
+    }
+    /// References made in the resource with the given URI.
+    pub fn references_from<'g>(
+        &'g self,
+        uri: &Uri<String>,
+    ) -> impl Iterator<Item = &'g Reference> + 'g {
+        let index = self.index_of(uri);
+        self.edges
+            .iter()
+            .zip(&self.references)
+            .filter(move |((source, _), _)| Some(*source) == index)
+            .map(|(_, reference)| reference)
+    }
+    /// References pointing into the resource with the given URI.
+    pub fn references_to<'g>(
+        &'g self,
+        uri: &Uri<String>,
+    ) -> impl Iterator<Item = &'g Reference> + 'g {
+        let index = self.index_of(uri);
+        self.edges
+            .iter()
+            .zip(&self.references)
+            .filter(move |((_, target), _)| index.is_some() && *target == index)
+            .map(|(_, reference)| reference)
+    }
+    /// References that don't point to any resource, anchor or location in the registry.
+    #[must_use]
+    pub fn unresolved(&self) -> &[UnresolvedReference] {
+        &self.unresolved
+    }
+    /// Resources that are not referenced by any other resource.
+    pub fn unreferenced(&self) -> impl Iterator<Item = &Uri<String>> {
+        let mut referenced = vec![false; self.resources.len()];
+        for (source, target) in &self.edges {
+            if let Some(target) = *target {
+                if target != *source {
+                    referenced[target] = true;
+                }
+            }
+        }
+        self.resources
+            .iter()
+            .zip(referenced)
+            .filter(|(_, referenced)| !referenced)
+            .map(|(uri, _)| uri)
+    }
+    /// Groups of resources that reference each other, directly or indirectly.
+    ///
+    /// References within a single resource are not considered cycles. Each group and the list of
+    /// groups are sorted.
+    #[must_use]
+    pub fn cycles(&self) -> Vec<Vec<&Uri<String>>> {
+        let mut successors = vec![Vec::new(); self.resources.len()];
+        for (source, target) in &self.edges {
+            if let Some(target) = *target {
+                if target != *source {
+                    successors[*source].push(target);
+                }
+            }
+        }
+        let mut components = StronglyConnected::new(&successors).find();
+        components.retain(|component| component.len() > 1);
+        for component in &mut components {
+            component.sort_unstable();
+        }
+        components.sort_unstable();
+        components
+            .into_iter()
+            .map(|component| {
+                component
+                    .into_iter()
+                    .map(|index| &self.resources[index])
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn index_of(&self, uri: &Uri<String>) -> Option<usize> {
+        self.resources
+            .binary_search_by(|probe| probe.as_str().cmp(uri.as_str()))
+            .ok()
+    }
+}
+
+/// The URI references in a resource are resolved against, out of all URIs it is registered under.
+fn canonical<'a>(uris: &[&'a Uri<String>], resource: &Resource) -> &'a Uri<String> {
+    if let (Some(id), [_, _, ..]) = (resource.id(), uris) {
+        for uri in uris {
+            if let Ok(resolved) = uri::resolve_against(&uri.borrow(), id) {
+                if let Some(found) = uris.iter().find(|uri| **uri == &resolved) {
+                    return found;
+                }
+            }
+        }
+    }
+    uris.iter()
+        .min_by(|left, right| left.as_str().cmp(right.as_str()))
+        .expect("At least one URI")
+}
+
+/// Collect references in `contents` and its subschemas, except for subresources with an `$id`.
+fn collect_references<'a>(
+    contents: &'a Value,
+    draft: Draft,
+    found: &mut Vec<(ReferenceKind, &'a str)>,
+) {
+    if let Some(object) = contents.as_object() {
+        for kind in ReferenceKind::ALL {
+            if !kind.applies_to(draft) {
+                continue;
+            }
+            if let Some(reference) = object.get(kind.keyword()).and_then(Value::as_str) {
+                found.push((kind, reference));
+            }
+        }
+    }
+    for subresource in draft.subresources_of(contents) {
+        let draft = draft.detect(subresource).unwrap_or(draft);
+        if draft.id_of(subresource).is_none() {
+            collect_references(subresource, draft, found);
+        }
+    }
+}
+
+/// Tarjan's algorithm for finding strongly connected components.
+struct StronglyConnected<'a> {
+    successors: &'a [Vec<usize>],
+    index: Vec<Option<usize>>,
+    lowlink: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    next: usize,
+    components: Vec<Vec<usize>>,
+}
+
+impl<'a> StronglyConnected<'a> {
+    fn new(successors: &'a [Vec<usize>]) -> Self {
+        let count = successors.len();
+        StronglyConnected {
+            successors,
+            index: vec![None; count],
+            lowlink: vec![0; count],
+            on_stack: vec![false; count],
+            stack: Vec::new(),
+            next: 0,
+            components: Vec::new(),
+        }
+    }
+
+    fn find(mut self) -> Vec<Vec<usize>> {
+        for node in 0..self.successors.len() {
+            if self.index[node].is_none() {
+                self.visit(node);
+            }
+        }
+        self.components
+    }
+
+    fn visit(&mut self, node: usize) {
+        self.index[node] = Some(self.next);
+        self.lowlink[node] = self.next;
+        self.next += 1;
+        self.stack.push(node);
+        self.on_stack[node] = true;
+        for &successor in &self.successors[node] {
+            match self.index[successor] {
+                None => {
+                    self.visit(successor);
+                    self.lowlink[node] = self.lowlink[node].min(self.lowlink[successor]);
+                }
+                Some(index) if self.on_stack[successor] => {
+                    self.lowlink[node] = self.lowlink[node].min(index);
+                }
+                Some(_) => {}
+            }
+        }
+        if Some(self.lowlink[node]) == self.index[node] {
+            let mut component = Vec::new();
+            while let Some(member) = self.stack.pop() {
+                self.on_stack[member] = false;
+                component.push(member);
+                if member == node {
+                    break;
+                }
+            }
+            self.components.push(component);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use fluent_uri::Uri;
+
+    use super::ReferenceKind;
+    use crate::{Draft, Registry, Resource};
+
+    fn strings(uris: Vec<&Uri<String>>) -> Vec<&str> {
+        uris.into_iter().map(Uri::as_str).collect()
+    }
+
+    #[test]
+    fn reference_graph() {
+        let registry = Registry::try_from_resources(
+            [
+                (
+                    "http://example.com/a",
+                    json!({
+                        "$id": "http://example.com/a",
+                        "$defs": {
+                            "b": {"$ref": "b"},
+                            "missing": {"$ref": "#/$defs/nope"},
+                            "self": {"$ref": "#/$defs/b"}
+                        }
+                    }),
+                ),
+                ("http://example.com/b", json!({"$ref": "c#name"})),
+                (
+                    "http://example.com/c",
+                    json!({"$defs": {"x": {"$anchor": "name", "$ref": "a"}}}),
+                ),
+                (
+                    "http://example.com/d",
+                    json!({
+                        "$dynamicAnchor": "meta",
+                        "$dynamicRef": "#meta",
+                        "$defs": {"nested": {"$id": "nested", "$ref": "d"}}
+                    }),
+                ),
+                (
+                    "http://example.com/e",
+                    json!({
+                        "$schema": "http://json-schema.org/draft-07/schema#",
+                        "properties": {"$ref": {"$dynamicRef": "#meta"}},
+                        "enum": [{"$ref": "missing"}]
+                    }),
+                ),
+                (
+                    "http://example.com/f",
+                    json!({
+                        "$schema": "https://json-schema.org/draft/2019-09/schema",
+                        "$recursiveAnchor": true,
+                        "$recursiveRef": "#"
+                    }),
+                ),
+            ]
+            .into_iter()
+            .map(|(uri, contents)| {
+                (
+                    uri,
+                    Resource::from_contents(contents).expect("Invalid resource"),
+                )
+            }),
+        )
+        .expect("Invalid resources");
+        let graph = registry.reference_graph();
+
+        let uri = |uri: &str| crate::uri::from_str(uri).expect("Invalid URI");
+        assert_eq!(
+            strings(graph.resources().iter().collect()),
+            [
+                "http://example.com/a",
+                "http://example.com/b",
+                "http://example.com/c",
+                "http://example.com/d",
+                "http://example.com/e",
+                "http://example.com/f",
+                "http://example.com/nested",
+            ]
+        );
+        // Nothing in keyword values or unknown to the draft counts as a reference
+        assert_eq!(graph.references().len(), 8);
+        assert_eq!(
+            graph.references_from(&uri("http://example.com/e")).count(),
+            0
+        );
+
+        let mut to_a: Vec<_> = graph
+            .references_to(&uri("http://example.com/a"))
+            .map(|reference| (reference.source().as_str(), reference.value()))
+            .collect();
+        to_a.sort_unstable();
+        assert_eq!(
+            to_a,
+            [
+                ("http://example.com/a", "#/$defs/b"),
+                ("http://example.com/a", "#/$defs/nope"),
+                ("http://example.com/c", "a"),
+            ]
+        );
+
+        let dynamic: Vec<_> = graph
+            .references_from(&uri("http://example.com/d"))
+            .map(|reference| (reference.kind(), reference.target().map(Uri::as_str)))
+            .collect();
+        assert_eq!(
+            dynamic,
+            [(ReferenceKind::DynamicRef, Some("http://example.com/d#meta"))]
+        );
+        let recursive: Vec<_> = graph
+            .references_from(&uri("http://example.com/f"))
+            .map(|reference| (reference.kind(), reference.value()))
+            .collect();
+        assert_eq!(recursive, [(ReferenceKind::RecursiveRef, "#")]);
+
+        let unresolved = graph.unresolved();
+        assert_eq!(unresolved.len(), 1);
+        assert_eq!(unresolved[0].reference().value(), "#/$defs/nope");
+        assert_eq!(
+            unresolved[0].error().to_string(),
+            "Pointer '/$defs/nope' does not exist"
+        );
+
+        let cycles: Vec<_> = graph.cycles().into_iter().map(strings).collect();
+        assert_eq!(
+            cycles,
+            [[
+                "http://example.com/a",
+                "http://example.com/b",
+                "http://example.com/c"
+            ]]
+        );
+        assert_eq!(
+            strings(graph.unreferenced().collect()),
+            [
+                "http://example.com/e",
+                "http://example.com/f",
+                "http://example.com/nested"
+            ]
+        );
+    }
+
+    #[test]
+    fn aliases() {
+        // Registered both as `http://example.com/root` and under its `$id`
+        let registry = Registry::try_new(
+            "http://example.com/root",
+            Draft::Draft202012.create_resource(json!({
+                "$id": "schemas/",
+                "$defs": {"inner": {"$id": "inner", "$ref": "#/nope"}}
+            })),
+        )
+        .expect("Invalid resources");
+        assert_eq!(registry.resources().count(), 3);
+        let graph = registry.reference_graph();
+        let resources: Vec<_> = graph.resources().iter().map(Uri::as_str).collect();
+        assert_eq!(
+            resources,
+            [
+                "http://example.com/schemas/",
+                "http://example.com/schemas/inner"
+            ]
+        );
+        let reference = graph.unresolved()[0].reference();
+        assert_eq!(
+            reference.source().as_str(),
+            "http://example.com/schemas/inner"
+        );
+        assert_eq!(
+            reference.target().map(Uri::as_str),
+            Some("http://example.com/schemas/inner#/nope")
+        );
+        assert!(graph.cycles().is_empty());
+    }
+}
@@ -2,9 +2,13 @@
 //!
 //! An implementation-agnostic JSON reference resolution library for Rust.
 mod anchors;
+mod combinators;
+mod directory;
 mod error;
+mod graph;
 mod list;
 pub mod meta;
+mod policy;
 mod registry;
 mod resolver;
 mod resource;
@@ -15,12 +19,19 @@ pub mod uri;
 mod vocabularies;
 
 pub(crate) use anchors::Anchor;
+pub use anchors::{AnchorEntry, AnchorKind};
+pub use combinators::{AttemptsError, FallbackRetriever, FnRetriever, MapRetriever, SchemeRouter};
+pub use directory::{DirectoryError, LoadError};
 pub use error::{Error, UriError};
 pub use fluent_uri::{Iri, IriRef, Uri, UriRef};
+pub use graph::{Reference, ReferenceGraph, ReferenceKind, UnresolvedReference};
 pub use list::List;
+pub use policy::{PolicyViolation, RetrievalPolicy};
 pub use registry::{Registry, RegistryOptions, SPECIFICATIONS};
 pub use resolver::{Resolved, Resolver};
 pub use resource::{Resource, ResourceRef};
+#[cfg(feature = "retrieve-async")]
+pub use retriever::AsyncRetrieve;
 pub use retriever::{DefaultRetriever, Retrieve};
 pub(crate) use segments::Segments;
 pub use specification::Draft;
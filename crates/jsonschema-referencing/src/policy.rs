@@ -0,0 +1,430 @@
+use core::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, ToSocketAddrs};
+
+use fluent_uri::Uri;
+use percent_encoding::percent_decode_str;
+
+use crate::Error;
+
+/// Restrictions on which external resources may be retrieved while building a
+/// [`crate::Registry`].
+///
+/// Every URI is checked before it is passed to the retriever, so the policy applies to any
+/// [`crate::Retrieve`] implementation. Retrievers that follow redirects have to check each
+/// target with [`RetrievalPolicy::check`] themselves. By default, nothing is restricted.
+///
+/// - Schemes, hosts and paths can be allowed or denied. If anything is allowed, everything else
+///   is denied. Denials take precedence over allowances.
+/// - Host patterns are either exact host names, or `*.` followed by a domain to match all of its
+///   subdomains. Host rules only apply to URIs with a host, so `file:` URIs have to be
+///   restricted via their scheme or path.
+/// - Path patterns are prefixes of the percent-decoded URI path. If there are any path rules,
+///   paths with `..` segments are denied.
+///
+/// # Examples
+///
+/// ```rust
+/// use referencing::{Registry, RetrievalPolicy};
+///
+/// let policy = RetrievalPolicy::new()
+///     .allow_scheme("https")
+///     .allow_host("*.example.com")
+///     .block_private_networks(true)
+///     .max_retrievals(20)
+///     .max_depth(3);
+///
+/// let registry = Registry::options().policy(policy);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RetrievalPolicy {
+    allowed_schemes: Vec<String>,
+    denied_schemes: Vec<String>,
+    allowed_hosts: Vec<String>,
+    denied_hosts: Vec<String>,
+    allowed_paths: Vec<String>,
+    denied_paths: Vec<String>,
+    block_private_networks: bool,
+    max_retrievals: Option<usize>,
+    max_depth: Option<usize>,
+}
+
+impl RetrievalPolicy {
+    /// Create a policy that doesn't restrict anything.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            allowed_schemes: Vec::new(),
+            denied_schemes: Vec::new(),
+            allowed_hosts: Vec::new(),
+            denied_hosts: Vec::new(),
+            allowed_paths: Vec::new(),
+            denied_paths: Vec::new(),
+            block_private_networks: false,
+            max_retrievals: None,
+            max_depth: None,
+        }
+    }
+    /// Allow retrieving URIs with the given scheme, e.g. `https`.
+    #[must_use]
+    pub fn allow_scheme(mut self, scheme: impl Into<String>) -> Self {
+        self.allowed_schemes
+            .push(scheme.into().to_ascii_lowercase());
+        self
+    }
+    /// Deny retrieving URIs with the given scheme, e.g. `file`.
+    #[must_use]
+    pub fn deny_scheme(mut self, scheme: impl Into<String>) -> Self {
+        self.denied_schemes.push(scheme.into().to_ascii_lowercase());
+        self
+    }
+    /// Allow retrieving URIs with a host matching the given pattern.
+    #[must_use]
+    pub fn allow_host(mut self, pattern: impl Into<String>) -> Self {
+        self.allowed_hosts.push(normalize_host(&pattern.into()));
+        self
+    }
+    /// Deny retrieving URIs with a host matching the given pattern.
+    #[must_use]
+    pub fn deny_host(mut self, pattern: impl Into<String>) -> Self {
+        self.denied_hosts.push(normalize_host(&pattern.into()));
+        self
+    }
+    /// Allow retrieving URIs with a path starting with the given prefix.
+    #[must_use]
+    pub fn allow_path(mut self, prefix: impl Into<String>) -> Self {
+        self.allowed_paths.push(prefix.into());
+        self
+    }
+    /// Deny retrieving URIs with a path starting with the given prefix.
+    #[must_use]
+    pub fn deny_path(mut self, prefix: impl Into<String>) -> Self {
+        self.denied_paths.push(prefix.into());
+        self
+    }
+    /// Deny retrieving URIs with hosts that are, or resolve to, loopback, private, link-local
+    /// or otherwise non-public IP addresses, like cloud metadata endpoints.
+    ///
+    /// Host names are resolved to check their addresses and are denied if that fails. The
+    /// retriever resolves them again, so this doesn't protect against DNS rebinding.
+    ///
+    /// Redirects, e.g. from a public host to a metadata endpoint, are only covered if the
+    /// retriever checks their targets, like the built-in retrievers of `jsonschema` and its
+    /// `HttpRetriever` do.
+    #[must_use]
+    pub fn block_private_networks(mut self, yes: bool) -> Self {
+        self.block_private_networks = yes;
+        self
+    }
+    /// Retrieve at most `limit` resources while building a registry.
+    #[must_use]
+    pub fn max_retrievals(mut self, limit: usize) -> Self {
+        self.max_retrievals = Some(limit);
+        self
+    }
+    /// Retrieve only resources that are at most `limit` references away from the resources a
+    /// registry is built from. With `0`, nothing is retrieved.
+    #[must_use]
+    pub fn max_depth(mut self, limit: usize) -> Self {
+        self.max_depth = Some(limit);
+        self
+    }
+    /// Check whether the scheme, host and path of `uri` are allowed.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first rule that `uri` violates.
+    pub fn check(&self, uri: &Uri<&str>) -> Result<(), PolicyViolation> {
+        let scheme = uri.scheme().as_str().to_ascii_lowercase();
+        if !is_allowed(&self.allowed_schemes, &self.denied_schemes, |allowed| {
+            *allowed == scheme
+        }) {
+            return Err(PolicyViolation::SchemeNotAllowed { scheme });
+        }
+        if let Some(authority) = uri.authority().filter(|a| !a.host().is_empty()) {
+            let host = normalize_host(&percent_decode_str(authority.host()).decode_utf8_lossy());
+            if !is_allowed(&self.allowed_hosts, &self.denied_hosts, |pattern| {
+                host_matches(pattern, &host)
+            }) {
+                return Err(PolicyViolation::HostNotAllowed { host });
+            }
+            if self.block_private_networks {
+                let port =
+                    authority
+                        .port_to_u16()
+                        .ok()
+                        .flatten()
+                        .unwrap_or(match scheme.as_str() {
+                            "http" => 80,
+                            "https" => 443,
+                            _ => 0,
+                        });
+                check_addresses(&host, port)?;
+            }
+        }
+        if !self.allowed_paths.is_empty() || !self.denied_paths.is_empty() {
+            let path = percent_decode_str(uri.path().as_str())
+                .decode_utf8_lossy()
+                .into_owned();
+            // Dot segments that were percent-encoded are not removed when parsing URIs
+            let escapes = path.split(['/', '\\']).any(|segment| segment == "..");
+            let allowed = !escapes
+                && is_allowed(&self.allowed_paths, &self.denied_paths, |prefix| {
+                    path.starts_with(prefix)
+                });
+            if !allowed {
+                return Err(PolicyViolation::PathNotAllowed { path });
+            }
+        }
+        Ok(())
+    }
+    /// Check whether `uri` may be retrieved as the next resource while building a registry.
+    pub(crate) fn check_retrieval(
+        &self,
+        uri: &Uri<String>,
+        depth: usize,
+        retrievals: usize,
+    ) -> Result<(), Error> {
+        let result = if let Some(limit) = self.max_depth.filter(|limit| depth > *limit) {
+            Err(PolicyViolation::TooDeep { limit })
+        } else if let Some(limit) = self.max_retrievals.filter(|limit| retrievals >= *limit) {
+            Err(PolicyViolation::TooManyRetrievals { limit })
+        } else {
+            self.check(&uri.borrow())
+        };
+        result.map_err(|violation| Error::policy_violation(uri.as_str(), violation))
+    }
+}
+
+/// A rule of a [`RetrievalPolicy`] that retrieving a resource would violate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicyViolation {
+    /// The URI scheme is not allowed.
+    SchemeNotAllowed { scheme: String },
+    /// The URI host is not allowed.
+    HostNotAllowed { host: String },
+    /// The URI path is not allowed.
+    PathNotAllowed { path: String },
+    /// The URI host is, or resolves to, a non-public IP address.
+    PrivateAddress { host: String, address: IpAddr },
+    /// The URI host couldn't be resolved to check its addresses.
+    UnresolvableHost { host: String },
+    /// Retrieving the resource would exceed the maximum number of retrievals.
+    TooManyRetrievals { limit: usize },
+    /// The resource is further away than the maximum reference depth.
+    TooDeep { limit: usize },
+}
+
+impl fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolicyViolation::SchemeNotAllowed { scheme } => {
+                f.write_fmt(format_args!("scheme '{scheme}' is not allowed"))
+            }
+            PolicyViolation::HostNotAllowed { host } => {
+                f.write_fmt(format_args!("host '{host}' is not allowed"))
+            }
+            PolicyViolation::PathNotAllowed { path } => {
+                f.write_fmt(format_args!("path '{path}' is not allowed"))
+            }
+            PolicyViolation::PrivateAddress { host, address } => f.write_fmt(format_args!(
+                "host '{host}' has the non-public address {address}"
+            )),
+            PolicyViolation::UnresolvableHost { host } => f.write_fmt(format_args!(
+                "host '{host}' could not be resolved to check its addresses"
+            )),
+            PolicyViolation::TooManyRetrievals { limit } => f.write_fmt(format_args!(
+                "more than {limit} resources would be retrieved"
+            )),
+            PolicyViolation::TooDeep { limit } => f.write_fmt(format_args!(
+                "the resource is more than {limit} references away"
+            )),
+        }
+    }
+}
+
+impl std::error::Error for PolicyViolation {}
+
+fn is_allowed(allowed: &[String], denied: &[String], matches: impl Fn(&str) -> bool) -> bool {
+    !denied.iter().any(|pattern| matches(pattern))
+        && (allowed.is_empty() || allowed.iter().any(|pattern| matches(pattern)))
+}
+
+fn normalize_host(host: &str) -> String {
+    host.trim_end_matches('.').to_ascii_lowercase()
+}
+
+fn host_matches(pattern: &str, host: &str) -> bool {
+    match pattern.strip_prefix("*.") {
+        Some(domain) => host
+            .strip_suffix(domain)
+            .is_some_and(|subdomain| subdomain.ends_with('.')),
+        None => pattern == host,
+    }
+}
+
+fn check_addresses(host: &str, port: u16) -> Result<(), PolicyViolation> {
+    let literal = host.trim_start_matches('[').trim_end_matches(']');
+    let addresses: Vec<IpAddr> = if let Ok(address) = literal.parse() {
+        vec![address]
+    } else {
+        (host, port)
+            .to_socket_addrs()
+            .map(|addresses| addresses.map(|address| address.ip()).collect())
+            .map_err(|_| PolicyViolation::UnresolvableHost {
+                host: host.to_string(),
+            })?
+    };
+    match addresses.into_iter().find(|address| !is_public(*address)) {
+        Some(address) => Err(PolicyViolation::PrivateAddress {
+            host: host.to_string(),
+            address,
+        }),
+        None => Ok(()),
+    }
+}
+
+fn is_public(address: IpAddr) -> bool {
+    match address {
+        IpAddr::V4(address) => is_public_v4(address),
+        IpAddr::V6(address) => is_public_v6(address),
+    }
+}
+
+fn is_public_v4(address: Ipv4Addr) -> bool {
+    let [a, b, c, _] = address.octets();
+    !(address.is_private()
+        || address.is_loopback()
+        || address.is_link_local()
+        || address.is_unspecified()
+        || address.is_broadcast()
+        || address.is_multicast()
+        || address.is_documentation()
+        // "This network"
+        || a == 0
+        // Shared address space
+        || (a == 100 && (b & 0b1100_0000) == 64)
+        // IETF protocol assignments
+        || (a == 192 && b == 0 && c == 0)
+        // Benchmarking
+        || (a == 198 && (b & 0b1111_1110) == 18)
+        // Reserved
+        || a >= 240)
+}
+
+fn is_public_v6(address: Ipv6Addr) -> bool {
+    let segments = address.segments();
+    if let Some(address) = address.to_ipv4_mapped() {
+        return is_public_v4(address);
+    }
+    // NAT64
+    if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+        let [a, b] = segments[6].to_be_bytes();
+        let [c, d] = segments[7].to_be_bytes();
+        return is_public_v4(Ipv4Addr::new(a, b, c, d));
+    }
+    !(address.is_loopback()
+        || address.is_unspecified()
+        || address.is_multicast()
+        // Unique local
+        || (segments[0] & 0xfe00) == 0xfc00
+        // Link-local
+        || (segments[0] & 0xffc0) == 0xfe80
+        // Documentation
+        || (segments[0] == 0x2001 && segments[1] == 0x0db8))
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::{PolicyViolation, RetrievalPolicy};
+    use crate::uri;
+
+    fn check(policy: &RetrievalPolicy, uri: &str) -> Result<(), PolicyViolation> {
+        policy.check(&uri::from_str(uri).expect("Invalid URI").borrow())
+    }
+
+    #[test_case("https://example.com/schema.json"; "https")]
+    #[test_case("file:///etc/passwd"; "file")]
+    #[test_case("http://169.254.169.254/latest/meta-data"; "metadata endpoint")]
+    fn unrestricted(uri: &str) {
+        assert_eq!(check(&RetrievalPolicy::new(), uri), Ok(()));
+    }
+
+    #[test_case("https://example.com/a.json", &Ok(()))]
+    #[test_case("HTTPS://example.com/a.json", &Ok(()); "case insensitive")]
+    #[test_case("http://example.com/a.json", &Err(PolicyViolation::SchemeNotAllowed { scheme: "http".into() }))]
+    #[test_case("file:///etc/passwd", &Err(PolicyViolation::SchemeNotAllowed { scheme: "file".into() }))]
+    fn schemes(uri: &str, expected: &Result<(), PolicyViolation>) {
+        let policy = RetrievalPolicy::new().allow_scheme("https");
+        assert_eq!(&check(&policy, uri), expected);
+    }
+
+    #[test_case("https://example.com/a.json", &Ok(()))]
+    #[test_case("https://EXAMPLE.com./a.json", &Ok(()); "normalized")]
+    #[test_case("https://schemas.example.org/a.json", &Ok(()); "subdomain")]
+    #[test_case("https://example.org/a.json", &Err(PolicyViolation::HostNotAllowed { host: "example.org".into() }); "apex of wildcard")]
+    #[test_case("https://internal.example.org/a.json", &Err(PolicyViolation::HostNotAllowed { host: "internal.example.org".into() }); "denied subdomain")]
+    #[test_case("https://badexample.com/a.json", &Err(PolicyViolation::HostNotAllowed { host: "badexample.com".into() }))]
+    #[test_case("file:///etc/passwd", &Ok(()); "no host")]
+    fn hosts(uri: &str, expected: &Result<(), PolicyViolation>) {
+        let policy = RetrievalPolicy::new()
+            .allow_host("example.com")
+            .allow_host("*.example.org")
+            .deny_host("internal.example.org");
+        assert_eq!(&check(&policy, uri), expected);
+    }
+
+    #[test_case("file:///opt/schemas/a.json", &Ok(()))]
+    #[test_case("file:///opt/schemas/private/a.json", &Err(PolicyViolation::PathNotAllowed { path: "/opt/schemas/private/a.json".into() }))]
+    #[test_case("file:///etc/passwd", &Err(PolicyViolation::PathNotAllowed { path: "/etc/passwd".into() }))]
+    #[test_case("file:///opt/schemas/a%2F..%2F..%2F..%2Fetc/passwd", &Err(PolicyViolation::PathNotAllowed { path: "/opt/schemas/a/../../../etc/passwd".into() }); "encoded dot segments")]
+    fn paths(uri: &str, expected: &Result<(), PolicyViolation>) {
+        let policy = RetrievalPolicy::new()
+            .allow_path("/opt/schemas/")
+            .deny_path("/opt/schemas/private/");
+        assert_eq!(&check(&policy, uri), expected);
+    }
+
+    #[test_case("http://127.0.0.1/", "127.0.0.1")]
+    #[test_case("http://10.1.2.3/", "10.1.2.3")]
+    #[test_case("http://169.254.169.254/latest/meta-data", "169.254.169.254")]
+    #[test_case("http://100.100.100.200/", "100.100.100.200")]
+    #[test_case("http://0.0.0.0/", "0.0.0.0")]
+    #[test_case("http://[::1]/", "::1")]
+    #[test_case("http://[fd00:ec2::254]/", "fd00:ec2::254")]
+    #[test_case("http://[fe80::1]/", "fe80::1")]
+    #[test_case("http://[::ffff:192.168.0.1]/", "::ffff:192.168.0.1")]
+    #[test_case("http://localhost:8080/", "127.0.0.1")]
+    fn private_networks(uri: &str, address: &str) {
+        let policy = RetrievalPolicy::new().block_private_networks(true);
+        let violation = check(&policy, uri).expect_err("Should be blocked");
+        let PolicyViolation::PrivateAddress {
+            address: actual, ..
+        } = violation
+        else {
+            panic!("Unexpected violation: {violation:?}");
+        };
+        // `localhost` may resolve to IPv6 first
+        assert!(actual.to_string() == address || actual.is_loopback());
+    }
+
+    #[test_case("http://1.1.1.1/")]
+    #[test_case("http://[2606:4700:4700::1111]/")]
+    #[test_case("file:///opt/schemas/a.json")]
+    fn public_networks(uri: &str) {
+        let policy = RetrievalPolicy::new().block_private_networks(true);
+        assert_eq!(check(&policy, uri), Ok(()));
+    }
+
+    #[test]
+    fn unresolvable_host() {
+        let policy = RetrievalPolicy::new().block_private_networks(true);
+        assert_eq!(
+            check(&policy, "https://unresolvable.invalid/a.json"),
+            Err(PolicyViolation::UnresolvableHost {
+                host: "unresolvable.invalid".into()
+            })
+        );
+    }
+}
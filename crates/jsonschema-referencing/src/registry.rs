@@ -2,6 +2,7 @@ use std::{
     collections::VecDeque,
     fmt::Debug,
     hash::{Hash, Hasher},
+    path::Path,
     sync::{Arc, RwLock},
 };
 
@@ -10,14 +11,18 @@ use fluent_uri::Uri;
 use once_cell::sync::Lazy;
 use serde_json::Value;
 
+#[cfg(feature = "retrieve-async")]
+use crate::AsyncRetrieve;
 use crate::{
-    anchors::{AnchorKey, AnchorKeyRef},
+    anchors::{AnchorEntry, AnchorKey, AnchorKeyRef},
+    directory::{self, DirectoryError},
+    graph::ReferenceGraph,
     list::List,
     meta,
     resource::unescape_segment,
     uri,
     vocabularies::{self, VocabularySet},
-    Anchor, DefaultRetriever, Draft, Error, Resolver, Resource, Retrieve,
+    Anchor, DefaultRetriever, Draft, Error, Resolver, Resource, RetrievalPolicy, Retrieve,
 };
 
 type ResourceMap = AHashMap<Uri<String>, Arc<Resource>>;
@@ -35,6 +40,8 @@ pub static SPECIFICATIONS: Lazy<Registry> = Lazy::new(|| {
     process_resources(
         pairs,
         &DefaultRetriever,
+        &RetrievalPolicy::new(),
+        None,
         &mut resources,
         &mut anchors,
         Draft::default(),
@@ -44,6 +51,7 @@ pub static SPECIFICATIONS: Lazy<Registry> = Lazy::new(|| {
         resources,
         anchors,
         resolving_cache: RwLock::new(AHashMap::new()),
+        parent: None,
     }
 });
 
@@ -58,6 +66,8 @@ pub struct Registry {
     resources: ResourceMap,
     anchors: AHashMap<AnchorKey, Anchor>,
     resolving_cache: RwLock<AHashMap<u64, Arc<Uri<String>>>>,
+    /// Registry to look up resources that are not in this one.
+    parent: Option<Arc<Registry>>,
 }
 
 impl Clone for Registry {
@@ -66,6 +76,7 @@ impl Clone for Registry {
             resources: self.resources.clone(),
             anchors: self.anchors.clone(),
             resolving_cache: RwLock::new(AHashMap::new()),
+            parent: self.parent.clone(),
         }
     }
 }
@@ -73,6 +84,7 @@ impl Clone for Registry {
 /// Configuration options for creating a [`Registry`].
 pub struct RegistryOptions {
     retriever: Box<dyn Retrieve>,
+    policy: RetrievalPolicy,
     draft: Draft,
 }
 
@@ -82,6 +94,7 @@ impl RegistryOptions {
     pub fn new() -> Self {
         Self {
             retriever: Box::new(DefaultRetriever),
+            policy: RetrievalPolicy::new(),
             draft: Draft::default(),
         }
     }
@@ -91,6 +104,12 @@ impl RegistryOptions {
         self.retriever = retriever;
         self
     }
+    /// Set a policy restricting which external resources may be retrieved.
+    #[must_use]
+    pub fn policy(mut self, policy: RetrievalPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
     /// Set specification version under which the resources should be interpreted under.
     #[must_use]
     pub fn draft(mut self, draft: Draft) -> Self {
@@ -103,7 +122,7 @@ impl RegistryOptions {
     ///
     /// Returns an error if the URI is invalid or if there's an issue processing the resource.
     pub fn try_new(self, uri: impl Into<String>, resource: Resource) -> Result<Registry, Error> {
-        Registry::try_new_impl(uri, resource, &*self.retriever, self.draft)
+        Registry::try_new_impl(uri, resource, &*self.retriever, &self.policy, self.draft)
     }
     /// Create a [`Registry`] from multiple resources using these options.
     ///
@@ -114,7 +133,26 @@ impl RegistryOptions {
         self,
         pairs: impl Iterator<Item = (impl Into<String>, Resource)>,
     ) -> Result<Registry, Error> {
-        Registry::try_from_resources_impl(pairs, &*self.retriever, self.draft)
+        Registry::try_from_resources_impl(pairs, &*self.retriever, &self.policy, self.draft)
+    }
+    /// Create a [`Registry`] from all `.json` files in a directory and its subdirectories using
+    /// these options.
+    ///
+    /// Each file is registered under its `file:` URI and under its `$id`, if it has one.
+    ///
+    /// # Errors
+    ///
+    /// Returns all files that couldn't be read or parsed and all duplicate `$id`s, or the error
+    /// from building the registry.
+    pub fn try_from_directory(self, path: impl AsRef<Path>) -> Result<Registry, DirectoryError> {
+        let pairs = directory::load(path.as_ref(), self.draft)?;
+        Registry::try_from_resources_impl(
+            pairs.into_iter(),
+            &*self.retriever,
+            &self.policy,
+            self.draft,
+        )
+        .map_err(DirectoryError::registry)
     }
 }
 
@@ -141,7 +179,13 @@ impl Registry {
     ///
     /// Returns an error if the URI is invalid or if there's an issue processing the resource.
     pub fn try_new(uri: impl Into<String>, resource: Resource) -> Result<Self, Error> {
-        Self::try_new_impl(uri, resource, &DefaultRetriever, Draft::default())
+        Self::try_new_impl(
+            uri,
+            resource,
+            &DefaultRetriever,
+            &RetrievalPolicy::new(),
+            Draft::default(),
+        )
     }
     /// Create a new [`Registry`] from an iterator of (URI, Resource) pairs.
     ///
@@ -155,28 +199,55 @@ impl Registry {
     pub fn try_from_resources(
         pairs: impl Iterator<Item = (impl Into<String>, Resource)>,
     ) -> Result<Self, Error> {
-        Self::try_from_resources_impl(pairs, &DefaultRetriever, Draft::default())
+        Self::try_from_resources_impl(
+            pairs,
+            &DefaultRetriever,
+            &RetrievalPolicy::new(),
+            Draft::default(),
+        )
+    }
+    /// Create a new [`Registry`] from all `.json` files in a directory and its subdirectories.
+    ///
+    /// Each file is registered under its `file:` URI and under its `$id`, if it has one.
+    ///
+    /// # Errors
+    ///
+    /// Returns all files that couldn't be read or parsed and all duplicate `$id`s, or the error
+    /// from building the registry.
+    pub fn try_from_directory(path: impl AsRef<Path>) -> Result<Self, DirectoryError> {
+        RegistryOptions::new().try_from_directory(path)
     }
     fn try_new_impl(
         uri: impl Into<String>,
         resource: Resource,
         retriever: &dyn Retrieve,
+        policy: &RetrievalPolicy,
         draft: Draft,
     ) -> Result<Self, Error> {
-        Self::try_from_resources_impl([(uri, resource)].into_iter(), retriever, draft)
+        Self::try_from_resources_impl([(uri, resource)].into_iter(), retriever, policy, draft)
     }
     fn try_from_resources_impl(
         pairs: impl Iterator<Item = (impl Into<String>, Resource)>,
         retriever: &dyn Retrieve,
+        policy: &RetrievalPolicy,
         draft: Draft,
     ) -> Result<Self, Error> {
         let mut resources = ResourceMap::new();
         let mut anchors = AHashMap::new();
-        process_resources(pairs, retriever, &mut resources, &mut anchors, draft)?;
+        process_resources(
+            pairs,
+            retriever,
+            policy,
+            None,
+            &mut resources,
+            &mut anchors,
+            draft,
+        )?;
         Ok(Registry {
             resources,
             anchors,
             resolving_cache: RwLock::new(AHashMap::new()),
+            parent: None,
         })
     }
     /// Create a new registry with a new resource.
@@ -228,16 +299,150 @@ impl Registry {
         pairs: impl Iterator<Item = (impl Into<String>, Resource)>,
         retriever: &dyn Retrieve,
         draft: Draft,
+    ) -> Result<Registry, Error> {
+        self.try_with_resources_and_policy(pairs, retriever, &RetrievalPolicy::new(), draft)
+    }
+    /// Create a new registry with new resources, retrieving only the external resources allowed
+    /// by the given policy with the given retriever.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any URI is invalid, if retrieving a resource violates the policy or if
+    /// there's an issue processing the resources.
+    pub fn try_with_resources_and_policy(
+        self,
+        pairs: impl Iterator<Item = (impl Into<String>, Resource)>,
+        retriever: &dyn Retrieve,
+        policy: &RetrievalPolicy,
+        draft: Draft,
+    ) -> Result<Registry, Error> {
+        let mut resources = self.resources;
+        let mut anchors = self.anchors;
+        process_resources(
+            pairs,
+            retriever,
+            policy,
+            self.parent.as_deref(),
+            &mut resources,
+            &mut anchors,
+            draft,
+        )?;
+        Ok(Registry {
+            resources,
+            anchors,
+            resolving_cache: RwLock::new(AHashMap::new()),
+            parent: self.parent,
+        })
+    }
+    /// Create a new registry with new resources, retrieving external resources with the given
+    /// asynchronous retriever.
+    ///
+    /// External resources referenced by the same resource are retrieved concurrently.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any URI is invalid or if there's an issue processing the resources.
+    #[cfg(feature = "retrieve-async")]
+    pub async fn try_with_resources_and_async_retriever(
+        self,
+        pairs: impl Iterator<Item = (impl Into<String>, Resource)>,
+        retriever: &dyn AsyncRetrieve,
+        draft: Draft,
+    ) -> Result<Registry, Error> {
+        self.try_with_resources_and_policy_async(pairs, retriever, &RetrievalPolicy::new(), draft)
+            .await
+    }
+    /// Create a new registry with new resources, retrieving only the external resources allowed
+    /// by the given policy with the given asynchronous retriever.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any URI is invalid, if retrieving a resource violates the policy or if
+    /// there's an issue processing the resources.
+    #[cfg(feature = "retrieve-async")]
+    pub async fn try_with_resources_and_policy_async(
+        self,
+        pairs: impl Iterator<Item = (impl Into<String>, Resource)>,
+        retriever: &dyn AsyncRetrieve,
+        policy: &RetrievalPolicy,
+        draft: Draft,
     ) -> Result<Registry, Error> {
         let mut resources = self.resources;
         let mut anchors = self.anchors;
-        process_resources(pairs, retriever, &mut resources, &mut anchors, draft)?;
+        process_resources_async(
+            pairs,
+            retriever,
+            policy,
+            self.parent.as_deref(),
+            &mut resources,
+            &mut anchors,
+            draft,
+        )
+        .await?;
         Ok(Registry {
             resources,
             anchors,
             resolving_cache: RwLock::new(AHashMap::new()),
+            parent: self.parent,
         })
     }
+    /// Look up resources missing from this registry in `parent`.
+    ///
+    /// Resources and anchors of the parent are shared rather than copied, and resources that
+    /// are already in the parent are not retrieved again when new resources are added. This
+    /// makes it cheap to build many registries on top of one large, fully processed registry.
+    #[must_use]
+    pub fn with_parent(mut self, parent: Arc<Registry>) -> Registry {
+        self.parent = Some(parent);
+        self
+    }
+    /// All resources in this registry and its parents, in no particular order.
+    ///
+    /// Resources are listed under the URIs they were added or retrieved with, and resources and
+    /// subresources with an `$id` under that `$id` as well. Resources of a parent that are
+    /// replaced by this registry are skipped.
+    #[must_use]
+    pub fn resources(&self) -> Box<dyn Iterator<Item = (&Uri<String>, &Resource)> + '_> {
+        let own = self
+            .resources
+            .iter()
+            .map(|(uri, resource)| (uri, &**resource));
+        match &self.parent {
+            Some(parent) => Box::new(
+                own.chain(
+                    parent
+                        .resources()
+                        .filter(|(uri, _)| !self.resources.contains_key(*uri)),
+                ),
+            ),
+            None => Box::new(own),
+        }
+    }
+    /// All anchors and dynamic anchors in this registry and its parents, in no particular order.
+    #[must_use]
+    pub fn anchors(&self) -> Box<dyn Iterator<Item = AnchorEntry<'_>> + '_> {
+        let own = self
+            .anchors
+            .iter()
+            .map(|(key, anchor)| AnchorEntry::new(key, anchor));
+        match &self.parent {
+            Some(parent) => Box::new(
+                own.chain(
+                    parent
+                        .anchors()
+                        .filter(|anchor| !self.resources.contains_key(anchor.uri())),
+                ),
+            ),
+            None => Box::new(own),
+        }
+    }
+    /// Collect all references between the resources in this registry and its parents.
+    ///
+    /// See [`ReferenceGraph`] for unresolved references and reference cycles.
+    #[must_use]
+    pub fn reference_graph(&self) -> ReferenceGraph {
+        ReferenceGraph::new(self)
+    }
     /// Create a new [`Resolver`] for this registry with the given base URI.
     ///
     /// # Errors
@@ -260,8 +465,18 @@ impl Registry {
     ) -> Resolver {
         Resolver::from_parts(self, base_uri, scopes)
     }
+    /// Find a resource in this registry or in any of its parents.
+    pub(crate) fn resource(&self, uri: &Uri<String>) -> Option<&Resource> {
+        match self.resources.get(uri) {
+            Some(resource) => Some(resource),
+            None => self.parent.as_ref()?.resource(uri),
+        }
+    }
+    fn contains_resource(&self, uri: &Uri<String>) -> bool {
+        self.resource(uri).is_some()
+    }
     pub(crate) fn get_or_retrieve<'r>(&'r self, uri: &Uri<String>) -> Result<&'r Resource, Error> {
-        if let Some(resource) = self.resources.get(uri) {
+        if let Some(resource) = self.resource(uri) {
             Ok(resource)
         } else {
             Err(Error::unretrievable(
@@ -276,13 +491,16 @@ impl Registry {
         if let Some(value) = self.anchors.get(key.borrow_dyn()) {
             return Ok(value);
         }
-        let resource = &self.resources[uri];
-        if let Some(id) = resource.id() {
-            let uri = uri::from_str(id)?;
-            let key = AnchorKeyRef::new(&uri, name);
-            if let Some(value) = self.anchors.get(key.borrow_dyn()) {
-                return Ok(value);
+        if let Some(resource) = self.resources.get(uri) {
+            if let Some(id) = resource.id() {
+                let uri = uri::from_str(id)?;
+                let key = AnchorKeyRef::new(&uri, name);
+                if let Some(value) = self.anchors.get(key.borrow_dyn()) {
+                    return Ok(value);
+                }
             }
+        } else if let Some(parent) = &self.parent {
+            return parent.anchor(uri, name);
         }
         if name.contains('/') {
             Err(Error::invalid_anchor(name.to_string()))
@@ -325,7 +543,7 @@ impl Registry {
             Err(Error::UnknownSpecification { specification }) => {
                 // Try to lookup the specification and find enabled vocabularies
                 if let Ok(Some(resource)) =
-                    uri::from_str(&specification).map(|uri| self.resources.get(&uri))
+                    uri::from_str(&specification).map(|uri| self.resource(&uri))
                 {
                     if let Ok(Some(vocabularies)) = vocabularies::find(resource.contents()) {
                         return vocabularies;
@@ -341,30 +559,149 @@ impl Registry {
 fn process_resources(
     pairs: impl Iterator<Item = (impl Into<String>, Resource)>,
     retriever: &dyn Retrieve,
+    policy: &RetrievalPolicy,
+    parent: Option<&Registry>,
     resources: &mut ResourceMap,
     anchors: &mut AHashMap<AnchorKey, Anchor>,
     default_draft: Draft,
 ) -> Result<(), Error> {
-    let mut queue = VecDeque::with_capacity(32);
-    let mut seen = AHashSet::new();
-    let mut external = AHashSet::new();
-    let mut scratch = String::new();
+    let mut state = ProcessingState::new(pairs, resources)?;
+    let mut retrievals = 0;
+
+    loop {
+        if state.queue.is_empty() && state.external.is_empty() {
+            break;
+        }
 
-    // Populate the resources & queue from the input
-    for (uri, resource) in pairs {
-        let uri = uri::from_str(uri.into().trim_end_matches('#'))?;
-        let resource = Arc::new(resource);
-        resources.insert(uri.clone(), Arc::clone(&resource));
-        queue.push_back((uri, resource));
+        // Process current queue and collect references to external resources
+        state.process_queue(resources, anchors)?;
+        state.depth += 1;
+        // Retrieve external resources
+        for uri in state.external.drain() {
+            let mut fragmentless = uri.clone();
+            fragmentless.set_fragment(None);
+            if !resources.contains_key(&fragmentless)
+                && !parent.is_some_and(|parent| parent.contains_resource(&fragmentless))
+            {
+                policy.check_retrieval(&fragmentless, state.depth, retrievals)?;
+                retrievals += 1;
+                let retrieved = retriever
+                    .retrieve(&fragmentless.borrow())
+                    .map_err(|err| Error::unretrievable(fragmentless.as_str(), err))?;
+                handle_retrieved(
+                    uri,
+                    fragmentless,
+                    retrieved,
+                    resources,
+                    &mut state.queue,
+                    default_draft,
+                )?;
+            }
+        }
     }
 
+    Ok(())
+}
+
+#[cfg(feature = "retrieve-async")]
+async fn process_resources_async(
+    pairs: impl Iterator<Item = (impl Into<String>, Resource)>,
+    retriever: &dyn AsyncRetrieve,
+    policy: &RetrievalPolicy,
+    parent: Option<&Registry>,
+    resources: &mut ResourceMap,
+    anchors: &mut AHashMap<AnchorKey, Anchor>,
+    default_draft: Draft,
+) -> Result<(), Error> {
+    let mut state = ProcessingState::new(pairs, resources)?;
+    let mut retrievals = 0;
+
     loop {
-        if queue.is_empty() && external.is_empty() {
+        if state.queue.is_empty() && state.external.is_empty() {
             break;
         }
 
-        // Process current queue and collect references to external resources
-        while let Some((mut base, resource)) = queue.pop_front() {
+        state.process_queue(resources, anchors)?;
+        state.depth += 1;
+        // Resources referenced from the same round don't depend on each other and are
+        // retrieved concurrently
+        let mut pending: Vec<(Uri<String>, Uri<String>)> = Vec::new();
+        for uri in state.external.drain() {
+            let mut fragmentless = uri.clone();
+            fragmentless.set_fragment(None);
+            if !resources.contains_key(&fragmentless)
+                && !parent.is_some_and(|parent| parent.contains_resource(&fragmentless))
+                && !pending
+                    .iter()
+                    .any(|(_, existing)| *existing == fragmentless)
+            {
+                policy.check_retrieval(&fragmentless, state.depth, retrievals)?;
+                retrievals += 1;
+                pending.push((uri, fragmentless));
+            }
+        }
+        let uris: Vec<Uri<&str>> = pending
+            .iter()
+            .map(|(_, fragmentless)| fragmentless.borrow())
+            .collect();
+        let retrieved =
+            futures::future::join_all(uris.iter().map(|uri| retriever.retrieve(uri))).await;
+        for ((uri, fragmentless), retrieved) in pending.into_iter().zip(retrieved) {
+            let retrieved =
+                retrieved.map_err(|err| Error::unretrievable(fragmentless.as_str(), err))?;
+            handle_retrieved(
+                uri,
+                fragmentless,
+                retrieved,
+                resources,
+                &mut state.queue,
+                default_draft,
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Resources waiting to be processed and references to external resources found so far.
+struct ProcessingState {
+    queue: VecDeque<(Uri<String>, Arc<Resource>)>,
+    seen: AHashSet<u64>,
+    external: AHashSet<Uri<String>>,
+    scratch: String,
+    /// Number of references between the initial resources and the external ones found so far.
+    depth: usize,
+}
+
+impl ProcessingState {
+    fn new(
+        pairs: impl Iterator<Item = (impl Into<String>, Resource)>,
+        resources: &mut ResourceMap,
+    ) -> Result<Self, Error> {
+        let mut queue = VecDeque::with_capacity(32);
+        // Populate the resources & queue from the input
+        for (uri, resource) in pairs {
+            let uri = uri::from_str(uri.into().trim_end_matches('#'))?;
+            let resource = Arc::new(resource);
+            resources.insert(uri.clone(), Arc::clone(&resource));
+            queue.push_back((uri, resource));
+        }
+        Ok(Self {
+            queue,
+            seen: AHashSet::new(),
+            external: AHashSet::new(),
+            scratch: String::new(),
+            depth: 0,
+        })
+    }
+
+    /// Register all queued resources with their subresources and anchors.
+    fn process_queue(
+        &mut self,
+        resources: &mut ResourceMap,
+        anchors: &mut AHashMap<AnchorKey, Anchor>,
+    ) -> Result<(), Error> {
+        while let Some((mut base, resource)) = self.queue.pop_front() {
             if let Some(id) = resource.id() {
                 base = uri::resolve_against(&base.borrow(), id)?;
             }
@@ -381,9 +718,9 @@ fn process_resources(
             collect_external_resources(
                 &base,
                 resource.contents(),
-                &mut external,
-                &mut seen,
-                &mut scratch,
+                &mut self.external,
+                &mut self.seen,
+                &mut self.scratch,
             )?;
 
             // Process subresources
@@ -395,57 +732,58 @@ fn process_resources(
                     collect_external_resources(
                         &base,
                         subresource.contents(),
-                        &mut external,
-                        &mut seen,
-                        &mut scratch,
+                        &mut self.external,
+                        &mut self.seen,
+                        &mut self.scratch,
                     )?;
                 } else {
                     collect_external_resources(
                         &base,
                         subresource.contents(),
-                        &mut external,
-                        &mut seen,
-                        &mut scratch,
+                        &mut self.external,
+                        &mut self.seen,
+                        &mut self.scratch,
                     )?;
                 };
-                queue.push_back((base.clone(), subresource));
+                self.queue.push_back((base.clone(), subresource));
             }
             if resource.id().is_some() {
                 resources.insert(base, resource);
             }
         }
-        // Retrieve external resources
-        for uri in external.drain() {
-            let mut fragmentless = uri.clone();
-            fragmentless.set_fragment(None);
-            if !resources.contains_key(&fragmentless) {
-                let retrieved = retriever
-                    .retrieve(&fragmentless.borrow())
-                    .map_err(|err| Error::unretrievable(fragmentless.as_str(), err))?;
-                let resource = Arc::new(Resource::from_contents_and_specification(
-                    retrieved,
+        Ok(())
+    }
+}
+
+/// Store a retrieved resource and queue it for processing.
+fn handle_retrieved(
+    uri: Uri<String>,
+    fragmentless: Uri<String>,
+    retrieved: Value,
+    resources: &mut ResourceMap,
+    queue: &mut VecDeque<(Uri<String>, Arc<Resource>)>,
+    default_draft: Draft,
+) -> Result<(), Error> {
+    let resource = Arc::new(Resource::from_contents_and_specification(
+        retrieved,
+        default_draft,
+    )?);
+    resources.insert(fragmentless.clone(), Arc::clone(&resource));
+    if let Some(fragment) = uri.fragment() {
+        // The original `$ref` could have a fragment that points to a place that won't
+        // be discovered via the regular sub-resources discovery. Therefore we need to
+        // explicitly check it
+        if let Some(resolved) = pointer(resource.contents(), fragment.as_str()) {
+            queue.push_back((
+                uri,
+                Arc::new(Resource::from_contents_and_specification(
+                    resolved.clone(),
                     default_draft,
-                )?);
-                resources.insert(fragmentless.clone(), Arc::clone(&resource));
-                if let Some(fragment) = uri.fragment() {
-                    // The original `$ref` could have a fragment that points to a place that won't
-                    // be discovered via the regular sub-resources discovery. Therefore we need to
-                    // explicitly check it
-                    if let Some(resolved) = pointer(resource.contents(), fragment.as_str()) {
-                        queue.push_back((
-                            uri,
-                            Arc::new(Resource::from_contents_and_specification(
-                                resolved.clone(),
-                                default_draft,
-                            )?),
-                        ));
-                    }
-                }
-                queue.push_back((fragmentless, resource));
-            }
+                )?),
+            ));
         }
     }
-
+    queue.push_back((fragmentless, resource));
     Ok(())
 }
 
@@ -553,14 +891,14 @@ fn parse_index(s: &str) -> Option<usize> {
 }
 #[cfg(test)]
 mod tests {
-    use std::error::Error as _;
+    use std::{error::Error as _, sync::Arc};
 
     use ahash::AHashMap;
     use fluent_uri::Uri;
     use serde_json::{json, Value};
     use test_case::test_case;
 
-    use crate::{uri::from_str, Draft, Registry, Resource, Retrieve};
+    use crate::{uri::from_str, Draft, Error, Registry, Resource, RetrievalPolicy, Retrieve};
 
     use super::{RegistryOptions, SPECIFICATIONS};
 
@@ -973,10 +1311,246 @@ mod tests {
         assert_eq!(resolved.contents(), &json!({"type": "object"}));
     }
 
+    #[test]
+    fn test_with_parent() {
+        let retriever = create_test_retriever(&[(
+            "http://example.com/library",
+            json!({"$defs": {"name": {"$anchor": "name", "type": "string"}}}),
+        )]);
+        let parent = Arc::new(
+            SPECIFICATIONS
+                .clone()
+                .try_with_resource_and_retriever(
+                    "http://example.com/root",
+                    Resource::from_contents(json!({"$ref": "library"})).expect("Invalid resource"),
+                    &retriever,
+                )
+                .expect("Invalid resource"),
+        );
+        // The library is already in the parent, so nothing is retrieved
+        let registry = Registry::try_new(
+            "http://example.com/other",
+            Draft::Draft202012.create_resource(json!({"type": "integer"})),
+        )
+        .expect("Invalid resource")
+        .with_parent(Arc::clone(&parent))
+        .try_with_resource_and_retriever(
+            "http://example.com/child",
+            Draft::Draft202012.create_resource(json!({"$ref": "library#name"})),
+            &create_test_retriever(&[]),
+        )
+        .expect("Invalid resource");
+        let resolver = registry
+            .try_resolver("http://example.com/child")
+            .expect("Invalid base URI");
+        let resolved = resolver.lookup("library#name").expect("Lookup failed");
+        assert_eq!(
+            resolved.contents(),
+            &json!({"$anchor": "name", "type": "string"})
+        );
+        let resolved = resolver
+            .lookup("http://json-schema.org/draft-07/schema#/definitions/nonNegativeInteger")
+            .expect("Lookup failed");
+        assert_eq!(
+            resolved.contents(),
+            &json!({"type": "integer", "minimum": 0})
+        );
+        // Parent's resources and anchors are listed too, without duplicates
+        let uris: Vec<_> = registry.resources().map(|(uri, _)| uri.as_str()).collect();
+        assert_eq!(uris.len(), parent.resources().count() + 2);
+        assert!(uris.contains(&"http://example.com/child"));
+        assert!(uris.contains(&"http://example.com/library"));
+        assert_eq!(
+            registry
+                .anchors()
+                .filter(|anchor| anchor.name() == "name")
+                .count(),
+            1
+        );
+        // Own resources and anchors still take precedence
+        assert!(resolver.lookup("other").is_ok());
+        assert!(resolver.lookup("library#missing").is_err());
+        // The parent is not affected
+        assert!(parent
+            .try_resolver("http://example.com/root")
+            .expect("Invalid base URI")
+            .lookup("child")
+            .is_err());
+    }
+
     #[test]
     fn test_invalid_reference() {
         // Found via fuzzing
         let resource = Draft::Draft202012.create_resource(json!({"$schema": "$##"}));
         let _ = Registry::try_new("http://#/", resource);
     }
+
+    #[test_case(RetrievalPolicy::new(), None; "unrestricted")]
+    #[test_case(RetrievalPolicy::new().max_depth(2), None; "deep enough")]
+    #[test_case(
+        RetrievalPolicy::new().max_depth(1),
+        Some("Retrieving 'http://example.com/c' is not allowed by the retrieval policy: the resource is more than 1 references away");
+        "too deep"
+    )]
+    #[test_case(
+        RetrievalPolicy::new().max_retrievals(1),
+        Some("Retrieving 'http://example.com/c' is not allowed by the retrieval policy: more than 1 resources would be retrieved");
+        "too many retrievals"
+    )]
+    #[test_case(
+        RetrievalPolicy::new().deny_path("/c"),
+        Some("Retrieving 'http://example.com/c' is not allowed by the retrieval policy: path '/c' is not allowed");
+        "denied path"
+    )]
+    #[test_case(
+        RetrievalPolicy::new().allow_scheme("https"),
+        Some("Retrieving 'http://example.com/b' is not allowed by the retrieval policy: scheme 'http' is not allowed");
+        "denied scheme"
+    )]
+    fn test_retrieval_policy(policy: RetrievalPolicy, expected: Option<&str>) {
+        let retriever = create_test_retriever(&[
+            ("http://example.com/b", json!({"$ref": "c"})),
+            ("http://example.com/c", json!({"type": "string"})),
+        ]);
+        let result = Registry::options()
+            .retriever(Box::new(retriever))
+            .policy(policy)
+            .try_new(
+                "http://example.com/a",
+                Draft::Draft202012.create_resource(json!({"$ref": "b"})),
+            );
+        match expected {
+            None => {
+                result.expect("Should be allowed");
+            }
+            Some(expected) => {
+                let error = result.expect_err("Should be denied");
+                assert!(matches!(error, Error::PolicyViolation { .. }));
+                assert_eq!(error.to_string(), expected);
+            }
+        }
+    }
+
+    #[cfg(feature = "retrieve-async")]
+    mod async_retrieval {
+        use std::sync::{
+            atomic::{AtomicUsize, Ordering},
+            Mutex,
+        };
+
+        use ahash::AHashMap;
+        use fluent_uri::Uri;
+        use serde_json::{json, Value};
+
+        use crate::{AsyncRetrieve, Draft, Resource, SPECIFICATIONS};
+
+        #[derive(Default)]
+        struct TestAsyncRetriever {
+            schemas: AHashMap<String, Value>,
+            in_flight: AtomicUsize,
+            max_in_flight: AtomicUsize,
+            retrieved: Mutex<Vec<String>>,
+        }
+
+        impl TestAsyncRetriever {
+            fn new(schemas: &[(&str, Value)]) -> Self {
+                TestAsyncRetriever {
+                    schemas: schemas
+                        .iter()
+                        .map(|(uri, schema)| ((*uri).to_string(), schema.clone()))
+                        .collect(),
+                    ..Default::default()
+                }
+            }
+        }
+
+        #[async_trait::async_trait]
+        impl AsyncRetrieve for TestAsyncRetriever {
+            async fn retrieve(
+                &self,
+                uri: &Uri<&str>,
+            ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+                let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+                // Give other retrievals a chance to start
+                tokio::task::yield_now().await;
+                self.in_flight.fetch_sub(1, Ordering::SeqCst);
+                self.retrieved
+                    .lock()
+                    .expect("Lock is poisoned")
+                    .push(uri.as_str().to_string());
+                self.schemas
+                    .get(uri.as_str())
+                    .cloned()
+                    .ok_or_else(|| format!("Failed to find {uri}").into())
+            }
+        }
+
+        #[tokio::test]
+        async fn test_concurrent_retrieval() {
+            let retriever = TestAsyncRetriever::new(&[
+                (
+                    "http://example.com/a",
+                    json!({"$ref": "http://example.com/c#/$defs/c"}),
+                ),
+                ("http://example.com/b", json!({"type": "string"})),
+                (
+                    "http://example.com/c",
+                    json!({"$defs": {"c": {"type": "integer"}}}),
+                ),
+            ]);
+            let resource = Draft::Draft202012.create_resource(json!({
+                "properties": {
+                    "a": {"$ref": "http://example.com/a"},
+                    "b": {"$ref": "http://example.com/b"}
+                }
+            }));
+            let registry = SPECIFICATIONS
+                .clone()
+                .try_with_resources_and_async_retriever(
+                    [("http://example.com/root", resource)].into_iter(),
+                    &retriever,
+                    Draft::Draft202012,
+                )
+                .await
+                .expect("Invalid resources");
+            // `a` and `b` are independent, `c` is only known after `a` is retrieved
+            assert_eq!(retriever.max_in_flight.load(Ordering::SeqCst), 2);
+            let mut retrieved = retriever.retrieved.into_inner().expect("Lock is poisoned");
+            retrieved.sort_unstable();
+            assert_eq!(
+                retrieved,
+                [
+                    "http://example.com/a",
+                    "http://example.com/b",
+                    "http://example.com/c"
+                ]
+            );
+            let resolver = registry.try_resolver("").expect("Invalid base URI");
+            let resolved = resolver
+                .lookup("http://example.com/c#/$defs/c")
+                .expect("Lookup failed");
+            assert_eq!(resolved.contents(), &json!({"type": "integer"}));
+        }
+
+        #[tokio::test]
+        async fn test_retrieval_error() {
+            let retriever = TestAsyncRetriever::new(&[]);
+            let resource = Resource::from_contents(json!({"$ref": "http://example.com/missing"}))
+                .expect("Invalid resource");
+            let error = SPECIFICATIONS
+                .clone()
+                .try_with_resources_and_async_retriever(
+                    [("http://example.com/root", resource)].into_iter(),
+                    &retriever,
+                    Draft::Draft202012,
+                )
+                .await
+                .expect_err("Should fail");
+            assert_eq!(
+                error.to_string(),
+                "Resource 'http://example.com/missing' is not present in a registry and retrieving it failed: Failed to find http://example.com/missing"
+            );
+        }
+    }
 }
@@ -20,6 +20,29 @@ pub trait Retrieve: Send + Sync {
     fn retrieve(&self, uri: &Uri<&str>) -> Result<Value, Box<dyn std::error::Error + Send + Sync>>;
 }
 
+/// Trait for retrieving resources from external sources without blocking.
+///
+/// The asynchronous counterpart of [`Retrieve`]. Independent resources are retrieved
+/// concurrently when building a [`crate::Registry`] with
+/// [`crate::Registry::try_with_resources_and_async_retriever`].
+#[cfg(feature = "retrieve-async")]
+#[async_trait::async_trait]
+pub trait AsyncRetrieve: Send + Sync {
+    /// Attempt to retrieve a resource from the given URI.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - The URI of the resource to retrieve.
+    ///
+    /// # Errors
+    ///
+    /// If the resource couldn't be retrieved or an error occurred.
+    async fn retrieve(
+        &self,
+        uri: &Uri<&str>,
+    ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>>;
+}
+
 #[derive(Debug, Clone)]
 struct DefaultRetrieverError;
 
@@ -39,3 +62,14 @@ impl Retrieve for DefaultRetriever {
         Err(Box::new(DefaultRetrieverError))
     }
 }
+
+#[cfg(feature = "retrieve-async")]
+#[async_trait::async_trait]
+impl AsyncRetrieve for DefaultRetriever {
+    async fn retrieve(
+        &self,
+        _: &Uri<&str>,
+    ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+        Err(Box::new(DefaultRetrieverError))
+    }
+}
@@ -0,0 +1,379 @@
+//! Caching of retrieved external resources on disk.
+use referencing::{Retrieve, Uri};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    error, fmt, fs,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Number of temporary files created by this process so far, to keep their names unique.
+static TEMPORARY_FILES: AtomicUsize = AtomicUsize::new(0);
+
+/// Retriever that stores resources retrieved by another retriever on disk and serves them from
+/// there on subsequent retrievals, including ones from other processes.
+///
+/// Entries are kept forever unless a time to live is set via [`CachingRetriever::with_ttl`].
+/// If refreshing an expired entry fails, the expired entry is used instead. Failing to write
+/// the cache doesn't fail retrieval.
+///
+/// In offline mode, enabled via [`CachingRetriever::should_work_offline`], the wrapped retriever
+/// is never called and all resources have to be in the cache already. This is useful for
+/// running without network access, e.g. in CI, with a cache that was filled beforehand.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(all(feature = "resolve-http", not(target_arch = "wasm32")))]
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use jsonschema::{CachingRetriever, HttpRetriever};
+/// use std::time::Duration;
+///
+/// let retriever = CachingRetriever::new(HttpRetriever::builder().build()?, ".schema-cache")
+///     .with_ttl(Duration::from_secs(24 * 60 * 60));
+///
+/// let validator = jsonschema::options()
+///     .with_retriever(retriever)
+///     .build(&serde_json::json!({"type": "string"}))?;
+/// # Ok(())
+/// # }
+/// # #[cfg(not(all(feature = "resolve-http", not(target_arch = "wasm32"))))]
+/// # fn main() {}
+/// ```
+#[derive(Debug, Clone)]
+pub struct CachingRetriever<R> {
+    inner: R,
+    directory: PathBuf,
+    ttl: Option<Duration>,
+    offline: bool,
+}
+
+impl<R: Retrieve> CachingRetriever<R> {
+    /// Create a retriever that caches resources retrieved by `inner` in `directory`.
+    ///
+    /// The directory is created when the first resource is stored.
+    pub fn new(inner: R, directory: impl Into<PathBuf>) -> Self {
+        CachingRetriever {
+            inner,
+            directory: directory.into(),
+            ttl: None,
+            offline: false,
+        }
+    }
+    /// Retrieve cached resources again once they are older than `ttl`.
+    #[must_use]
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+    /// Serve resources only from the cache, without calling the wrapped retriever.
+    #[must_use]
+    pub fn should_work_offline(mut self, yes: bool) -> Self {
+        self.offline = yes;
+        self
+    }
+    /// The directory where resources are cached.
+    #[must_use]
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+    /// Path of the cache entry for `uri`.
+    fn path(&self, uri: &str) -> PathBuf {
+        self.directory.join(format!("{:016x}.json", fnv1a(uri)))
+    }
+    fn load(&self, uri: &str) -> Option<Entry> {
+        let contents = fs::read(self.path(uri)).ok()?;
+        let entry: Entry = serde_json::from_slice(&contents).ok()?;
+        // Different URIs may have the same hash
+        (entry.uri == uri).then_some(entry)
+    }
+    fn store(&self, uri: &str, document: &Value) -> std::io::Result<()> {
+        let entry = EntryRef {
+            uri,
+            retrieved_at: now(),
+            document,
+        };
+        fs::create_dir_all(&self.directory)?;
+        let path = self.path(uri);
+        // Write to a temporary file first, so concurrent readers never see a partial entry. Its
+        // name is unique across processes and threads, so concurrent writers don't clash either
+        let temporary = path.with_extension(format!(
+            "{}.{}.tmp",
+            std::process::id(),
+            TEMPORARY_FILES.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&temporary, serde_json::to_vec(&entry)?)?;
+        fs::rename(&temporary, &path).map_err(|error| {
+            let _ = fs::remove_file(&temporary);
+            error
+        })
+    }
+    fn is_fresh(&self, entry: &Entry) -> bool {
+        self.ttl.map_or(true, |ttl| {
+            now().saturating_sub(entry.retrieved_at) < ttl.as_secs()
+        })
+    }
+}
+
+impl<R: Retrieve> Retrieve for CachingRetriever<R> {
+    fn retrieve(&self, uri: &Uri<&str>) -> Result<Value, Box<dyn error::Error + Send + Sync>> {
+        let cached = self.load(uri.as_str());
+        if self.offline {
+            return cached
+                .map(|entry| entry.document)
+                .ok_or_else(|| Box::new(NotCached) as Box<dyn error::Error + Send + Sync>);
+        }
+        match cached {
+            Some(entry) if self.is_fresh(&entry) => Ok(entry.document),
+            cached => match self.inner.retrieve(uri) {
+                Ok(document) => {
+                    let _ = self.store(uri.as_str(), &document);
+                    Ok(document)
+                }
+                Err(error) => cached.map(|entry| entry.document).ok_or(error),
+            },
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Entry {
+    uri: String,
+    /// Seconds since the Unix epoch.
+    retrieved_at: u64,
+    document: Value,
+}
+
+#[derive(Serialize)]
+struct EntryRef<'a> {
+    uri: &'a str,
+    retrieved_at: u64,
+    document: &'a Value,
+}
+
+/// The resource is not in the cache and the retriever is offline.
+#[derive(Debug)]
+struct NotCached;
+
+impl fmt::Display for NotCached {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Resource is not cached and the retriever is in offline mode")
+    }
+}
+
+impl error::Error for NotCached {}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// FNV-1a hash, which is stable across platforms and versions, unlike the standard hashers.
+fn fnv1a(value: &str) -> u64 {
+    value.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::CachingRetriever;
+    use referencing::{uri, Retrieve, Uri};
+    use serde_json::{json, Value};
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    /// Returns a document with the number of retrievals so far, or fails if `fail` is set.
+    #[derive(Clone, Default)]
+    struct CountingRetriever {
+        count: Arc<AtomicUsize>,
+        fail: bool,
+    }
+
+    impl CountingRetriever {
+        fn count(&self) -> usize {
+            self.count.load(Ordering::SeqCst)
+        }
+    }
+
+    impl Retrieve for CountingRetriever {
+        fn retrieve(
+            &self,
+            uri: &Uri<&str>,
+        ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+            if self.fail {
+                return Err(format!("Failed to retrieve {uri}").into());
+            }
+            let count = self.count.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok(json!({"uri": uri.as_str(), "count": count}))
+        }
+    }
+
+    fn retrieve(retriever: &impl Retrieve, uri: &str) -> Result<Value, String> {
+        let uri = uri::from_str(uri).expect("Invalid URI");
+        retriever
+            .retrieve(&uri.borrow())
+            .map_err(|error| error.to_string())
+    }
+
+    #[test]
+    fn cached() {
+        let directory = tempfile::tempdir().expect("Failed to create a directory");
+        let inner = CountingRetriever::default();
+        let retriever = CachingRetriever::new(inner.clone(), directory.path().join("cache"));
+        let a = "https://example.com/a.json";
+        let b = "https://example.com/b.json";
+        assert_eq!(retrieve(&retriever, a), Ok(json!({"uri": a, "count": 1})));
+        assert_eq!(retrieve(&retriever, a), Ok(json!({"uri": a, "count": 1})));
+        assert_eq!(retrieve(&retriever, b), Ok(json!({"uri": b, "count": 2})));
+        // Another retriever with the same directory, like in a later run
+        let retriever = CachingRetriever::new(inner.clone(), directory.path().join("cache"));
+        assert_eq!(retrieve(&retriever, b), Ok(json!({"uri": b, "count": 2})));
+        assert_eq!(inner.count(), 2);
+    }
+
+    #[test]
+    fn concurrent() {
+        let directory = tempfile::tempdir().expect("Failed to create a directory");
+        let uri = "https://example.com/a.json";
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    let retriever =
+                        CachingRetriever::new(CountingRetriever::default(), directory.path());
+                    for _ in 0..20 {
+                        retriever
+                            .store(uri, &json!({"uri": uri}))
+                            .expect("Failed to store");
+                    }
+                });
+            }
+        });
+        let retriever = CachingRetriever::new(CountingRetriever::default(), directory.path())
+            .should_work_offline(true);
+        assert_eq!(retrieve(&retriever, uri), Ok(json!({"uri": uri})));
+        // No temporary files are left behind
+        let files = std::fs::read_dir(directory.path())
+            .expect("Failed to read the directory")
+            .count();
+        assert_eq!(files, 1);
+    }
+
+    #[test]
+    fn expired() {
+        let directory = tempfile::tempdir().expect("Failed to create a directory");
+        let inner = CountingRetriever::default();
+        let uri = "https://example.com/a.json";
+        let retriever = CachingRetriever::new(inner.clone(), directory.path())
+            .with_ttl(Duration::from_secs(3600));
+        assert_eq!(
+            retrieve(&retriever, uri),
+            Ok(json!({"uri": uri, "count": 1}))
+        );
+        assert_eq!(
+            retrieve(&retriever, uri),
+            Ok(json!({"uri": uri, "count": 1}))
+        );
+        let retriever =
+            CachingRetriever::new(inner.clone(), directory.path()).with_ttl(Duration::ZERO);
+        assert_eq!(
+            retrieve(&retriever, uri),
+            Ok(json!({"uri": uri, "count": 2}))
+        );
+        assert_eq!(
+            retrieve(&retriever, uri),
+            Ok(json!({"uri": uri, "count": 3}))
+        );
+    }
+
+    #[test]
+    fn expired_and_failing() {
+        let directory = tempfile::tempdir().expect("Failed to create a directory");
+        let uri = "https://example.com/a.json";
+        let inner = CountingRetriever::default();
+        let retriever = CachingRetriever::new(inner.clone(), directory.path());
+        assert_eq!(
+            retrieve(&retriever, uri),
+            Ok(json!({"uri": uri, "count": 1}))
+        );
+        let failing = CountingRetriever {
+            fail: true,
+            ..CountingRetriever::default()
+        };
+        let retriever = CachingRetriever::new(failing, directory.path()).with_ttl(Duration::ZERO);
+        assert_eq!(
+            retrieve(&retriever, uri),
+            Ok(json!({"uri": uri, "count": 1}))
+        );
+        assert_eq!(
+            retrieve(&retriever, "https://example.com/b.json"),
+            Err("Failed to retrieve https://example.com/b.json".to_string())
+        );
+    }
+
+    #[test]
+    fn offline() {
+        let directory = tempfile::tempdir().expect("Failed to create a directory");
+        let inner = CountingRetriever::default();
+        let cached = "https://example.com/cached.json";
+        retrieve(
+            &CachingRetriever::new(inner.clone(), directory.path()),
+            cached,
+        )
+        .expect("Failed to retrieve");
+        let retriever = CachingRetriever::new(inner.clone(), directory.path())
+            .with_ttl(Duration::ZERO)
+            .should_work_offline(true);
+        assert_eq!(
+            retrieve(&retriever, cached),
+            Ok(json!({"uri": cached, "count": 1}))
+        );
+        assert_eq!(inner.count(), 1);
+
+        let schema = json!({"$ref": "https://example.com/missing.json"});
+        let error = crate::options()
+            .with_retriever(retriever)
+            .build(&schema)
+            .expect_err("Should fail");
+        assert!(matches!(
+            error.kind,
+            crate::error::ValidationErrorKind::Referencing(referencing::Error::Unretrievable { ref uri, .. })
+                if uri == "https://example.com/missing.json"
+        ));
+        assert_eq!(
+            error.to_string(),
+            "Resource 'https://example.com/missing.json' is not present in a registry and retrieving it failed: Resource is not cached and the retriever is in offline mode"
+        );
+    }
+
+    #[test]
+    fn validator() {
+        let directory = tempfile::tempdir().expect("Failed to create a directory");
+        let inner = CountingRetriever::default();
+        let schema = json!({
+            "properties": {
+                "a": {"$ref": "https://example.com/a.json"},
+                "b": {"$ref": "https://example.com/b.json"}
+            }
+        });
+        for _ in 0..2 {
+            crate::options()
+                .with_retriever(CachingRetriever::new(inner.clone(), directory.path()))
+                .build(&schema)
+                .expect("Invalid schema");
+        }
+        assert_eq!(inner.count(), 2);
+        let entries = std::fs::read_dir(directory.path())
+            .expect("Failed to read the directory")
+            .count();
+        assert_eq!(entries, 2);
+    }
+}
@@ -0,0 +1,419 @@
+//! Schema-directed type coercion.
+//!
+//! Values coming from query strings, environment variables or form posts are usually strings,
+//! even when the schema expects numbers or booleans. Coercion rewrites such values in place to
+//! match the `type` keyword before validation, in the same way as Ajv's `coerceTypes` option.
+//!
+//! ```rust
+//! use jsonschema::coercion::TypeCoercion;
+//! use serde_json::json;
+//!
+//! let schema = json!({
+//!     "properties": {
+//!         "limit": {"type": "integer", "maximum": 100},
+//!         "verbose": {"type": "boolean"}
+//!     }
+//! });
+//! let validator = jsonschema::options()
+//!     .with_type_coercion(TypeCoercion::Scalars)
+//!     .build(&schema)
+//!     .expect("Invalid schema");
+//!
+//! let mut instance = json!({"limit": "42", "verbose": "true"});
+//! let coercions = validator.coerce(&mut instance);
+//! assert_eq!(instance, json!({"limit": 42, "verbose": true}));
+//! assert_eq!(coercions.len(), 2);
+//! assert_eq!(coercions[0].instance_path().as_str(), "/limit");
+//! assert_eq!(coercions[0].from(), &json!("42"));
+//! assert!(validator.is_valid(&instance));
+//! ```
+use crate::{
+    node::SchemaNode,
+    paths::Location,
+    primitive_type::{PrimitiveType, PrimitiveTypesBitMap},
+    validator::Validate,
+};
+use serde_json::{Number, Value};
+
+/// Which coercions are performed by [`crate::Validator::coerce`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeCoercion {
+    /// Convert between strings, numbers, booleans and `null`:
+    ///
+    /// - to `string`: numbers, `true` / `false`, and `null` as `""`;
+    /// - to `number`: strings holding a JSON number, `true` / `false` as `1` / `0`, and `null` as `0`;
+    /// - to `integer`: the same as `number`, as long as the result is an integer;
+    /// - to `boolean`: `"true"` / `"false"`, `1` / `0`, and `null` as `false`;
+    /// - to `null`: `""`, `0` and `false`.
+    Scalars,
+    /// Everything [`TypeCoercion::Scalars`] does, plus wrapping scalars into single-element
+    /// arrays when `type` is `array`, and unwrapping single-element arrays otherwise.
+    Arrays,
+}
+
+/// A single rewrite performed during coercion.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Coercion {
+    instance_path: Location,
+    from: Value,
+    to: Value,
+}
+
+impl Coercion {
+    /// Location of the coerced value within the instance.
+    #[must_use]
+    pub fn instance_path(&self) -> &Location {
+        &self.instance_path
+    }
+    /// The value before coercion.
+    #[must_use]
+    pub fn from(&self) -> &Value {
+        &self.from
+    }
+    /// The value after coercion.
+    #[must_use]
+    pub fn to(&self) -> &Value {
+        &self.to
+    }
+}
+
+/// State shared by validators during a single coercion pass.
+pub(crate) struct Coercer {
+    mode: TypeCoercion,
+    coercions: Vec<Coercion>,
+}
+
+impl Coercer {
+    pub(crate) fn new(mode: TypeCoercion) -> Self {
+        Coercer {
+            mode,
+            coercions: Vec::new(),
+        }
+    }
+
+    pub(crate) fn into_coercions(self) -> Vec<Coercion> {
+        self.coercions
+    }
+
+    /// Rewrite `instance` to one of `types` unless it already has one of them.
+    ///
+    /// Target types are tried in the order `string`, `number`, `integer`, `boolean`, `null`,
+    /// `array`, and the instance is left intact if none of them fits.
+    pub(crate) fn coerce_to(
+        &mut self,
+        instance: &mut Value,
+        location: &Location,
+        types: PrimitiveTypesBitMap,
+    ) {
+        if has_type(instance, types) {
+            return;
+        }
+        let mut candidate = &*instance;
+        let mut coerced = None;
+        if self.mode == TypeCoercion::Arrays && !types.contains_type(PrimitiveType::Array) {
+            if let Value::Array(items) = candidate {
+                if let [item] = items.as_slice() {
+                    candidate = item;
+                    if has_type(candidate, types) {
+                        coerced = Some(candidate.clone());
+                    }
+                }
+            }
+        }
+        let coerced = coerced.or_else(|| {
+            [
+                PrimitiveType::String,
+                PrimitiveType::Number,
+                PrimitiveType::Integer,
+                PrimitiveType::Boolean,
+                PrimitiveType::Null,
+                PrimitiveType::Array,
+            ]
+            .into_iter()
+            .filter(|target| types.contains_type(*target))
+            .find_map(|target| self.convert(candidate, target))
+        });
+        if let Some(coerced) = coerced {
+            let from = std::mem::replace(instance, coerced);
+            self.coercions.push(Coercion {
+                instance_path: location.clone(),
+                from,
+                to: instance.clone(),
+            });
+        }
+    }
+
+    /// Coerce `instance` with `node` and keep the result only if `node` accepts it.
+    pub(crate) fn try_coerce(
+        &mut self,
+        node: &SchemaNode,
+        instance: &mut Value,
+        location: &Location,
+    ) -> bool {
+        let mut candidate = instance.clone();
+        let mut inner = Coercer::new(self.mode);
+        node.coerce(&mut candidate, location, &mut inner);
+        if node.is_valid(&candidate) {
+            *instance = candidate;
+            self.coercions.extend(inner.coercions);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Coerce each property of `instance` with the subschemas that apply to it.
+    pub(crate) fn coerce_properties<'a, F>(
+        &mut self,
+        instance: &mut Value,
+        location: &Location,
+        subschemas: F,
+    ) where
+        F: Fn(&str) -> Vec<&'a SchemaNode>,
+    {
+        if let Value::Object(object) = instance {
+            for (property, value) in object.iter_mut() {
+                let nodes = subschemas(property);
+                if !nodes.is_empty() {
+                    let location = location.join(property);
+                    for node in nodes {
+                        node.coerce(value, &location, self);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Coerce each item of `instance` with the subschema that applies to its index.
+    pub(crate) fn coerce_items<'a, F>(
+        &mut self,
+        instance: &mut Value,
+        location: &Location,
+        subschema: F,
+    ) where
+        F: Fn(usize) -> Option<&'a SchemaNode>,
+    {
+        if let Value::Array(items) = instance {
+            for (idx, item) in items.iter_mut().enumerate() {
+                if let Some(node) = subschema(idx) {
+                    node.coerce(item, &location.join(idx), self);
+                }
+            }
+        }
+    }
+
+    fn convert(&self, value: &Value, target: PrimitiveType) -> Option<Value> {
+        match (target, value) {
+            (PrimitiveType::String, Value::Number(number)) => {
+                Some(Value::String(number.to_string()))
+            }
+            (PrimitiveType::String, Value::Bool(boolean)) => {
+                Some(Value::String(boolean.to_string()))
+            }
+            (PrimitiveType::String, Value::Null) => Some(Value::String(String::new())),
+            (PrimitiveType::Number | PrimitiveType::Integer, Value::String(string)) => {
+                let number = string.parse::<Number>().ok()?;
+                if target == PrimitiveType::Integer && !is_integer(&number) {
+                    None
+                } else {
+                    Some(Value::Number(number))
+                }
+            }
+            (PrimitiveType::Number | PrimitiveType::Integer, Value::Bool(boolean)) => {
+                Some(Value::from(u8::from(*boolean)))
+            }
+            (PrimitiveType::Number | PrimitiveType::Integer, Value::Null) => Some(Value::from(0)),
+            (PrimitiveType::Boolean, Value::String(string)) => match string.as_str() {
+                "true" => Some(Value::Bool(true)),
+                "false" => Some(Value::Bool(false)),
+                _ => None,
+            },
+            (PrimitiveType::Boolean, Value::Number(number)) => {
+                let value = number.as_f64()?;
+                if value == 1. {
+                    Some(Value::Bool(true))
+                } else if value == 0. {
+                    Some(Value::Bool(false))
+                } else {
+                    None
+                }
+            }
+            (PrimitiveType::Boolean, Value::Null) => Some(Value::Bool(false)),
+            (PrimitiveType::Null, Value::String(string)) if string.is_empty() => Some(Value::Null),
+            (PrimitiveType::Null, Value::Number(number)) if number.as_f64() == Some(0.) => {
+                Some(Value::Null)
+            }
+            (PrimitiveType::Null, Value::Bool(false)) => Some(Value::Null),
+            (
+                PrimitiveType::Array,
+                Value::String(_) | Value::Number(_) | Value::Bool(_) | Value::Null,
+            ) if self.mode == TypeCoercion::Arrays => Some(Value::Array(vec![value.clone()])),
+            _ => None,
+        }
+    }
+}
+
+fn has_type(instance: &Value, types: PrimitiveTypesBitMap) -> bool {
+    match instance {
+        Value::Number(number) => {
+            types.contains_type(PrimitiveType::Number)
+                || (types.contains_type(PrimitiveType::Integer) && is_integer(number))
+        }
+        _ => types.contains_type(PrimitiveType::from(instance)),
+    }
+}
+
+fn is_integer(number: &Number) -> bool {
+    number.is_u64() || number.is_i64() || number.as_f64().expect("Always valid").fract() == 0.
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TypeCoercion;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    fn coerce(schema: &Value, mut instance: Value, mode: TypeCoercion) -> (Value, Vec<String>) {
+        let validator = crate::options()
+            .with_type_coercion(mode)
+            .build(schema)
+            .expect("Invalid schema");
+        let paths = validator
+            .coerce(&mut instance)
+            .iter()
+            .map(|coercion| coercion.instance_path().to_string())
+            .collect();
+        (instance, paths)
+    }
+
+    #[test_case(&json!({"type": "integer"}), json!("42"), &json!(42); "string to integer")]
+    #[test_case(&json!({"type": "integer"}), json!("4.2"), &json!("4.2"); "fractional string to integer")]
+    #[test_case(&json!({"type": "number"}), json!("-4.2e1"), &json!(-42.0); "string to number")]
+    #[test_case(&json!({"type": "number"}), json!(" 42"), &json!(" 42"); "padded string to number")]
+    #[test_case(&json!({"type": "number"}), json!(true), &json!(1); "boolean to number")]
+    #[test_case(&json!({"type": "integer"}), json!(null), &json!(0); "null to integer")]
+    #[test_case(&json!({"type": "string"}), json!(4.5), &json!("4.5"); "number to string")]
+    #[test_case(&json!({"type": "string"}), json!(false), &json!("false"); "boolean to string")]
+    #[test_case(&json!({"type": "string"}), json!(null), &json!(""); "null to string")]
+    #[test_case(&json!({"type": "boolean"}), json!("true"), &json!(true); "string to boolean")]
+    #[test_case(&json!({"type": "boolean"}), json!("yes"), &json!("yes"); "unknown string to boolean")]
+    #[test_case(&json!({"type": "boolean"}), json!(0), &json!(false); "number to boolean")]
+    #[test_case(&json!({"type": "null"}), json!(""), &json!(null); "empty string to null")]
+    #[test_case(&json!({"type": "null"}), json!(false), &json!(null); "false to null")]
+    #[test_case(&json!({"type": ["boolean", "integer"]}), json!("1"), &json!(1); "number goes before boolean")]
+    #[test_case(&json!({"type": ["string", "integer"]}), json!(1), &json!(1); "already matching")]
+    #[test_case(&json!({"type": "array"}), json!("a"), &json!("a"); "no wrapping")]
+    #[test_case(&json!({"type": "object"}), json!("{}"), &json!("{}"); "object")]
+    fn scalars(schema: &Value, instance: Value, expected: &Value) {
+        let (instance, _) = coerce(schema, instance, TypeCoercion::Scalars);
+        assert_eq!(&instance, expected);
+    }
+
+    #[test_case(&json!({"type": "array"}), json!(1), &json!([1]); "wrap")]
+    #[test_case(&json!({"type": "integer"}), json!([1]), &json!(1); "unwrap")]
+    #[test_case(&json!({"type": "integer"}), json!(["1"]), &json!(1); "unwrap and convert")]
+    #[test_case(&json!({"type": "integer"}), json!([1, 2]), &json!([1, 2]); "no unwrapping of longer arrays")]
+    #[test_case(&json!({"type": "array", "items": {"type": "boolean"}}), json!("true"), &json!([true]); "wrap before items")]
+    fn arrays(schema: &Value, instance: Value, expected: &Value) {
+        let (instance, _) = coerce(schema, instance, TypeCoercion::Arrays);
+        assert_eq!(&instance, expected);
+    }
+
+    #[test_case(
+        &json!({"properties": {"a": {"type": "integer"}}, "patternProperties": {"^b": {"type": "boolean"}}, "additionalProperties": {"type": "null"}}),
+        json!({"a": "1", "bb": "false", "c": ""}),
+        &json!({"a": 1, "bb": false, "c": null}),
+        &["/a", "/bb", "/c"];
+        "object applicators"
+    )]
+    #[test_case(
+        &json!({"prefixItems": [{"type": "integer"}], "items": {"type": "boolean"}}),
+        json!(["1", "true", 1]),
+        &json!([1, true, true]),
+        &["/0", "/1", "/2"];
+        "array applicators"
+    )]
+    #[test_case(
+        &json!({"anyOf": [{"type": "integer", "minimum": 10}, {"type": "number"}]}),
+        json!("4.5"),
+        &json!(4.5),
+        &[""];
+        "first accepting branch"
+    )]
+    #[test_case(
+        &json!({"oneOf": [{"type": "null"}, {"type": "boolean"}]}),
+        json!("x"),
+        &json!("x"),
+        &[];
+        "no accepting branch"
+    )]
+    #[test_case(
+        &json!({"if": {"properties": {"kind": {"type": "integer"}}}, "then": {"properties": {"value": {"type": "integer"}}}, "else": {"properties": {"value": {"type": "string"}}}}),
+        json!({"kind": "1", "value": "2"}),
+        &json!({"kind": 1, "value": 2}),
+        &["/kind", "/value"];
+        "if then"
+    )]
+    #[test_case(
+        &json!({"if": {"properties": {"kind": {"type": "integer"}}}, "then": {"properties": {"value": {"type": "integer"}}}, "else": {"properties": {"value": {"type": "string"}}}}),
+        json!({"kind": "x", "value": 2}),
+        &json!({"kind": "x", "value": "2"}),
+        &["/value"];
+        "if else"
+    )]
+    #[test_case(
+        &json!({"$defs": {"port": {"type": "integer"}}, "properties": {"port": {"$ref": "#/$defs/port"}}}),
+        json!({"port": "80"}),
+        &json!({"port": 80}),
+        &["/port"];
+        "reference"
+    )]
+    fn applicators(schema: &Value, instance: Value, expected: &Value, paths: &[&str]) {
+        let (instance, mut coerced) = coerce(schema, instance, TypeCoercion::Scalars);
+        assert_eq!(&instance, expected);
+        coerced.sort();
+        assert_eq!(coerced, paths);
+    }
+
+    #[test]
+    fn disabled() {
+        let validator = crate::validator_for(&json!({"type": "integer"})).expect("Invalid schema");
+        let mut instance = json!("1");
+        assert!(validator.coerce(&mut instance).is_empty());
+        assert_eq!(instance, json!("1"));
+    }
+
+    #[test]
+    fn validate_coerced() {
+        let schema = json!({"properties": {"a": {"type": "integer"}}});
+        let validator = crate::options()
+            .with_type_coercion(TypeCoercion::Scalars)
+            .build(&schema)
+            .expect("Invalid schema");
+        let mut instance = json!({"a": "1"});
+        let coercions = validator
+            .validate_coerced(&mut instance)
+            .expect("Coerced instance is valid");
+        assert_eq!(coercions.len(), 1);
+        assert_eq!(instance, json!({"a": 1}));
+        let mut instance = json!({"a": "x"});
+        assert!(validator.validate_coerced(&mut instance).is_err());
+        let validator = crate::validator_for(&schema).expect("Invalid schema");
+        let mut instance = json!({"a": "1"});
+        assert!(validator.validate_coerced(&mut instance).is_err());
+        assert_eq!(instance, json!({"a": "1"}));
+    }
+
+    #[test]
+    fn reported_values() {
+        let validator = crate::options()
+            .with_type_coercion(TypeCoercion::Scalars)
+            .build(&json!({"type": "boolean"}))
+            .expect("Invalid schema");
+        let mut instance = json!("false");
+        let coercions = validator.coerce(&mut instance);
+        assert_eq!(coercions.len(), 1);
+        assert_eq!(coercions[0].from(), &json!("false"));
+        assert_eq!(coercions[0].to(), &json!(false));
+    }
+}
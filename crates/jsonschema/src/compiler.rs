@@ -1,6 +1,7 @@
 use crate::{
     content_encoding::{ContentEncodingCheckType, ContentEncodingConverterType},
     content_media_type::ContentMediaTypeCheckType,
+    error_message,
     keywords::{
         self,
         custom::{CustomKeyword, KeywordFactory},
@@ -247,7 +248,60 @@ pub(crate) fn build_validator(
     schema: &Value,
 ) -> Result<Validator, ValidationError<'static>> {
     let draft = config.draft_for(schema)?;
-    let resource_ref = draft.create_resource_ref(schema);
+    let (base_uri, resources) = prepare_resources(&mut config, draft, schema);
+
+    // Get retriever for external resources
+    let retriever = config.retriever();
+
+    // Build a registry & resolver needed for validator compilation
+    let registry = Arc::new(base_registry(&config).try_with_resources_and_policy(
+        resources.into_iter(),
+        &*retriever,
+        &config.retrieval_policy,
+        draft,
+    )?);
+    finish_build(config, registry, &base_uri, draft, schema)
+}
+
+#[cfg(feature = "resolve-async")]
+pub(crate) async fn build_validator_async(
+    mut config: ValidationOptions,
+    schema: &Value,
+) -> Result<Validator, ValidationError<'static>> {
+    let draft = config.draft_for_async(schema).await?;
+    let (base_uri, resources) = prepare_resources(&mut config, draft, schema);
+
+    let retriever = config.async_retriever();
+
+    let registry = Arc::new(
+        base_registry(&config)
+            .try_with_resources_and_policy_async(
+                resources.into_iter(),
+                &*retriever,
+                &config.retrieval_policy,
+                draft,
+            )
+            .await?,
+    );
+    finish_build(config, registry, &base_uri, draft, schema)
+}
+
+/// The registry to add the schema and its resources to.
+///
+/// A shared registry is only referenced, so its resources are neither copied nor retrieved again.
+fn base_registry(config: &ValidationOptions) -> Registry {
+    match &config.registry {
+        Some(shared) => SPECIFICATIONS.clone().with_parent(Arc::clone(shared)),
+        None => SPECIFICATIONS.clone(),
+    }
+}
+
+/// The base URI of `schema` and all resources to put in the registry.
+fn prepare_resources(
+    config: &mut ValidationOptions,
+    draft: Draft,
+    schema: &Value,
+) -> (String, Vec<(String, Resource)>) {
     let resource = draft.create_resource(schema.clone());
     let base_uri = resource.id().unwrap_or(DEFAULT_ROOT_URL).to_string();
 
@@ -257,18 +311,19 @@ pub(crate) fn build_validator(
     for (uri, resource) in config.resources.drain() {
         resources.push((uri, resource));
     }
+    (base_uri, resources)
+}
 
-    // Get retriever for external resources
-    let retriever = Arc::clone(&config.retriever);
-
-    // Build a registry & resolver needed for validator compilation
-    let registry = Arc::new(SPECIFICATIONS.clone().try_with_resources_and_retriever(
-        resources.into_iter(),
-        &*retriever,
-        draft,
-    )?);
+fn finish_build(
+    config: ValidationOptions,
+    registry: Arc<Registry>,
+    base_uri: &str,
+    draft: Draft,
+    schema: &Value,
+) -> Result<Validator, ValidationError<'static>> {
+    let resource_ref = draft.create_resource_ref(schema);
     let vocabularies = registry.find_vocabularies(draft, schema);
-    let resolver = Rc::new(registry.try_resolver(&base_uri)?);
+    let resolver = Rc::new(registry.try_resolver(base_uri)?);
 
     let config = Arc::new(config);
     let ctx = Context::new(
@@ -280,9 +335,10 @@ pub(crate) fn build_validator(
         Location::new(),
     );
 
-    // Validate the schema itself
+    // Validate the schema itself. The first error of `iter_errors` carries the errors of
+    // `anyOf` / `oneOf` branches, which `best_match` looks into
     if config.validate_schema {
-        if let Err(error) = {
+        if let Some(error) = {
             match draft {
                 Draft::Draft4 => &crate::draft4::meta::VALIDATOR,
                 Draft::Draft6 => &crate::draft6::meta::VALIDATOR,
@@ -292,7 +348,8 @@ pub(crate) fn build_validator(
                 _ => unreachable!("Unknown draft"),
             }
         }
-        .validate(schema)
+        .iter_errors(schema)
+        .next()
         {
             return Err(error.to_owned());
         }
@@ -353,6 +410,7 @@ pub(crate) fn compile_with<'a>(
                             ctx,
                             validators,
                             Some(annotations),
+                            None,
                         ))
                     } else {
                         // Infinite reference to the same location
@@ -384,7 +442,13 @@ pub(crate) fn compile_with<'a>(
             } else {
                 Some(annotations)
             };
-            Ok(SchemaNode::from_keywords(ctx, validators, annotations))
+            let error_message = error_message::compile(ctx, schema)?;
+            Ok(SchemaNode::from_keywords(
+                ctx,
+                validators,
+                annotations,
+                error_message,
+            ))
         }
         _ => Err(ValidationError::multiple_type_error(
             Location::new(),
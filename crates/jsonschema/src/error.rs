@@ -35,9 +35,31 @@
 //! ```text
 //! value is longer than 5 characters
 //! ```
+//!
+//! ## Serialization
+//!
+//! With the `serde` feature, [`ValidationError`] and [`ValidationErrorKind`] implement
+//! `Serialize` and `Deserialize`. The kind is stored in the `kind` field, next to its own fields,
+//! the `instance` and the `instance_path` / `schema_path` JSON pointers:
+//!
+//! ```json
+//! {
+//!     "kind": "max_length",
+//!     "limit": 5,
+//!     "instance": "sensitive data",
+//!     "instance_path": "",
+//!     "schema_path": "/maxLength"
+//! }
+//! ```
+//!
+//! Errors from `referencing` are nested under `error` with their own `kind`, and the source of an
+//! unretrievable resource error is kept only as its message.
+pub use crate::error_tree::{best_match, ErrorTree};
 use crate::{
+    error_message,
     paths::Location,
     primitive_type::{PrimitiveType, PrimitiveTypesBitMap},
+    suggestions,
 };
 use serde_json::{Map, Number, Value};
 use std::{
@@ -50,10 +72,12 @@ use std::{
 
 /// An error that can occur during validation.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ValidationError<'a> {
     /// Value of the property that failed validation.
     pub instance: Cow<'a, Value>,
     /// Type of validation error.
+    #[cfg_attr(feature = "serde", serde(flatten))]
     pub kind: ValidationErrorKind,
     /// Path to the value that failed validation.
     pub instance_path: Location,
@@ -91,28 +115,74 @@ pub(crate) fn error(instance: ValidationError) -> ErrorIterator {
 
 /// Kinds of errors that may happen during validation
 #[derive(Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "kind", rename_all = "snake_case")
+)]
 #[allow(missing_docs)]
 pub enum ValidationErrorKind {
     /// The input array contain more items than expected.
     AdditionalItems { limit: usize },
     /// Unexpected properties.
-    AdditionalProperties { unexpected: Vec<String> },
+    AdditionalProperties {
+        unexpected: Vec<String>,
+        /// Defined properties that the unexpected ones might be misspellings of.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Vec::is_empty")
+        )]
+        suggestions: Vec<String>,
+    },
     /// The input value is not valid under any of the schemas listed in the 'anyOf' keyword.
-    AnyOf,
+    AnyOf {
+        /// Errors from each of the 'anyOf' schemas, in the same order. Only collected by
+        /// `iter_errors`, empty from `validate`.
+        context: Vec<Vec<ValidationError<'static>>>,
+    },
     /// Results from a [`fancy_regex::RuntimeError::BacktrackLimitExceeded`] variant when matching
-    BacktrackLimitExceeded { error: fancy_regex::Error },
+    BacktrackLimitExceeded {
+        #[cfg_attr(feature = "serde", serde(with = "crate::error_serde::regex_error"))]
+        error: fancy_regex::Error,
+    },
     /// The input value doesn't match expected constant.
-    Constant { expected_value: Value },
+    Constant {
+        expected_value: Value,
+        /// The expected value, if the input value might be a misspelling of it.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Vec::is_empty")
+        )]
+        suggestions: Vec<String>,
+    },
     /// The input array doesn't contain items conforming to the specified schema.
-    Contains,
+    Contains {
+        /// Errors from each array item, in the same order. Only collected by `iter_errors`, empty
+        /// from `validate` or if there are too many matching items.
+        context: Vec<Vec<ValidationError<'static>>>,
+    },
     /// The input value does not respect the defined contentEncoding
     ContentEncoding { content_encoding: String },
     /// The input value does not respect the defined contentMediaType
     ContentMediaType { content_media_type: String },
     /// Custom error message for user-defined validation.
     Custom { message: String },
+    /// Message from the `errorMessage` keyword replacing the message for `kind`.
+    ErrorMessage {
+        message: String,
+        #[cfg_attr(feature = "serde", serde(rename = "inner"))]
+        kind: Box<ValidationErrorKind>,
+    },
     /// The input value doesn't match any of specified options.
-    Enum { options: Value },
+    Enum {
+        options: Value,
+        /// Options that the input value might be a misspelling of.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Vec::is_empty")
+        )]
+        suggestions: Vec<String>,
+    },
     /// Value is too large.
     ExclusiveMaximum { limit: Value },
     /// Value is too small.
@@ -122,7 +192,13 @@ pub enum ValidationErrorKind {
     /// When the input doesn't match to the specified format.
     Format { format: String },
     /// May happen in `contentEncoding` validation if `base64` encoded data is invalid.
-    FromUtf8 { error: FromUtf8Error },
+    FromUtf8 {
+        #[cfg_attr(
+            feature = "serde",
+            serde(rename = "bytes", with = "crate::error_serde::from_utf8_error")
+        )]
+        error: FromUtf8Error,
+    },
     /// Too many items in an array.
     MaxItems { limit: u64 },
     /// Value is too large.
@@ -144,9 +220,19 @@ pub enum ValidationErrorKind {
     /// Negated schema failed validation.
     Not { schema: Value },
     /// The given schema is valid under more than one of the schemas listed in the 'oneOf' keyword.
-    OneOfMultipleValid,
+    OneOfMultipleValid {
+        /// Errors from each of the 'oneOf' schemas, in the same order. Only collected by
+        /// `iter_errors`, empty from `validate`.
+        context: Vec<Vec<ValidationError<'static>>>,
+        /// Indices of the 'oneOf' schemas the instance is valid under.
+        matched: Vec<usize>,
+    },
     /// The given schema is not valid under any of the schemas listed in the 'oneOf' keyword.
-    OneOfNotValid,
+    OneOfNotValid {
+        /// Errors from each of the 'oneOf' schemas, in the same order. Only collected by
+        /// `iter_errors`, empty from `validate`.
+        context: Vec<Vec<ValidationError<'static>>>,
+    },
     /// When the input doesn't match to a pattern.
     Pattern { pattern: String },
     /// Object property names are invalid.
@@ -154,9 +240,20 @@ pub enum ValidationErrorKind {
         error: Box<ValidationError<'static>>,
     },
     /// When a required property is missing.
-    Required { property: Value },
+    Required {
+        property: Value,
+        /// Properties of the input value that might be misspellings of the required one.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Vec::is_empty")
+        )]
+        suggestions: Vec<String>,
+    },
     /// When the input value doesn't match one or multiple required types.
-    Type { kind: TypeKind },
+    Type {
+        #[cfg_attr(feature = "serde", serde(rename = "types"))]
+        kind: TypeKind,
+    },
     /// Unexpected items.
     UnevaluatedItems { unexpected: Vec<String> },
     /// Unexpected properties.
@@ -164,10 +261,21 @@ pub enum ValidationErrorKind {
     /// When the input array has non-unique elements.
     UniqueItems,
     /// Error during schema ref resolution.
-    Referencing(referencing::Error),
+    Referencing(
+        #[cfg_attr(
+            feature = "serde",
+            serde(with = "crate::error_serde::referencing_error")
+        )]
+        referencing::Error,
+    ),
 }
 
 #[derive(Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(untagged)
+)]
 #[allow(missing_docs)]
 pub enum TypeKind {
     Single(PrimitiveType),
@@ -220,11 +328,15 @@ impl<'a> ValidationError<'a> {
         instance_path: Location,
         instance: &'a Value,
         unexpected: Vec<String>,
+        suggestions: Vec<String>,
     ) -> ValidationError<'a> {
         ValidationError {
             instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::AdditionalProperties { unexpected },
+            kind: ValidationErrorKind::AdditionalProperties {
+                unexpected,
+                suggestions,
+            },
             schema_path: location,
         }
     }
@@ -232,11 +344,12 @@ impl<'a> ValidationError<'a> {
         location: Location,
         instance_path: Location,
         instance: &'a Value,
+        context: Vec<Vec<ValidationError<'static>>>,
     ) -> ValidationError<'a> {
         ValidationError {
             instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::AnyOf,
+            kind: ValidationErrorKind::AnyOf { context },
             schema_path: location,
         }
     }
@@ -264,6 +377,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Constant {
                 expected_value: Value::Array(expected_value.to_vec()),
+                suggestions: Vec::new(),
             },
             schema_path: location,
         }
@@ -279,6 +393,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Constant {
                 expected_value: Value::Bool(expected_value),
+                suggestions: Vec::new(),
             },
             schema_path: location,
         }
@@ -293,6 +408,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Constant {
                 expected_value: Value::Null,
+                suggestions: Vec::new(),
             },
             schema_path: location,
         }
@@ -308,6 +424,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Constant {
                 expected_value: Value::Number(expected_value.clone()),
+                suggestions: Vec::new(),
             },
             schema_path: location,
         }
@@ -323,6 +440,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Constant {
                 expected_value: Value::Object(expected_value.clone()),
+                suggestions: Vec::new(),
             },
             schema_path: location,
         }
@@ -332,12 +450,14 @@ impl<'a> ValidationError<'a> {
         instance_path: Location,
         instance: &'a Value,
         expected_value: &str,
+        suggestions: Vec<String>,
     ) -> ValidationError<'a> {
         ValidationError {
             instance_path,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Constant {
                 expected_value: Value::String(expected_value.to_string()),
+                suggestions,
             },
             schema_path: location,
         }
@@ -346,11 +466,12 @@ impl<'a> ValidationError<'a> {
         location: Location,
         instance_path: Location,
         instance: &'a Value,
+        context: Vec<Vec<ValidationError<'static>>>,
     ) -> ValidationError<'a> {
         ValidationError {
             instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::Contains,
+            kind: ValidationErrorKind::Contains { context },
             schema_path: location,
         }
     }
@@ -389,12 +510,14 @@ impl<'a> ValidationError<'a> {
         instance_path: Location,
         instance: &'a Value,
         options: &Value,
+        suggestions: Vec<String>,
     ) -> ValidationError<'a> {
         ValidationError {
             instance_path,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Enum {
                 options: options.clone(),
+                suggestions,
             },
             schema_path: location,
         }
@@ -594,11 +717,13 @@ impl<'a> ValidationError<'a> {
         location: Location,
         instance_path: Location,
         instance: &'a Value,
+        context: Vec<Vec<ValidationError<'static>>>,
+        matched: Vec<usize>,
     ) -> ValidationError<'a> {
         ValidationError {
             instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::OneOfMultipleValid,
+            kind: ValidationErrorKind::OneOfMultipleValid { context, matched },
             schema_path: location,
         }
     }
@@ -606,11 +731,12 @@ impl<'a> ValidationError<'a> {
         location: Location,
         instance_path: Location,
         instance: &'a Value,
+        context: Vec<Vec<ValidationError<'static>>>,
     ) -> ValidationError<'a> {
         ValidationError {
             instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::OneOfNotValid,
+            kind: ValidationErrorKind::OneOfNotValid { context },
             schema_path: location,
         }
     }
@@ -647,11 +773,15 @@ impl<'a> ValidationError<'a> {
         instance_path: Location,
         instance: &'a Value,
         property: Value,
+        suggestions: Vec<String>,
     ) -> ValidationError<'a> {
         ValidationError {
             instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::Required { property },
+            kind: ValidationErrorKind::Required {
+                property,
+                suggestions,
+            },
             schema_path: location,
         }
     }
@@ -761,6 +891,11 @@ impl From<FromUtf8Error> for ValidationError<'_> {
     }
 }
 
+fn write_json_string(f: &mut Formatter<'_>, value: &String) -> fmt::Result {
+    // Strings are escaped the same way as in JSON values
+    f.write_str(&serde_json::to_string(value).map_err(|_| fmt::Error)?)
+}
+
 fn write_quoted_list(f: &mut Formatter<'_>, items: &[impl fmt::Display]) -> fmt::Result {
     let mut iter = items.iter();
     if let Some(item) = iter.next() {
@@ -810,27 +945,34 @@ impl fmt::Display for ValidationError<'_> {
 
                 write_unexpected_suffix(f, array.len() - limit)
             }
-            ValidationErrorKind::AdditionalProperties { unexpected } => {
+            ValidationErrorKind::AdditionalProperties {
+                unexpected,
+                suggestions,
+            } => {
                 f.write_str("Additional properties are not allowed (")?;
                 write_quoted_list(f, unexpected)?;
-                write_unexpected_suffix(f, unexpected.len())
+                write_unexpected_suffix(f, unexpected.len())?;
+                suggestions::write(f, ". Did you mean ", suggestions, "?", |f, suggestion| {
+                    write!(f, "'{suggestion}'")
+                })
             }
-            ValidationErrorKind::AnyOf => write!(
+            ValidationErrorKind::AnyOf { .. } => write!(
                 f,
                 "{} is not valid under any of the schemas listed in the 'anyOf' keyword",
                 self.instance
             ),
-            ValidationErrorKind::OneOfNotValid => write!(
+            ValidationErrorKind::OneOfNotValid { .. } => write!(
                 f,
                 "{} is not valid under any of the schemas listed in the 'oneOf' keyword",
                 self.instance
             ),
-            ValidationErrorKind::Contains => write!(
+            ValidationErrorKind::Contains { .. } => write!(
                 f,
                 "None of {} are valid under the given schema",
                 self.instance
             ),
-            ValidationErrorKind::Constant { expected_value } => {
+            // The only possible suggestion is the expected value, which is already shown
+            ValidationErrorKind::Constant { expected_value, .. } => {
                 write!(f, "{} was expected", expected_value)
             }
             ValidationErrorKind::ContentEncoding { content_encoding } => {
@@ -848,8 +990,12 @@ impl fmt::Display for ValidationError<'_> {
                 )
             }
             ValidationErrorKind::FromUtf8 { error } => error.fmt(f),
-            ValidationErrorKind::Enum { options } => {
-                write!(f, "{} is not one of {}", self.instance, options)
+            ValidationErrorKind::Enum {
+                options,
+                suggestions,
+            } => {
+                write!(f, "{} is not one of {}", self.instance, options)?;
+                suggestions::write(f, ". Did you mean ", suggestions, "?", write_json_string)
             }
             ValidationErrorKind::ExclusiveMaximum { limit } => write!(
                 f,
@@ -917,7 +1063,7 @@ impl fmt::Display for ValidationError<'_> {
             ValidationErrorKind::Not { schema } => {
                 write!(f, "{} is not allowed for {}", schema, self.instance)
             }
-            ValidationErrorKind::OneOfMultipleValid => write!(
+            ValidationErrorKind::OneOfMultipleValid { .. } => write!(
                 f,
                 "{} is valid under more than one of the schemas listed in the 'oneOf' keyword",
                 self.instance
@@ -926,8 +1072,12 @@ impl fmt::Display for ValidationError<'_> {
                 write!(f, r#"{} does not match "{}""#, self.instance, pattern)
             }
             ValidationErrorKind::PropertyNames { error } => error.fmt(f),
-            ValidationErrorKind::Required { property } => {
-                write!(f, "{} is a required property", property)
+            ValidationErrorKind::Required {
+                property,
+                suggestions,
+            } => {
+                write!(f, "{} is a required property", property)?;
+                suggestions::write(f, ". Is ", suggestions, " misspelled?", write_json_string)
             }
             ValidationErrorKind::MultipleOf { multiple_of } => {
                 write!(f, "{} is not a multiple of {}", self.instance, multiple_of)
@@ -967,10 +1117,85 @@ impl fmt::Display for ValidationError<'_> {
                 Ok(())
             }
             ValidationErrorKind::Custom { message } => f.write_str(message),
+            ValidationErrorKind::ErrorMessage { message, .. } => {
+                error_message::render(f, message, Ok(&self.instance))
+            }
+        }
+    }
+}
+
+/// An error that can occur when validating a document read from [`std::io::Read`].
+#[derive(Debug)]
+pub enum ReaderError {
+    /// The document could not be read or is not valid JSON.
+    Json(serde_json::Error),
+    /// The document is not valid under the schema.
+    Validation(ValidationError<'static>),
+}
+
+impl error::Error for ReaderError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            ReaderError::Json(error) => Some(error),
+            ReaderError::Validation(error) => Some(error),
+        }
+    }
+}
+
+impl fmt::Display for ReaderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ReaderError::Json(error) => error.fmt(f),
+            ReaderError::Validation(error) => error.fmt(f),
+        }
+    }
+}
+
+impl From<serde_json::Error> for ReaderError {
+    fn from(error: serde_json::Error) -> Self {
+        ReaderError::Json(error)
+    }
+}
+
+impl From<ValidationError<'static>> for ReaderError {
+    fn from(error: ValidationError<'static>) -> Self {
+        ReaderError::Validation(error)
+    }
+}
+
+/// An error that can occur when validating a [`serde::Serialize`] value.
+#[derive(Debug)]
+pub enum SerializeError {
+    /// The value can not be represented as JSON, e.g. a map has non-string keys.
+    Serialize(serde_json::Error),
+    /// The value is not valid under the schema.
+    Validation(ValidationError<'static>),
+}
+
+impl error::Error for SerializeError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            SerializeError::Serialize(error) => Some(error),
+            SerializeError::Validation(error) => Some(error),
+        }
+    }
+}
+
+impl fmt::Display for SerializeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SerializeError::Serialize(error) => error.fmt(f),
+            SerializeError::Validation(error) => error.fmt(f),
         }
     }
 }
 
+impl From<ValidationError<'static>> for SerializeError {
+    fn from(error: ValidationError<'static>) -> Self {
+        SerializeError::Validation(error)
+    }
+}
+
 /// A wrapper that provides a masked display of validation errors.
 pub struct MaskedValidationError<'a, 'b, 'c> {
     error: &'b ValidationError<'a>,
@@ -989,27 +1214,34 @@ impl fmt::Display for MaskedValidationError<'_, '_, '_> {
             ValidationErrorKind::AdditionalItems { limit } => {
                 write!(f, "Additional items are not allowed ({limit} items)")
             }
-            ValidationErrorKind::AdditionalProperties { unexpected } => {
+            ValidationErrorKind::AdditionalProperties {
+                unexpected,
+                suggestions,
+            } => {
                 f.write_str("Additional properties are not allowed (")?;
                 write_quoted_list(f, unexpected)?;
-                write_unexpected_suffix(f, unexpected.len())
+                write_unexpected_suffix(f, unexpected.len())?;
+                suggestions::write(f, ". Did you mean ", suggestions, "?", |f, suggestion| {
+                    write!(f, "'{suggestion}'")
+                })
             }
-            ValidationErrorKind::AnyOf => write!(
+            ValidationErrorKind::AnyOf { .. } => write!(
                 f,
                 "{} is not valid under any of the schemas listed in the 'anyOf' keyword",
                 self.placeholder
             ),
-            ValidationErrorKind::OneOfNotValid => write!(
+            ValidationErrorKind::OneOfNotValid { .. } => write!(
                 f,
                 "{} is not valid under any of the schemas listed in the 'oneOf' keyword",
                 self.placeholder
             ),
-            ValidationErrorKind::Contains => write!(
+            ValidationErrorKind::Contains { .. } => write!(
                 f,
                 "None of {} are valid under the given schema",
                 self.placeholder
             ),
-            ValidationErrorKind::Constant { expected_value } => {
+            // The only possible suggestion is the expected value, which is already shown
+            ValidationErrorKind::Constant { expected_value, .. } => {
                 write!(f, "{} was expected", expected_value)
             }
             ValidationErrorKind::ContentEncoding { content_encoding } => {
@@ -1027,8 +1259,12 @@ impl fmt::Display for MaskedValidationError<'_, '_, '_> {
                 )
             }
             ValidationErrorKind::FromUtf8 { error } => error.fmt(f),
-            ValidationErrorKind::Enum { options } => {
-                write!(f, "{} is not one of {}", self.placeholder, options)
+            ValidationErrorKind::Enum {
+                options,
+                suggestions,
+            } => {
+                write!(f, "{} is not one of {}", self.placeholder, options)?;
+                suggestions::write(f, ". Did you mean ", suggestions, "?", write_json_string)
             }
             ValidationErrorKind::ExclusiveMaximum { limit } => write!(
                 f,
@@ -1100,7 +1336,7 @@ impl fmt::Display for MaskedValidationError<'_, '_, '_> {
             ValidationErrorKind::Not { schema } => {
                 write!(f, "{} is not allowed for {}", schema, self.placeholder)
             }
-            ValidationErrorKind::OneOfMultipleValid => write!(
+            ValidationErrorKind::OneOfMultipleValid { .. } => write!(
                 f,
                 "{} is valid under more than one of the schemas listed in the 'oneOf' keyword",
                 self.placeholder
@@ -1108,9 +1344,15 @@ impl fmt::Display for MaskedValidationError<'_, '_, '_> {
             ValidationErrorKind::Pattern { pattern } => {
                 write!(f, r#"{} does not match "{}""#, self.placeholder, pattern)
             }
-            ValidationErrorKind::PropertyNames { error } => error.fmt(f),
-            ValidationErrorKind::Required { property } => {
-                write!(f, "{} is a required property", property)
+            ValidationErrorKind::PropertyNames { error } => {
+                error.masked_with(self.placeholder.as_ref()).fmt(f)
+            }
+            ValidationErrorKind::Required {
+                property,
+                suggestions,
+            } => {
+                write!(f, "{} is a required property", property)?;
+                suggestions::write(f, ". Is ", suggestions, " misspelled?", write_json_string)
             }
             ValidationErrorKind::MultipleOf { multiple_of } => {
                 write!(
@@ -1156,6 +1398,9 @@ impl fmt::Display for MaskedValidationError<'_, '_, '_> {
                 Ok(())
             }
             ValidationErrorKind::Custom { message } => f.write_str(message),
+            ValidationErrorKind::ErrorMessage { message, .. } => {
+                error_message::render(f, message, Err(&self.placeholder))
+            }
         }
     }
 }
@@ -1320,10 +1565,43 @@ mod tests {
     #[test_case(
         json!({"secret": "data", "key": "value"}),
         ValidationErrorKind::AdditionalProperties {
-            unexpected: vec!["secret".to_string(), "key".to_string()] 
+            unexpected: vec!["secret".to_string(), "key".to_string()],
+            suggestions: Vec::new(),
         },
         "Additional properties are not allowed ('secret', 'key' were unexpected)"
     )]
+    #[test_case(
+        json!({"emial": "data", "nmae": "value"}),
+        ValidationErrorKind::AdditionalProperties {
+            unexpected: vec!["emial".to_string(), "nmae".to_string()],
+            suggestions: vec!["email".to_string(), "name".to_string()],
+        },
+        "Additional properties are not allowed ('emial', 'nmae' were unexpected). Did you mean 'email' or 'name'?"
+    )]
+    #[test_case(
+        json!("Pendng"),
+        ValidationErrorKind::Enum {
+            options: json!(["Pending", "Done"]),
+            suggestions: vec!["Pending".to_string()],
+        },
+        "value is not one of [\"Pending\",\"Done\"]. Did you mean \"Pending\"?"
+    )]
+    #[test_case(
+        json!("Pendng"),
+        ValidationErrorKind::Constant {
+            expected_value: json!("Pending"),
+            suggestions: vec!["Pending".to_string()],
+        },
+        "\"Pending\" was expected"
+    )]
+    #[test_case(
+        json!({"emial": "data"}),
+        ValidationErrorKind::Required {
+            property: json!("email"),
+            suggestions: vec!["emial".to_string()],
+        },
+        "\"email\" is a required property. Is \"emial\" misspelled?"
+    )]
     #[test_case(
         json!(123),
         ValidationErrorKind::Minimum { limit: json!(456) },
@@ -1344,6 +1622,18 @@ mod tests {
         ValidationErrorKind::Type { kind: TypeKind::Single(PrimitiveType::String) },
         "value is not of type \"string\""
     )]
+    #[test_case(
+        json!({"secret": 1}),
+        ValidationErrorKind::PropertyNames {
+            error: Box::new(ValidationError {
+                instance: Cow::Owned(json!("secret")),
+                kind: ValidationErrorKind::MaxLength { limit: 2 },
+                instance_path: Location::new(),
+                schema_path: Location::new(),
+            })
+        },
+        "value is longer than 2 characters"
+    )]
     fn test_masked_error_messages(instance: Value, kind: ValidationErrorKind, expected: &str) {
         let error = ValidationError {
             instance: Cow::Owned(instance),
@@ -0,0 +1,321 @@
+//! Custom error messages from the `errorMessage` keyword.
+//!
+//! The keyword is enabled by [`crate::ValidationOptions::should_use_error_messages`] and works
+//! similarly to `ajv-errors`. Its value is either a single message for all errors of the schema
+//! it is defined in, or an object with a message per keyword of that schema:
+//!
+//! ```json
+//! {
+//!     "type": "integer",
+//!     "minimum": 18,
+//!     "errorMessage": {
+//!         "type": "Age must be a whole number, got ${0}",
+//!         "minimum": "Age must be at least 18"
+//!     }
+//! }
+//! ```
+//!
+//! `${0}` is replaced with the instance the schema with `errorMessage` applies to and
+//! `${0/path}` with a value inside it, given as a JSON pointer. Strings are inserted as is, other
+//! values as JSON. Errors that get the same message, like all errors of the schema with a single
+//! message, are reported once for that instance.
+use crate::{
+    compiler::Context,
+    error::ValidationErrorKind,
+    keywords::Keyword,
+    paths::{LazyLocation, Location},
+    primitive_type::{PrimitiveType, PrimitiveTypesBitMap},
+    ValidationError,
+};
+use ahash::AHashMap;
+use serde_json::{Map, Value};
+use std::{borrow::Cow, fmt};
+
+pub(crate) enum ErrorMessage {
+    /// One message for all errors of the schema.
+    Single(String),
+    /// Messages for errors of specific keywords.
+    Keywords(AHashMap<String, String>),
+}
+
+impl ErrorMessage {
+    fn new<'a>(ctx: &Context, value: &'a Value) -> Result<ErrorMessage, ValidationError<'a>> {
+        match value {
+            Value::String(message) => Ok(ErrorMessage::Single(message.clone())),
+            Value::Object(map) => {
+                let mut messages = AHashMap::with_capacity(map.len());
+                for (keyword, message) in map {
+                    let Value::String(message) = message else {
+                        return Err(ValidationError::single_type_error(
+                            Location::new(),
+                            ctx.location().join("errorMessage").join(keyword),
+                            message,
+                            PrimitiveType::String,
+                        ));
+                    };
+                    messages.insert(keyword.clone(), message.clone());
+                }
+                Ok(ErrorMessage::Keywords(messages))
+            }
+            _ => Err(ValidationError::multiple_type_error(
+                Location::new(),
+                ctx.location().join("errorMessage"),
+                value,
+                PrimitiveTypesBitMap::new()
+                    .add_type(PrimitiveType::String)
+                    .add_type(PrimitiveType::Object),
+            )),
+        }
+    }
+
+    /// Message template for errors coming from `keyword`.
+    pub(crate) fn for_keyword(&self, keyword: &str) -> Option<&str> {
+        match self {
+            ErrorMessage::Single(message) => Some(message),
+            ErrorMessage::Keywords(messages) => messages.get(keyword).map(String::as_str),
+        }
+    }
+
+    /// Replace the message of an error coming from `keyword`, if there is one for it.
+    ///
+    /// The error is then reported for `instance` at `location`, which the schema with
+    /// `errorMessage` applies to, as the message refers to it.
+    pub(crate) fn wrap<'i>(
+        &self,
+        keyword: &Keyword,
+        error: ValidationError<'i>,
+        instance: &'i Value,
+        location: &LazyLocation,
+    ) -> ValidationError<'i> {
+        if let Some(message) = self.for_keyword(keyword.as_str()) {
+            ValidationError {
+                instance: Cow::Borrowed(instance),
+                kind: ValidationErrorKind::ErrorMessage {
+                    message: message.to_string(),
+                    kind: Box::new(error.kind),
+                },
+                instance_path: location.into(),
+                schema_path: error.schema_path,
+            }
+        } else {
+            error
+        }
+    }
+}
+
+/// Write `template` with its `${0}` placeholders replaced with values from `instance`, or with
+/// `placeholder` if the instance should not be shown.
+pub(crate) fn render(
+    f: &mut impl fmt::Write,
+    template: &str,
+    instance: Result<&Value, &str>,
+) -> fmt::Result {
+    let mut rest = template;
+    while let Some(start) = rest.find("${") {
+        let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+            break;
+        };
+        f.write_str(&rest[..start])?;
+        match rest[start + 2..end].strip_prefix('0') {
+            Some(pointer) if pointer.is_empty() || pointer.starts_with('/') => match instance {
+                Ok(instance) => match instance.pointer(pointer) {
+                    Some(Value::String(value)) => f.write_str(value)?,
+                    Some(value) => write!(f, "{value}")?,
+                    None => {}
+                },
+                Err(placeholder) => f.write_str(placeholder)?,
+            },
+            _ => f.write_str(&rest[start..=end])?,
+        }
+        rest = &rest[end + 1..];
+    }
+    f.write_str(rest)
+}
+
+/// Render `template` for `instance` into a new string.
+pub(crate) fn render_to_string(template: &str, instance: &Value) -> String {
+    let mut message = String::new();
+    render(&mut message, template, Ok(instance)).expect("Writing to a string never fails");
+    message
+}
+
+/// Compile the `errorMessage` keyword of `schema`, unless it is disabled.
+pub(crate) fn compile<'a>(
+    ctx: &Context,
+    schema: &'a Map<String, Value>,
+) -> Result<Option<ErrorMessage>, ValidationError<'a>> {
+    match schema.get("errorMessage") {
+        Some(value) if ctx.config().error_messages() => ErrorMessage::new(ctx, value).map(Some),
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render;
+    use crate::{error::ValidationErrorKind, BasicOutput, Validator};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    fn validator(schema: &Value) -> Validator {
+        crate::options()
+            .should_use_error_messages(true)
+            .build(schema)
+            .expect("Invalid schema")
+    }
+
+    fn messages(schema: &Value, instance: &Value) -> Vec<String> {
+        validator(schema)
+            .iter_errors(instance)
+            .map(|error| error.to_string())
+            .collect()
+    }
+
+    #[test_case(
+        &json!({"type": "integer", "errorMessage": "Must be a whole number"}),
+        &json!(1.5),
+        &["Must be a whole number"];
+        "single message"
+    )]
+    #[test_case(
+        &json!({"type": "integer", "minimum": 18, "errorMessage": {"minimum": "${0} is too young"}}),
+        &json!(1.5),
+        &["1.5 is too young", "1.5 is not of type \"integer\""];
+        "keyword message"
+    )]
+    #[test_case(
+        &json!({"properties": {"name": {"type": "string"}}, "errorMessage": "Bad ${0}"}),
+        &json!({"name": 1}),
+        &[r#"Bad {"name":1}"#];
+        "nested error instance"
+    )]
+    #[test_case(
+        &json!({"properties": {"a": {"type": "string"}, "b": {"type": "string"}}, "required": ["c"], "errorMessage": "Bad ${0/a}"}),
+        &json!({"a": 1, "b": 2}),
+        &["Bad 1"];
+        "single message collapsed"
+    )]
+    #[test_case(
+        &json!({"items": {"type": "string"}, "minItems": 3, "errorMessage": {"items": "Only strings"}}),
+        &json!([1, 2]),
+        &["Only strings", "[1,2] has less than 3 items"];
+        "keyword message collapsed"
+    )]
+    #[test_case(
+        &json!({"$ref": "#/$defs/a", "$defs": {"a": {"type": "string", "errorMessage": "Not a string"}}}),
+        &json!(1),
+        &["Not a string"];
+        "behind reference"
+    )]
+    fn iter_errors(schema: &Value, instance: &Value, expected: &[&str]) {
+        assert_eq!(messages(schema, instance), expected);
+    }
+
+    #[test]
+    fn validate() {
+        let schema = json!({"minLength": 3, "errorMessage": {"minLength": "${0} is too short"}});
+        let validator = validator(&schema);
+        let instance = json!("ab");
+        let error = validator.validate(&instance).expect_err("Should fail");
+        assert_eq!(error.to_string(), "ab is too short");
+        assert_eq!(error.masked().to_string(), "value is too short");
+        assert_eq!(error.schema_path.as_str(), "/minLength");
+        let ValidationErrorKind::ErrorMessage { kind, .. } = error.kind else {
+            panic!("Unexpected kind: {:?}", error.kind);
+        };
+        assert!(matches!(*kind, ValidationErrorKind::MinLength { limit: 3 }));
+    }
+
+    #[test]
+    fn nested_error_location() {
+        let schema = json!({
+            "properties": {"tags": {"items": {"type": "string"}}},
+            "errorMessage": "Invalid ${0/tags}"
+        });
+        let validator = validator(&schema);
+        let instance = json!({"tags": ["a", 1]});
+        let error = validator.validate(&instance).expect_err("Should fail");
+        assert_eq!(error.to_string(), r#"Invalid ["a",1]"#);
+        assert_eq!(error.instance_path.as_str(), "");
+        assert_eq!(error.instance.as_ref(), &instance);
+        assert_eq!(error.schema_path.as_str(), "/properties/tags/items/type");
+    }
+
+    #[test]
+    fn basic_output() {
+        let schema = json!({
+            "properties": {"age": {"type": "integer", "errorMessage": "Bad age: ${0}"}},
+            "errorMessage": {"required": "Missing fields"},
+            "required": ["name"]
+        });
+        let validator = validator(&schema);
+        let instance = json!({"age": "x"});
+        let output = validator.apply(&instance).basic();
+        let BasicOutput::Invalid(errors) = output else {
+            panic!("Should be invalid");
+        };
+        let mut messages: Vec<_> = errors
+            .iter()
+            .map(|unit| unit.error_description().to_string())
+            .collect();
+        messages.sort();
+        assert_eq!(messages, ["Bad age: x", "Missing fields"]);
+    }
+
+    #[test]
+    fn basic_output_collapsed() {
+        let schema = json!({
+            "properties": {"a": {"type": "string"}, "b": {"type": "string"}},
+            "required": ["c"],
+            "errorMessage": "Invalid ${0/a}"
+        });
+        let validator = validator(&schema);
+        let instance = json!({"a": 1, "b": 2});
+        let output = validator.apply(&instance).basic();
+        let BasicOutput::Invalid(errors) = output else {
+            panic!("Should be invalid");
+        };
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].error_description().to_string(), "Invalid 1");
+        assert_eq!(errors[0].instance_location().as_str(), "");
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        let schema = json!({"type": "integer", "errorMessage": "Custom"});
+        let validator = crate::validator_for(&schema).expect("Invalid schema");
+        let instance = json!("a");
+        let error = validator.validate(&instance).expect_err("Should fail");
+        assert_eq!(error.to_string(), r#""a" is not of type "integer""#);
+    }
+
+    #[test_case(&json!({"errorMessage": 42}))]
+    #[test_case(&json!({"errorMessage": {"type": 42}}))]
+    fn invalid_schema(schema: &Value) {
+        assert!(crate::options()
+            .should_use_error_messages(true)
+            .build(schema)
+            .is_err());
+    }
+
+    #[test_case("plain", &json!(1), "plain")]
+    #[test_case("got ${0}", &json!(1.5), "got 1.5")]
+    #[test_case("got ${0}", &json!("abc"), "got abc")]
+    #[test_case("got ${0}", &json!({"a": [1]}), r#"got {"a":[1]}"#)]
+    #[test_case("${0/name} is ${0/age}", &json!({"name": "Bob", "age": 3}), "Bob is 3")]
+    #[test_case("missing ${0/x}!", &json!({}), "missing !")]
+    #[test_case("other ${1} and ${name}", &json!(1), "other ${1} and ${name}")]
+    #[test_case("unclosed ${0", &json!(1), "unclosed ${0")]
+    fn rendering(template: &str, instance: &Value, expected: &str) {
+        let mut message = String::new();
+        render(&mut message, template, Ok(instance)).expect("Should not fail");
+        assert_eq!(message, expected);
+    }
+
+    #[test]
+    fn rendering_masked() {
+        let mut message = String::new();
+        render(&mut message, "${0} is ${0/a} wrong", Err("value")).expect("Should not fail");
+        assert_eq!(message, "value is value wrong");
+    }
+}
@@ -0,0 +1,443 @@
+//! Serde representations for errors of other crates that are stored in
+//! [`crate::error::ValidationErrorKind`].
+//!
+//! These errors don't implement `Serialize` / `Deserialize`, so they are stored as the data they
+//! were created from and are re-created from it during deserialization.
+
+/// `fancy_regex` errors that may happen during matching, stored as `"backtrack_limit_exceeded"`
+/// or `"stack_overflow"`.
+pub(crate) mod regex_error {
+    use fancy_regex::{Error, RuntimeError};
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(
+        error: &Error,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(match error {
+            Error::RuntimeError(RuntimeError::StackOverflow) => "stack_overflow",
+            _ => "backtrack_limit_exceeded",
+        })
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Error, D::Error> {
+        match String::deserialize(deserializer)?.as_str() {
+            "backtrack_limit_exceeded" => {
+                Ok(Error::RuntimeError(RuntimeError::BacktrackLimitExceeded))
+            }
+            "stack_overflow" => Ok(Error::RuntimeError(RuntimeError::StackOverflow)),
+            other => Err(de::Error::unknown_variant(
+                other,
+                &["backtrack_limit_exceeded", "stack_overflow"],
+            )),
+        }
+    }
+}
+
+/// Invalid UTF-8, stored as the bytes that failed to decode.
+pub(crate) mod from_utf8_error {
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+    use std::string::FromUtf8Error;
+
+    pub(crate) fn serialize<S: Serializer>(
+        error: &FromUtf8Error,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        error.as_bytes().serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<FromUtf8Error, D::Error> {
+        let bytes = Vec::<u8>::deserialize(deserializer)?;
+        String::from_utf8(bytes)
+            .err()
+            .ok_or_else(|| de::Error::custom("bytes are valid UTF-8"))
+    }
+}
+
+/// Reference resolution errors, stored under `error` with their own `kind` discriminator.
+///
+/// The source of an `unretrievable` error is kept only as its message.
+pub(crate) mod referencing_error {
+    use referencing::{Error, PolicyViolation, Uri, UriError, UriRef};
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+    use std::net::IpAddr;
+
+    #[derive(Serialize, Deserialize)]
+    struct Wrapper {
+        error: Repr,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(tag = "kind", rename_all = "snake_case")]
+    enum Repr {
+        Unretrievable { uri: String, source: String },
+        PolicyViolation { uri: String, violation: Violation },
+        PointerToNowhere { pointer: String },
+        InvalidPercentEncoding { pointer: String },
+        InvalidArrayIndex { pointer: String, index: String },
+        NoSuchAnchor { anchor: String },
+        InvalidAnchor { anchor: String },
+        InvalidUri { uri: String, is_reference: bool },
+        UnresolvableUri { uri: String, base: String },
+        UnknownSpecification { specification: String },
+    }
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(tag = "rule", rename_all = "snake_case")]
+    enum Violation {
+        SchemeNotAllowed { scheme: String },
+        HostNotAllowed { host: String },
+        PathNotAllowed { path: String },
+        PrivateAddress { host: String, address: IpAddr },
+        UnresolvableHost { host: String },
+        TooManyRetrievals { limit: usize },
+        TooDeep { limit: usize },
+    }
+
+    impl From<&PolicyViolation> for Violation {
+        fn from(violation: &PolicyViolation) -> Self {
+            match violation.clone() {
+                PolicyViolation::SchemeNotAllowed { scheme } => {
+                    Violation::SchemeNotAllowed { scheme }
+                }
+                PolicyViolation::HostNotAllowed { host } => Violation::HostNotAllowed { host },
+                PolicyViolation::PathNotAllowed { path } => Violation::PathNotAllowed { path },
+                PolicyViolation::PrivateAddress { host, address } => {
+                    Violation::PrivateAddress { host, address }
+                }
+                PolicyViolation::UnresolvableHost { host } => Violation::UnresolvableHost { host },
+                PolicyViolation::TooManyRetrievals { limit } => {
+                    Violation::TooManyRetrievals { limit }
+                }
+                PolicyViolation::TooDeep { limit } => Violation::TooDeep { limit },
+            }
+        }
+    }
+
+    impl From<Violation> for PolicyViolation {
+        fn from(violation: Violation) -> Self {
+            match violation {
+                Violation::SchemeNotAllowed { scheme } => {
+                    PolicyViolation::SchemeNotAllowed { scheme }
+                }
+                Violation::HostNotAllowed { host } => PolicyViolation::HostNotAllowed { host },
+                Violation::PathNotAllowed { path } => PolicyViolation::PathNotAllowed { path },
+                Violation::PrivateAddress { host, address } => {
+                    PolicyViolation::PrivateAddress { host, address }
+                }
+                Violation::UnresolvableHost { host } => PolicyViolation::UnresolvableHost { host },
+                Violation::TooManyRetrievals { limit } => {
+                    PolicyViolation::TooManyRetrievals { limit }
+                }
+                Violation::TooDeep { limit } => PolicyViolation::TooDeep { limit },
+            }
+        }
+    }
+
+    pub(crate) fn serialize<S: Serializer>(
+        error: &Error,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let error = match error {
+            Error::Unretrievable { uri, source } => Repr::Unretrievable {
+                uri: uri.clone(),
+                source: source.to_string(),
+            },
+            Error::PolicyViolation { uri, violation } => Repr::PolicyViolation {
+                uri: uri.clone(),
+                violation: violation.into(),
+            },
+            Error::PointerToNowhere { pointer } => Repr::PointerToNowhere {
+                pointer: pointer.clone(),
+            },
+            Error::InvalidPercentEncoding { pointer, .. } => Repr::InvalidPercentEncoding {
+                pointer: pointer.clone(),
+            },
+            Error::InvalidArrayIndex { pointer, index, .. } => Repr::InvalidArrayIndex {
+                pointer: pointer.clone(),
+                index: index.clone(),
+            },
+            Error::NoSuchAnchor { anchor } => Repr::NoSuchAnchor {
+                anchor: anchor.clone(),
+            },
+            Error::InvalidAnchor { anchor } => Repr::InvalidAnchor {
+                anchor: anchor.clone(),
+            },
+            Error::InvalidUri(UriError::Parse {
+                uri, is_reference, ..
+            }) => Repr::InvalidUri {
+                uri: uri.clone(),
+                is_reference: *is_reference,
+            },
+            Error::InvalidUri(UriError::Resolve { uri, base, .. }) => Repr::UnresolvableUri {
+                uri: uri.clone(),
+                base: base.to_string(),
+            },
+            Error::UnknownSpecification { specification } => Repr::UnknownSpecification {
+                specification: specification.clone(),
+            },
+        };
+        Wrapper { error }.serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Error, D::Error> {
+        Ok(match Wrapper::deserialize(deserializer)?.error {
+            Repr::Unretrievable { uri, source } => Error::Unretrievable {
+                uri,
+                source: source.into(),
+            },
+            Repr::PolicyViolation { uri, violation } => Error::PolicyViolation {
+                uri,
+                violation: violation.into(),
+            },
+            Repr::PointerToNowhere { pointer } => Error::PointerToNowhere { pointer },
+            Repr::InvalidPercentEncoding { pointer } => {
+                let source = percent_encoding::percent_decode_str(pointer.get(1..).unwrap_or(""))
+                    .decode_utf8()
+                    .err()
+                    .ok_or_else(|| de::Error::custom("pointer is valid UTF-8 when decoded"))?;
+                Error::InvalidPercentEncoding { pointer, source }
+            }
+            Repr::InvalidArrayIndex { pointer, index } => {
+                let source = index
+                    .parse::<usize>()
+                    .err()
+                    .ok_or_else(|| de::Error::custom("index is a valid array index"))?;
+                Error::InvalidArrayIndex {
+                    pointer,
+                    index,
+                    source,
+                }
+            }
+            Repr::NoSuchAnchor { anchor } => Error::NoSuchAnchor { anchor },
+            Repr::InvalidAnchor { anchor } => Error::InvalidAnchor { anchor },
+            Repr::InvalidUri { uri, is_reference } => {
+                let error = if is_reference {
+                    UriRef::parse(uri.as_str()).err()
+                } else {
+                    Uri::parse(uri.as_str()).err()
+                }
+                .ok_or_else(|| de::Error::custom("uri is valid"))?;
+                Error::InvalidUri(UriError::Parse {
+                    uri,
+                    is_reference,
+                    error,
+                })
+            }
+            Repr::UnresolvableUri { uri, base } => {
+                let base =
+                    Uri::parse(base).map_err(|error| de::Error::custom(error.to_string()))?;
+                let error = UriRef::parse(uri.as_str())
+                    .map_err(|error| de::Error::custom(error.to_string()))?
+                    .resolve_against(&base)
+                    .err()
+                    .ok_or_else(|| de::Error::custom("uri can be resolved against base"))?;
+                Error::InvalidUri(UriError::Resolve { uri, base, error })
+            }
+            Repr::UnknownSpecification { specification } => {
+                Error::UnknownSpecification { specification }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{error::ValidationErrorKind, paths::Location, ValidationError};
+    use serde_json::{json, Value};
+    use std::borrow::Cow;
+    use test_case::test_case;
+
+    fn roundtrip(error: &ValidationError<'_>) -> Value {
+        let serialized = serde_json::to_value(error).expect("Serializable");
+        let deserialized: ValidationError =
+            serde_json::from_value(serialized.clone()).expect("Deserializable");
+        assert_eq!(deserialized.to_string(), error.to_string());
+        assert_eq!(deserialized.instance, error.instance);
+        assert_eq!(deserialized.instance_path, error.instance_path);
+        assert_eq!(deserialized.schema_path, error.schema_path);
+        assert_eq!(
+            serde_json::to_value(&deserialized).expect("Serializable"),
+            serialized
+        );
+        serialized
+    }
+
+    #[test_case(&json!({"type": "string"}), &json!(1), &json!({"kind": "type", "types": "string"}); "single type")]
+    #[test_case(&json!({"type": ["string", "null"]}), &json!(1), &json!({"kind": "type", "types": ["null", "string"]}); "multiple types")]
+    #[test_case(&json!({"minimum": 5}), &json!(1), &json!({"kind": "minimum", "limit": 5}); "minimum")]
+    #[test_case(&json!({"multipleOf": 2.5}), &json!(1), &json!({"kind": "multiple_of", "multiple_of": 2.5}); "multiple of")]
+    #[test_case(&json!({"maxLength": 1}), &json!("ab"), &json!({"kind": "max_length", "limit": 1}); "max length")]
+    #[test_case(&json!({"required": ["a"]}), &json!({}), &json!({"kind": "required", "property": "a"}); "required")]
+    #[test_case(&json!({"enum": [1, 2]}), &json!(3), &json!({"kind": "enum", "options": [1, 2]}); "enum")]
+    #[test_case(&json!({"const": 1}), &json!(3), &json!({"kind": "constant", "expected_value": 1}); "constant")]
+    #[test_case(&json!({"properties": {}, "additionalProperties": false}), &json!({"a": 1}), &json!({"kind": "additional_properties", "unexpected": ["a"]}); "additional properties")]
+    #[test_case(&json!({"uniqueItems": true}), &json!([1, 1]), &json!({"kind": "unique_items"}); "unique items")]
+    #[test_case(&json!({"not": {"type": "integer"}}), &json!(3), &json!({"kind": "not", "schema": {"type": "integer"}}); "not")]
+    #[test_case(&json!(false), &json!(3), &json!({"kind": "false_schema"}); "false schema")]
+    #[test_case(&json!({"pattern": "^a"}), &json!("b"), &json!({"kind": "pattern", "pattern": "^a"}); "pattern")]
+    #[test_case(&json!({"format": "ipv4"}), &json!("b"), &json!({"kind": "format", "format": "ipv4"}); "format")]
+    fn kind(schema: &Value, instance: &Value, expected: &Value) {
+        let validator = crate::options()
+            .should_validate_formats(true)
+            .build(schema)
+            .expect("Invalid schema");
+        let error = validator.validate(instance).expect_err("Should fail");
+        let serialized = roundtrip(&error);
+        let mut expected = expected.clone();
+        let fields = expected.as_object_mut().expect("Object");
+        fields.insert("instance".into(), instance.clone());
+        fields.insert("instance_path".into(), json!(error.instance_path.as_str()));
+        fields.insert("schema_path".into(), json!(error.schema_path.as_str()));
+        assert_eq!(serialized, expected);
+    }
+
+    #[test]
+    fn nested_errors() {
+        let schema = json!({
+            "properties": {
+                "a": {"anyOf": [{"type": "string"}, {"minimum": 3}]},
+                "b": {"propertyNames": {"maxLength": 1}},
+                "c": {"oneOf": [{}, true]},
+                "d": {"contains": {"type": "string"}},
+                "e": {"type": "integer", "errorMessage": "Bad ${0}"}
+            }
+        });
+        let validator = crate::options()
+            .should_use_error_messages(true)
+            .build(&schema)
+            .expect("Invalid schema");
+        let instance = json!({"a": 1, "b": {"long": 1}, "c": 1, "d": [1], "e": "x"});
+        let errors: Vec<_> = validator.iter_errors(&instance).collect();
+        assert_eq!(errors.len(), 5);
+        for error in &errors {
+            roundtrip(error);
+        }
+        assert_eq!(
+            serde_json::to_value(&errors[0]).expect("Serializable"),
+            json!({
+                "kind": "any_of",
+                "context": [
+                    [{
+                        "kind": "type",
+                        "types": "string",
+                        "instance": 1,
+                        "instance_path": "/a",
+                        "schema_path": "/properties/a/anyOf/0/type"
+                    }],
+                    [{
+                        "kind": "minimum",
+                        "limit": 3,
+                        "instance": 1,
+                        "instance_path": "/a",
+                        "schema_path": "/properties/a/anyOf/1/minimum"
+                    }]
+                ],
+                "instance": 1,
+                "instance_path": "/a",
+                "schema_path": "/properties/a/anyOf"
+            })
+        );
+        let ValidationErrorKind::OneOfMultipleValid { matched, .. } = &errors[2].kind else {
+            panic!("Unexpected kind: {:?}", errors[2].kind);
+        };
+        assert_eq!(matched, &[0, 1]);
+        assert_eq!(
+            serde_json::to_value(&errors[4]).expect("Serializable")["inner"],
+            json!({"kind": "type", "types": "integer"})
+        );
+    }
+
+    fn error(kind: ValidationErrorKind) -> ValidationError<'static> {
+        ValidationError {
+            instance: Cow::Owned(json!("x")),
+            kind,
+            instance_path: Location::new().join("a"),
+            schema_path: Location::new().join("b"),
+        }
+    }
+
+    #[test]
+    fn backtrack_limit_exceeded() {
+        let kind = ValidationErrorKind::BacktrackLimitExceeded {
+            error: fancy_regex::Error::RuntimeError(
+                fancy_regex::RuntimeError::BacktrackLimitExceeded,
+            ),
+        };
+        let serialized = roundtrip(&error(kind));
+        assert_eq!(serialized["error"], json!("backtrack_limit_exceeded"));
+    }
+
+    #[test]
+    fn from_utf8() {
+        let kind = ValidationErrorKind::FromUtf8 {
+            error: String::from_utf8(vec![b'a', 0xff]).expect_err("Invalid UTF-8"),
+        };
+        let serialized = roundtrip(&error(kind));
+        assert_eq!(serialized["bytes"], json!([97, 255]));
+    }
+
+    #[test_case(&json!({"$ref": "#/$defs/missing"}), &json!({"kind": "pointer_to_nowhere", "pointer": "/$defs/missing"}); "pointer to nowhere")]
+    #[test_case(&json!({"$ref": "#/$defs/%ff"}), &json!({"kind": "invalid_percent_encoding", "pointer": "/$defs/%ff"}); "percent encoding")]
+    #[test_case(&json!({"$ref": "#missing"}), &json!({"kind": "no_such_anchor", "anchor": "missing"}); "no such anchor")]
+    #[test_case(&json!({"$ref": "http://a b"}), &json!({"kind": "invalid_uri", "uri": "http://a b", "is_reference": true}); "invalid uri")]
+    fn referencing_errors(schema: &Value, expected: &Value) {
+        let ValidationErrorKind::Referencing(inner) = crate::options()
+            .build(schema)
+            .expect_err("Invalid reference")
+            .kind
+        else {
+            panic!("Should be a referencing error");
+        };
+        let serialized = roundtrip(&error(ValidationErrorKind::Referencing(inner)));
+        assert_eq!(serialized["kind"], json!("referencing"));
+        assert_eq!(&serialized["error"], expected);
+    }
+
+    #[test]
+    fn referencing_unretrievable() {
+        let kind = ValidationErrorKind::Referencing(referencing::Error::Unretrievable {
+            uri: "http://example.com".to_string(),
+            source: "Connection refused".into(),
+        });
+        let serialized = roundtrip(&error(kind));
+        assert_eq!(
+            serialized["error"],
+            json!({"kind": "unretrievable", "uri": "http://example.com", "source": "Connection refused"})
+        );
+    }
+
+    #[test]
+    fn referencing_policy_violation() {
+        let kind = ValidationErrorKind::Referencing(referencing::Error::PolicyViolation {
+            uri: "http://169.254.169.254/latest".to_string(),
+            violation: referencing::PolicyViolation::PrivateAddress {
+                host: "169.254.169.254".to_string(),
+                address: [169, 254, 169, 254].into(),
+            },
+        });
+        let serialized = roundtrip(&error(kind));
+        assert_eq!(
+            serialized["error"],
+            json!({
+                "kind": "policy_violation",
+                "uri": "http://169.254.169.254/latest",
+                "violation": {"rule": "private_address", "host": "169.254.169.254", "address": "169.254.169.254"}
+            })
+        );
+    }
+
+    #[test_case(&json!({"kind": "unknown", "instance": 1, "instance_path": "", "schema_path": ""}); "unknown kind")]
+    #[test_case(&json!({"kind": "minimum", "instance": 1, "instance_path": "", "schema_path": ""}); "missing field")]
+    #[test_case(&json!({"kind": "from_utf8", "bytes": [97], "instance": 1, "instance_path": "", "schema_path": ""}); "valid utf8")]
+    #[test_case(&json!({"kind": "referencing", "error": {"kind": "invalid_array_index", "pointer": "/0", "index": "0"}, "instance": 1, "instance_path": "", "schema_path": ""}); "valid index")]
+    fn invalid(value: &Value) {
+        assert!(serde_json::from_value::<ValidationError>(value.clone()).is_err());
+    }
+}
@@ -0,0 +1,296 @@
+//! Grouping and ranking of validation errors.
+use crate::{error::ValidationErrorKind, paths::LocationSegment, ValidationError};
+use std::collections::{btree_map, BTreeMap};
+
+/// Validation errors indexed by instance location, then by keyword.
+///
+/// Each node of the tree corresponds to a location within the instance and holds the errors
+/// reported for it, keyed by the keyword that failed. Errors from `false` subschemas have no
+/// keyword and are keyed by `"false"`.
+///
+/// # Examples
+///
+/// ```rust
+/// use jsonschema::ErrorTree;
+/// use serde_json::json;
+///
+/// let schema = json!({
+///     "properties": {
+///         "tags": {"items": {"type": "string", "maxLength": 3}}
+///     },
+///     "required": ["name"]
+/// });
+/// let validator = jsonschema::validator_for(&schema).expect("Invalid schema");
+/// let instance = json!({"tags": ["ok", 42, "too long"]});
+///
+/// let tree: ErrorTree = validator.iter_errors(&instance).collect();
+/// assert_eq!(tree.total_errors(), 3);
+/// assert!(tree.contains("required"));
+/// assert!(tree.at("/tags/1").expect("Has errors").contains("type"));
+/// assert!(tree.child("tags").and_then(|tags| tags.child(2)).is_some());
+/// ```
+#[derive(Debug, Default)]
+pub struct ErrorTree<'a> {
+    errors: BTreeMap<String, Vec<ValidationError<'a>>>,
+    children: BTreeMap<String, ErrorTree<'a>>,
+    total_errors: usize,
+}
+
+impl<'a> ErrorTree<'a> {
+    /// Build a tree from `errors`.
+    pub fn new(errors: impl IntoIterator<Item = ValidationError<'a>>) -> ErrorTree<'a> {
+        errors.into_iter().collect()
+    }
+
+    /// Add `error` to the node for its instance location.
+    pub fn insert(&mut self, error: ValidationError<'a>) {
+        let keyword = keyword(&error);
+        let segments: Vec<String> = error
+            .instance_path
+            .as_str()
+            .split('/')
+            .skip(1)
+            .map(unescape)
+            .collect();
+        let mut node = self;
+        node.total_errors += 1;
+        for segment in segments {
+            node = node.children.entry(segment).or_default();
+            node.total_errors += 1;
+        }
+        node.errors.entry(keyword).or_default().push(error);
+    }
+
+    /// Errors reported for this location, keyed by keyword.
+    #[must_use]
+    pub fn errors(&self) -> &BTreeMap<String, Vec<ValidationError<'a>>> {
+        &self.errors
+    }
+
+    /// Errors of `keyword` reported for this location.
+    #[must_use]
+    pub fn errors_for(&self, keyword: &str) -> &[ValidationError<'a>] {
+        self.errors.get(keyword).map_or(&[], Vec::as_slice)
+    }
+
+    /// Whether `keyword` failed for this location.
+    #[must_use]
+    pub fn contains(&self, keyword: &str) -> bool {
+        self.errors.contains_key(keyword)
+    }
+
+    /// The subtree for a property or an item of this location, if it has any errors.
+    #[must_use]
+    pub fn child<'s>(&self, segment: impl Into<LocationSegment<'s>>) -> Option<&ErrorTree<'a>> {
+        self.children.get(&segment.into().to_string())
+    }
+
+    /// The subtree for the location given as a JSON pointer relative to this one, if it has
+    /// any errors.
+    #[must_use]
+    pub fn at(&self, pointer: &str) -> Option<&ErrorTree<'a>> {
+        if pointer.is_empty() {
+            return Some(self);
+        }
+        let mut node = self;
+        for segment in pointer.strip_prefix('/')?.split('/') {
+            node = node.children.get(&unescape(segment))?;
+        }
+        Some(node)
+    }
+
+    /// Subtrees of properties and items of this location that have errors.
+    pub fn children(&self) -> btree_map::Iter<'_, String, ErrorTree<'a>> {
+        self.children.iter()
+    }
+
+    /// The number of errors at this location and below it.
+    #[must_use]
+    pub fn total_errors(&self) -> usize {
+        self.total_errors
+    }
+
+    /// Whether there are no errors in the tree.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.total_errors == 0
+    }
+}
+
+impl<'a> FromIterator<ValidationError<'a>> for ErrorTree<'a> {
+    fn from_iter<T: IntoIterator<Item = ValidationError<'a>>>(iter: T) -> Self {
+        let mut tree = ErrorTree::default();
+        for error in iter {
+            tree.insert(error);
+        }
+        tree
+    }
+}
+
+impl<'a> Extend<ValidationError<'a>> for ErrorTree<'a> {
+    fn extend<T: IntoIterator<Item = ValidationError<'a>>>(&mut self, iter: T) {
+        for error in iter {
+            self.insert(error);
+        }
+    }
+}
+
+/// The keyword that failed, which is the last segment of the schema path unless the error comes
+/// from a `false` subschema, e.g. `/properties/foo`.
+fn keyword(error: &ValidationError<'_>) -> String {
+    let mut kind = &error.kind;
+    while let ValidationErrorKind::ErrorMessage { kind: inner, .. } = kind {
+        kind = inner;
+    }
+    if matches!(kind, ValidationErrorKind::FalseSchema) {
+        return "false".to_string();
+    }
+    error
+        .schema_path
+        .as_str()
+        .rsplit('/')
+        .next()
+        .map(unescape)
+        .unwrap_or_default()
+}
+
+fn unescape(segment: &str) -> String {
+    segment.replace("~1", "/").replace("~0", "~")
+}
+
+/// Pick the most relevant error out of `errors`.
+///
+/// Errors from `anyOf` and `oneOf` only say that none or several of the alternatives matched,
+/// so they rank below any other error. Among the rest, errors deeper in the instance are
+/// preferred, as they point closer to the actual problem. Ties go to the error that comes first.
+///
+/// If the most relevant error is from `anyOf` or `oneOf` matching none of the alternatives, the
+/// best match among the errors of all alternatives is returned instead.
+///
+/// # Examples
+///
+/// ```rust
+/// use serde_json::json;
+///
+/// let schema = json!({
+///     "oneOf": [{"type": "string"}, {"type": "array"}],
+///     "items": {"properties": {"id": {"type": "integer"}}}
+/// });
+/// let validator = jsonschema::validator_for(&schema).expect("Invalid schema");
+/// let instance = json!([{"id": "x"}]);
+///
+/// let error = jsonschema::best_match(validator.iter_errors(&instance)).expect("Has errors");
+/// assert_eq!(error.instance_path.as_str(), "/0/id");
+/// ```
+pub fn best_match<'a>(
+    errors: impl IntoIterator<Item = ValidationError<'a>>,
+) -> Option<ValidationError<'a>> {
+    let mut best: Option<((bool, usize), ValidationError<'a>)> = None;
+    for error in errors {
+        let key = relevance(&error);
+        if best.as_ref().map_or(true, |(best_key, _)| key > *best_key) {
+            best = Some((key, error));
+        }
+    }
+    let (_, mut best) = best?;
+    match &mut best.kind {
+        ValidationErrorKind::AnyOf { context } | ValidationErrorKind::OneOfNotValid { context }
+            if context.iter().any(|errors| !errors.is_empty()) =>
+        {
+            best_match(std::mem::take(context).into_iter().flatten())
+        }
+        _ => Some(best),
+    }
+}
+
+fn relevance(error: &ValidationError<'_>) -> (bool, usize) {
+    let mut kind = &error.kind;
+    while let ValidationErrorKind::ErrorMessage { kind: inner, .. } = kind {
+        kind = inner;
+    }
+    let is_weak = matches!(
+        kind,
+        ValidationErrorKind::AnyOf { .. }
+            | ValidationErrorKind::OneOfNotValid { .. }
+            | ValidationErrorKind::OneOfMultipleValid { .. }
+    );
+    let depth = error.instance_path.as_str().matches('/').count();
+    (!is_weak, depth)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{best_match, ErrorTree};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test]
+    fn tree() {
+        let schema = json!({
+            "properties": {
+                "a/b": {"type": "string", "minLength": 5},
+                "list": {"items": {"type": "integer"}}
+            },
+            "required": ["x", "y"]
+        });
+        let validator = crate::validator_for(&schema).expect("Invalid schema");
+        let instance = json!({"a/b": 1, "list": [1, "2", "3"]});
+        let tree: ErrorTree = validator.iter_errors(&instance).collect();
+        assert_eq!(tree.total_errors(), 5);
+        assert_eq!(tree.errors_for("required").len(), 2);
+        assert!(tree.errors_for("type").is_empty());
+        let child = tree.child("a/b").expect("Has errors");
+        assert_eq!(child.total_errors(), 1);
+        assert!(child.contains("type"));
+        assert!(std::ptr::eq(tree.at("/a~1b").expect("Has errors"), child));
+        let list = tree.at("/list").expect("Has errors");
+        assert_eq!(list.total_errors(), 2);
+        assert!(list.errors().is_empty());
+        let items: Vec<_> = list.children().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(items, ["1", "2"]);
+        assert!(list.child(0).is_none());
+        assert!(tree.at("/missing").is_none());
+        assert!(tree.at("no-slash").is_none());
+        assert!(std::ptr::eq(tree.at("").expect("Root"), &tree));
+    }
+
+    #[test]
+    fn false_schema() {
+        let schema = json!({
+            "properties": {"foo": false, "bar": {"prefixItems": [true, false]}},
+            "patternProperties": {"^x": {"not": {}, "errorMessage": "No x"}}
+        });
+        let validator = crate::validator_for(&schema).expect("Invalid schema");
+        let instance = json!({"foo": 1, "bar": [1, 2], "xy": 3});
+        let tree: ErrorTree = validator.iter_errors(&instance).collect();
+        assert_eq!(tree.total_errors(), 3);
+        let foo = tree.child("foo").expect("Has errors");
+        assert!(foo.contains("false"));
+        assert!(!foo.contains("foo"));
+        assert!(tree.at("/bar/1").expect("Has errors").contains("false"));
+        assert!(tree.child("xy").expect("Has errors").contains("not"));
+    }
+
+    #[test]
+    fn empty_tree() {
+        let tree = ErrorTree::new(Vec::new());
+        assert!(tree.is_empty());
+        assert_eq!(tree.total_errors(), 0);
+    }
+
+    #[test_case(&json!({"type": "string"}), &json!(1), ""; "single error")]
+    #[test_case(&json!({"properties": {"a": {"properties": {"b": {"type": "string"}}}}, "required": ["c"]}), &json!({"a": {"b": 1}}), "/a/b"; "deeper wins")]
+    #[test_case(&json!({"anyOf": [{"type": "string"}], "required": ["c"], "properties": {"a": {"oneOf": [{"type": "string"}]}}}), &json!({"a": 1}), ""; "weak loses")]
+    #[test_case(&json!({"properties": {"a": {"anyOf": [{"type": "string"}]}}, "oneOf": [{"type": "string"}]}), &json!({"a": 1}), "/a"; "deeper weak")]
+    #[test_case(&json!({"anyOf": [{"properties": {"a": {"type": "string"}}}, {"type": "array"}]}), &json!({"a": 1}), "/a"; "alternatives")]
+    fn best(schema: &Value, instance: &Value, expected: &str) {
+        let validator = crate::validator_for(schema).expect("Invalid schema");
+        let error = best_match(validator.iter_errors(instance)).expect("Has errors");
+        assert_eq!(error.instance_path.as_str(), expected);
+    }
+
+    #[test]
+    fn best_of_nothing() {
+        assert!(best_match(Vec::new()).is_none());
+    }
+}
@@ -0,0 +1,423 @@
+//! Pluggable rendering of validation error messages.
+//!
+//! The [`Display`](std::fmt::Display) implementation of [`ValidationError`] always produces
+//! English text. To show errors in other languages or wording, implement [`ErrorFormatter`], or
+//! load a [`MessageCatalog`] at runtime, and set it via
+//! [`ValidationOptions::with_error_formatter`](crate::ValidationOptions::with_error_formatter).
+//! Messages are then rendered with [`Validator::format_error`](crate::Validator::format_error)
+//! and in the error descriptions of the output formats of
+//! [`Validator::apply`](crate::Validator::apply).
+//!
+//! Errors themselves don't know the validator they come from, so their `Display` output, as well
+//! as that of [`ValidationError::masked_with`], stays in English. This also applies to errors
+//! grouped in an [`ErrorTree`](crate::ErrorTree), render them with `Validator::format_error`
+//! instead. Problem details documents take their own formatter via `ProblemOptions`.
+//!
+//! ```rust
+//! use jsonschema::formatter::MessageCatalog;
+//! use serde_json::json;
+//!
+//! let catalog = MessageCatalog::from_reader(
+//!     r#"{
+//!         "type": "{instance} ist nicht vom Typ {types}",
+//!         "minimum": "{instance} ist kleiner als {limit}"
+//!     }"#
+//!     .as_bytes(),
+//! )
+//! .expect("Invalid catalog");
+//! let validator = jsonschema::options()
+//!     .with_error_formatter(catalog)
+//!     .build(&json!({"type": "integer", "minimum": 5}))
+//!     .expect("Invalid schema");
+//!
+//! let instance = json!(3);
+//! let error = validator.validate(&instance).expect_err("Should fail");
+//! assert_eq!(validator.format_error(&error), "3 ist kleiner als 5");
+//! ```
+use crate::{
+    error::{TypeKind, ValidationErrorKind},
+    ValidationError,
+};
+use ahash::AHashMap;
+use serde_json::Value;
+use std::{fmt::Write, io::Read};
+
+/// Renders [`ValidationError`] into a human-readable message.
+pub trait ErrorFormatter: Send + Sync {
+    /// Render `error`, which holds the structured [`ValidationErrorKind`], the failing instance,
+    /// and its instance and schema paths.
+    fn format(&self, error: &ValidationError<'_>) -> String;
+}
+
+impl<F> ErrorFormatter for F
+where
+    F: Fn(&ValidationError<'_>) -> String + Send + Sync,
+{
+    fn format(&self, error: &ValidationError<'_>) -> String {
+        self(error)
+    }
+}
+
+/// The default English messages, the same as the [`Display`](std::fmt::Display) output of
+/// [`ValidationError`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DefaultFormatter;
+
+impl ErrorFormatter for DefaultFormatter {
+    fn format(&self, error: &ValidationError<'_>) -> String {
+        error.to_string()
+    }
+}
+
+/// Message templates for each error kind, loaded at runtime.
+///
+/// Templates are keyed by the name of the keyword that produced the error, e.g. `type` or
+/// `minLength`. Errors without a dedicated keyword use `falseSchema`, `oneOfNotValid`,
+/// `oneOfMultipleValid`, `fromUtf8`, `backtrackLimitExceeded`, `referencing` and `custom`.
+/// Kinds without a template are rendered with [`DefaultFormatter`], as are messages from the
+/// `errorMessage` keyword.
+///
+/// Templates refer to error details in braces:
+///
+/// - `{instance}`, `{instance_path}` and `{schema_path}` are available for every error;
+/// - `{limit}` for size and range keywords, `{expected}` for `const`, `{options}` for `enum`,
+///   `{types}` for `type`, `{property}` for `required`, `{pattern}`, `{format}`,
+///   `{multiple_of}`, `{schema}` for `not`, `{content_encoding}`, `{content_media_type}`,
+///   `{unexpected}` for additional and unevaluated items or properties, `{message}` for `custom`
+///   and `{error}` for nested errors.
+///
+/// Unknown names are kept as is. Use `{{` and `}}` for literal braces.
+#[derive(Debug, Default, Clone)]
+pub struct MessageCatalog {
+    templates: AHashMap<String, String>,
+}
+
+impl MessageCatalog {
+    /// Create a catalog from `(key, template)` pairs.
+    pub fn new<K, T>(templates: impl IntoIterator<Item = (K, T)>) -> MessageCatalog
+    where
+        K: Into<String>,
+        T: Into<String>,
+    {
+        MessageCatalog {
+            templates: templates
+                .into_iter()
+                .map(|(key, template)| (key.into(), template.into()))
+                .collect(),
+        }
+    }
+
+    /// Load a catalog from a JSON object mapping keys to templates.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input is not a JSON object with string values.
+    pub fn from_reader<R: Read>(reader: R) -> Result<MessageCatalog, serde_json::Error> {
+        let templates: AHashMap<String, String> = serde_json::from_reader(reader)?;
+        Ok(MessageCatalog { templates })
+    }
+
+    /// Add or replace the template for `key`.
+    #[must_use]
+    pub fn with_template(mut self, key: impl Into<String>, template: impl Into<String>) -> Self {
+        self.templates.insert(key.into(), template.into());
+        self
+    }
+}
+
+impl ErrorFormatter for MessageCatalog {
+    fn format(&self, error: &ValidationError<'_>) -> String {
+        if matches!(error.kind, ValidationErrorKind::ErrorMessage { .. }) {
+            return error.to_string();
+        }
+        let (key, parameters) = parameters(self, &error.kind);
+        let Some(template) = self.templates.get(key) else {
+            return error.to_string();
+        };
+        let mut message = String::with_capacity(template.len());
+        let mut rest = template.as_str();
+        while let Some(start) = rest.find(['{', '}']) {
+            message.push_str(&rest[..start]);
+            rest = &rest[start..];
+            if rest.starts_with("{{") || rest.starts_with("}}") {
+                message.push_str(&rest[..1]);
+                rest = &rest[2..];
+                continue;
+            }
+            let Some(end) = rest.find('}').filter(|_| rest.starts_with('{')) else {
+                // A lone brace
+                message.push_str(&rest[..1]);
+                rest = &rest[1..];
+                continue;
+            };
+            let name = &rest[1..end];
+            match name {
+                "instance" => write_value(&mut message, &error.instance),
+                "instance_path" => message.push_str(error.instance_path.as_str()),
+                "schema_path" => message.push_str(error.schema_path.as_str()),
+                _ => match parameters.iter().find(|(parameter, _)| *parameter == name) {
+                    Some((_, value)) => message.push_str(value),
+                    None => message.push_str(&rest[..=end]),
+                },
+            }
+            rest = &rest[end + 1..];
+        }
+        message.push_str(rest);
+        message
+    }
+}
+
+fn write_value(buffer: &mut String, value: &Value) {
+    write!(buffer, "{value}").expect("Writing to a string never fails");
+}
+
+fn json(value: &Value) -> String {
+    value.to_string()
+}
+
+fn quoted_list(items: &[String]) -> String {
+    let mut buffer = String::new();
+    for (idx, item) in items.iter().enumerate() {
+        if idx > 0 {
+            buffer.push_str(", ");
+        }
+        write_value(&mut buffer, &Value::String(item.clone()));
+    }
+    buffer
+}
+
+/// The catalog key and the kind-specific template parameters of an error.
+fn parameters(
+    catalog: &MessageCatalog,
+    kind: &ValidationErrorKind,
+) -> (&'static str, Vec<(&'static str, String)>) {
+    match kind {
+        ValidationErrorKind::AdditionalItems { limit } => {
+            ("additionalItems", vec![("limit", limit.to_string())])
+        }
+        ValidationErrorKind::AdditionalProperties {
+            unexpected,
+            suggestions,
+        } => (
+            "additionalProperties",
+            vec![
+                ("unexpected", quoted_list(unexpected)),
+                ("suggestions", quoted_list(suggestions)),
+            ],
+        ),
+        ValidationErrorKind::AnyOf { .. } => ("anyOf", Vec::new()),
+        ValidationErrorKind::BacktrackLimitExceeded { error } => {
+            ("backtrackLimitExceeded", vec![("error", error.to_string())])
+        }
+        ValidationErrorKind::Constant {
+            expected_value,
+            suggestions,
+        } => (
+            "const",
+            vec![
+                ("expected", json(expected_value)),
+                ("suggestions", quoted_list(suggestions)),
+            ],
+        ),
+        ValidationErrorKind::Contains { .. } => ("contains", Vec::new()),
+        ValidationErrorKind::ContentEncoding { content_encoding } => (
+            "contentEncoding",
+            vec![("content_encoding", content_encoding.clone())],
+        ),
+        ValidationErrorKind::ContentMediaType { content_media_type } => (
+            "contentMediaType",
+            vec![("content_media_type", content_media_type.clone())],
+        ),
+        ValidationErrorKind::Custom { message } => ("custom", vec![("message", message.clone())]),
+        ValidationErrorKind::ErrorMessage { kind, .. } => parameters(catalog, kind),
+        ValidationErrorKind::Enum {
+            options,
+            suggestions,
+        } => (
+            "enum",
+            vec![
+                ("options", json(options)),
+                ("suggestions", quoted_list(suggestions)),
+            ],
+        ),
+        ValidationErrorKind::ExclusiveMaximum { limit } => {
+            ("exclusiveMaximum", vec![("limit", json(limit))])
+        }
+        ValidationErrorKind::ExclusiveMinimum { limit } => {
+            ("exclusiveMinimum", vec![("limit", json(limit))])
+        }
+        ValidationErrorKind::FalseSchema => ("falseSchema", Vec::new()),
+        ValidationErrorKind::Format { format } => ("format", vec![("format", format.clone())]),
+        ValidationErrorKind::FromUtf8 { error } => ("fromUtf8", vec![("error", error.to_string())]),
+        ValidationErrorKind::MaxItems { limit } => ("maxItems", vec![("limit", limit.to_string())]),
+        ValidationErrorKind::Maximum { limit } => ("maximum", vec![("limit", json(limit))]),
+        ValidationErrorKind::MaxLength { limit } => {
+            ("maxLength", vec![("limit", limit.to_string())])
+        }
+        ValidationErrorKind::MaxProperties { limit } => {
+            ("maxProperties", vec![("limit", limit.to_string())])
+        }
+        ValidationErrorKind::MinItems { limit } => ("minItems", vec![("limit", limit.to_string())]),
+        ValidationErrorKind::Minimum { limit } => ("minimum", vec![("limit", json(limit))]),
+        ValidationErrorKind::MinLength { limit } => {
+            ("minLength", vec![("limit", limit.to_string())])
+        }
+        ValidationErrorKind::MinProperties { limit } => {
+            ("minProperties", vec![("limit", limit.to_string())])
+        }
+        ValidationErrorKind::MultipleOf { multiple_of } => {
+            ("multipleOf", vec![("multiple_of", multiple_of.to_string())])
+        }
+        ValidationErrorKind::Not { schema } => ("not", vec![("schema", json(schema))]),
+        ValidationErrorKind::OneOfMultipleValid { .. } => ("oneOfMultipleValid", Vec::new()),
+        ValidationErrorKind::OneOfNotValid { .. } => ("oneOfNotValid", Vec::new()),
+        ValidationErrorKind::Pattern { pattern } => ("pattern", vec![("pattern", pattern.clone())]),
+        ValidationErrorKind::PropertyNames { error } => {
+            ("propertyNames", vec![("error", catalog.format(error))])
+        }
+        ValidationErrorKind::Required {
+            property,
+            suggestions,
+        } => (
+            "required",
+            vec![
+                ("property", json(property)),
+                ("suggestions", quoted_list(suggestions)),
+            ],
+        ),
+        ValidationErrorKind::Type { kind } => {
+            let types = match kind {
+                TypeKind::Single(type_) => format!("\"{type_}\""),
+                TypeKind::Multiple(types) => types
+                    .into_iter()
+                    .map(|type_| format!("\"{type_}\""))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            };
+            ("type", vec![("types", types)])
+        }
+        ValidationErrorKind::UnevaluatedItems { unexpected } => (
+            "unevaluatedItems",
+            vec![("unexpected", quoted_list(unexpected))],
+        ),
+        ValidationErrorKind::UnevaluatedProperties { unexpected } => (
+            "unevaluatedProperties",
+            vec![("unexpected", quoted_list(unexpected))],
+        ),
+        ValidationErrorKind::UniqueItems => ("uniqueItems", Vec::new()),
+        ValidationErrorKind::Referencing(error) => {
+            ("referencing", vec![("error", error.to_string())])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DefaultFormatter, ErrorFormatter, MessageCatalog};
+    use crate::ValidationError;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    fn catalog() -> MessageCatalog {
+        MessageCatalog::new([
+            ("type", "{instance} ist nicht vom Typ {types}"),
+            ("minimum", "{instance} ist kleiner als {limit}"),
+            ("required", "{property} fehlt in {instance_path}"),
+            ("additionalProperties", "Unerwartet: {unexpected}"),
+            ("propertyNames", "Ungültiger Name: {error}"),
+            ("maxLength", "Zu lang {unknown}"),
+            (
+                "minLength",
+                "{{{instance}}} ist zu kurz {{limit}}, mindestens {limit} }",
+            ),
+        ])
+    }
+
+    #[test_case(&json!({"type": "integer"}), &json!("a"), r#""a" ist nicht vom Typ "integer""#)]
+    #[test_case(&json!({"type": ["integer", "null"]}), &json!("a"), r#""a" ist nicht vom Typ "integer", "null""#)]
+    #[test_case(&json!({"minimum": 5}), &json!(3), "3 ist kleiner als 5")]
+    #[test_case(&json!({"properties": {"a": {"required": ["b"]}}}), &json!({"a": {}}), r#""b" fehlt in /a"#)]
+    #[test_case(&json!({"additionalProperties": false, "properties": {"a": {}}}), &json!({"a": 1, "b": 2}), r#"Unerwartet: "b""#)]
+    #[test_case(&json!({"propertyNames": {"type": "integer"}}), &json!({"a": 1}), r#"Ungültiger Name: "a" ist nicht vom Typ "integer""#)]
+    #[test_case(&json!({"maxLength": 1}), &json!("ab"), "Zu lang {unknown}"; "unknown parameter")]
+    #[test_case(&json!({"minLength": 2}), &json!("a"), r#"{"a"} ist zu kurz {limit}, mindestens 2 }"#; "escaped braces")]
+    #[test_case(&json!({"maxItems": 1}), &json!([1, 2]), "[1,2] has more than 1 item"; "fallback")]
+    fn catalog_messages(schema: &Value, instance: &Value, expected: &str) {
+        let validator = crate::options()
+            .with_error_formatter(catalog())
+            .build(schema)
+            .expect("Invalid schema");
+        let error = validator.validate(instance).expect_err("Should fail");
+        assert_eq!(validator.format_error(&error), expected);
+    }
+
+    #[test]
+    fn error_message_keyword() {
+        let validator = crate::options()
+            .with_error_formatter(catalog())
+            .should_use_error_messages(true)
+            .build(&json!({"type": "integer", "errorMessage": "Custom ${0}"}))
+            .expect("Invalid schema");
+        let instance = json!("a");
+        let error = validator.validate(&instance).expect_err("Should fail");
+        assert_eq!(validator.format_error(&error), "Custom a");
+    }
+
+    #[test]
+    fn output_formats() {
+        let validator = crate::options()
+            .with_error_formatter(catalog())
+            .build(&json!({"properties": {"a": {"minimum": 5}}}))
+            .expect("Invalid schema");
+        let instance = json!({"a": 3});
+        let output = validator.apply(&instance).basic();
+        let crate::BasicOutput::Invalid(units) = &output else {
+            panic!("Should be invalid");
+        };
+        let messages: Vec<_> = units
+            .iter()
+            .map(|unit| unit.error_description().to_string())
+            .collect();
+        assert_eq!(messages, ["3 ist kleiner als 5"]);
+        assert_eq!(
+            serde_json::to_value(&output).expect("Serializable")["errors"][0]["error"],
+            "3 ist kleiner als 5"
+        );
+    }
+
+    #[test]
+    fn from_reader() {
+        let catalog = MessageCatalog::from_reader(r#"{"minimum": "< {limit}"}"#.as_bytes())
+            .expect("Valid catalog");
+        let validator = crate::options()
+            .with_error_formatter(catalog.with_template("maximum", "> {limit}"))
+            .build(&json!({"minimum": 1, "maximum": 2}))
+            .expect("Invalid schema");
+        let messages: Vec<_> = [json!(0), json!(3)]
+            .iter()
+            .map(|instance| {
+                let error = validator.validate(instance).expect_err("Should fail");
+                validator.format_error(&error)
+            })
+            .collect();
+        assert_eq!(messages, ["< 1", "> 2"]);
+        assert!(MessageCatalog::from_reader(r#"{"minimum": 1}"#.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn closure_and_default() {
+        let formatter = |error: &ValidationError<'_>| format!("at {}", error.instance_path);
+        let validator = crate::options()
+            .with_error_formatter(formatter)
+            .build(&json!({"items": {"type": "string"}}))
+            .expect("Invalid schema");
+        let instance = json!(["a", 1]);
+        let error = validator.validate(&instance).expect_err("Should fail");
+        assert_eq!(validator.format_error(&error), "at /1");
+        assert_eq!(DefaultFormatter.format(&error), error.to_string());
+        let validator = crate::validator_for(&json!({"type": "string"})).expect("Invalid schema");
+        let instance = json!(1);
+        let error = validator.validate(&instance).expect_err("Should fail");
+        assert_eq!(validator.format_error(&error), error.to_string());
+    }
+}
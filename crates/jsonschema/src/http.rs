@@ -0,0 +1,765 @@
+//! Configurable retrieval of external resources over HTTP.
+use ahash::AHashMap;
+use referencing::{PolicyViolation, RetrievalPolicy, Retrieve, Uri};
+use reqwest::{
+    blocking::Client,
+    header::{
+        HeaderMap, HeaderName, HeaderValue, ACCEPT, AUTHORIZATION, CONTENT_TYPE, COOKIE, LOCATION,
+        PROXY_AUTHORIZATION, WWW_AUTHENTICATE,
+    },
+    redirect, Proxy, StatusCode, Url,
+};
+use serde_json::Value;
+use std::{error, fmt, io::Read, time::Duration};
+
+/// Headers that are not sent anymore once a redirect leads to another host or port.
+const SENSITIVE_HEADERS: [HeaderName; 4] =
+    [AUTHORIZATION, COOKIE, PROXY_AUTHORIZATION, WWW_AUTHENTICATE];
+
+/// Retriever that fetches external resources over HTTP.
+///
+/// Unlike the built-in retriever, it supports timeouts, response size limits, custom headers,
+/// redirect limits, accepted content types and proxies. Use [`HttpRetriever::builder`] to
+/// configure it.
+///
+/// # Examples
+///
+/// ```rust
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use jsonschema::HttpRetriever;
+/// use std::time::Duration;
+///
+/// let retriever = HttpRetriever::builder()
+///     .with_timeout(Duration::from_secs(10))
+///     .with_max_body_size(1024 * 1024)
+///     .with_host_header("schemas.internal.example.com", "Authorization", "Bearer secret")
+///     .build()?;
+///
+/// let validator = jsonschema::options()
+///     .with_retriever(retriever)
+///     .build(&serde_json::json!({"type": "string"}))?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct HttpRetriever {
+    client: Client,
+    headers: HeaderMap,
+    host_headers: AHashMap<String, HeaderMap>,
+    redirect_limit: usize,
+    policy: RetrievalPolicy,
+    max_body_size: Option<u64>,
+    content_types: Vec<String>,
+}
+
+impl HttpRetriever {
+    /// Create a builder for configuring an [`HttpRetriever`].
+    #[must_use]
+    pub fn builder() -> HttpRetrieverBuilder {
+        HttpRetrieverBuilder::default()
+    }
+
+    fn is_accepted(&self, content_type: &str) -> bool {
+        // Parameters like `charset` don't affect whether the type is accepted
+        let essence = content_type.split(';').next().unwrap_or_default().trim();
+        self.content_types
+            .iter()
+            .any(|accepted| accepted.eq_ignore_ascii_case(essence))
+    }
+
+    fn read_body(
+        &self,
+        response: reqwest::blocking::Response,
+    ) -> Result<Vec<u8>, HttpRetrieverError> {
+        let Some(limit) = self.max_body_size else {
+            return Ok(response.bytes()?.to_vec());
+        };
+        if response
+            .content_length()
+            .is_some_and(|length| length > limit)
+        {
+            return Err(HttpRetrieverError::BodyTooLarge { limit });
+        }
+        // The declared length may be missing or wrong, so the body is limited while reading it
+        let mut body = Vec::new();
+        response
+            .take(limit.saturating_add(1))
+            .read_to_end(&mut body)
+            .map_err(HttpRetrieverError::Io)?;
+        if body.len() as u64 > limit {
+            return Err(HttpRetrieverError::BodyTooLarge { limit });
+        }
+        Ok(body)
+    }
+
+    /// Add the default headers and the headers for the request host.
+    ///
+    /// Sensitive default headers are left out once a redirect crossed hosts, like `reqwest` does.
+    fn add_headers(&self, request: &mut reqwest::blocking::Request, crossed_hosts: bool) {
+        let host_headers = request
+            .url()
+            .host_str()
+            .and_then(|host| self.host_headers.get(host));
+        let headers = request.headers_mut();
+        for (name, value) in &self.headers {
+            if host_headers.is_some_and(|host_headers| host_headers.contains_key(name))
+                || (crossed_hosts && SENSITIVE_HEADERS.contains(name))
+            {
+                continue;
+            }
+            headers.append(name, value.clone());
+        }
+        for (name, value) in host_headers.into_iter().flatten() {
+            headers.append(name, value.clone());
+        }
+    }
+
+    fn check_policy(&self, url: &Url) -> Result<(), HttpRetrieverError> {
+        // Only redirect locations can fail to parse, the initial URL is a valid URI already
+        let uri = referencing::uri::from_str(url.as_str()).map_err(|_| {
+            HttpRetrieverError::InvalidRedirect {
+                location: url.to_string(),
+            }
+        })?;
+        self.policy
+            .check(&uri.borrow())
+            .map_err(|violation| HttpRetrieverError::PolicyViolation {
+                url: url.to_string(),
+                violation,
+            })
+    }
+
+    fn fetch(&self, uri: &Uri<&str>) -> Result<Value, HttpRetrieverError> {
+        // Redirects are followed here rather than by `reqwest`, so that the headers for each host
+        // are only sent to that host
+        let mut request = self.client.get(uri.as_str()).build()?;
+        let mut crossed_hosts = false;
+        let mut redirects = 0;
+        let response = loop {
+            self.check_policy(request.url())?;
+            self.add_headers(&mut request, crossed_hosts);
+            let response = self.client.execute(request)?.error_for_status()?;
+            let Some(location) = redirect_location(&response)? else {
+                break response;
+            };
+            if redirects == self.redirect_limit {
+                return Err(if self.redirect_limit == 0 {
+                    HttpRetrieverError::Redirect {
+                        status: response.status().as_u16(),
+                    }
+                } else {
+                    HttpRetrieverError::TooManyRedirects {
+                        limit: self.redirect_limit,
+                    }
+                });
+            }
+            redirects += 1;
+            let previous = response.url();
+            crossed_hosts |= location.host_str() != previous.host_str()
+                || location.port_or_known_default() != previous.port_or_known_default();
+            request = self.client.get(location).build()?;
+        };
+        if response.status().is_redirection() {
+            // Only redirects without a location to follow, like `304 Not Modified`
+            return Err(HttpRetrieverError::Redirect {
+                status: response.status().as_u16(),
+            });
+        }
+        if !self.content_types.is_empty() {
+            let content_type = response
+                .headers()
+                .get(CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok());
+            if !content_type.is_some_and(|content_type| self.is_accepted(content_type)) {
+                return Err(HttpRetrieverError::UnexpectedContentType {
+                    content_type: content_type.map(str::to_string),
+                });
+            }
+        }
+        let body = self.read_body(response)?;
+        serde_json::from_slice(&body).map_err(HttpRetrieverError::Json)
+    }
+}
+
+/// The URL to follow if `response` is a redirect.
+fn redirect_location(
+    response: &reqwest::blocking::Response,
+) -> Result<Option<Url>, HttpRetrieverError> {
+    if !matches!(
+        response.status(),
+        StatusCode::MOVED_PERMANENTLY
+            | StatusCode::FOUND
+            | StatusCode::SEE_OTHER
+            | StatusCode::TEMPORARY_REDIRECT
+            | StatusCode::PERMANENT_REDIRECT
+    ) {
+        return Ok(None);
+    }
+    let Some(location) = response.headers().get(LOCATION) else {
+        return Ok(None);
+    };
+    let invalid = || HttpRetrieverError::InvalidRedirect {
+        location: String::from_utf8_lossy(location.as_bytes()).into_owned(),
+    };
+    let location = location.to_str().map_err(|_| invalid())?;
+    response
+        .url()
+        .join(location)
+        .map(Some)
+        .map_err(|_| invalid())
+}
+
+impl Retrieve for HttpRetriever {
+    fn retrieve(&self, uri: &Uri<&str>) -> Result<Value, Box<dyn error::Error + Send + Sync>> {
+        Ok(self.fetch(uri)?)
+    }
+}
+
+/// Builder for [`HttpRetriever`].
+#[derive(Debug, Clone, Default)]
+pub struct HttpRetrieverBuilder {
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    max_body_size: Option<u64>,
+    headers: Vec<(String, String)>,
+    host_headers: Vec<(String, String, String)>,
+    redirect_limit: Option<usize>,
+    policy: RetrievalPolicy,
+    content_types: Vec<String>,
+    proxy: Option<String>,
+}
+
+impl HttpRetrieverBuilder {
+    /// Set the total time limit for each request, from connecting until the body is read.
+    pub fn with_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.timeout = Some(timeout);
+        self
+    }
+    /// Set the time limit for connecting to the server.
+    pub fn with_connect_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+    /// Reject responses with bodies larger than `limit` bytes.
+    pub fn with_max_body_size(&mut self, limit: u64) -> &mut Self {
+        self.max_body_size = Some(limit);
+        self
+    }
+    /// Send a header with every request.
+    pub fn with_header(&mut self, name: impl Into<String>, value: impl Into<String>) -> &mut Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+    /// Send a header with requests to `host` only, e.g. credentials for a private schema
+    /// registry.
+    ///
+    /// It takes precedence over a header of the same name set via
+    /// [`HttpRetrieverBuilder::with_header`]. It is not sent when a redirect leads to another
+    /// host, and neither are credentials and cookies set via [`HttpRetrieverBuilder::with_header`].
+    pub fn with_host_header(
+        &mut self,
+        host: impl Into<String>,
+        name: impl Into<String>,
+        value: impl Into<String>,
+    ) -> &mut Self {
+        self.host_headers
+            .push((host.into().to_ascii_lowercase(), name.into(), value.into()));
+        self
+    }
+    /// Follow at most `limit` redirects, 10 by default. Redirects are not followed if `limit` is
+    /// zero.
+    pub fn with_redirect_limit(&mut self, limit: usize) -> &mut Self {
+        self.redirect_limit = Some(limit);
+        self
+    }
+    /// Only request URLs allowed by `policy`, including the targets of redirects.
+    ///
+    /// The policy set via [`crate::ValidationOptions::with_retrieval_policy`] only applies to the
+    /// URIs passed to the retriever, so set it here too to also apply it to redirects.
+    pub fn with_retrieval_policy(&mut self, policy: RetrievalPolicy) -> &mut Self {
+        self.policy = policy;
+        self
+    }
+    /// Accept responses with the given content type, like `application/schema+json`.
+    ///
+    /// Responses with any content type are accepted unless this is called at least once.
+    pub fn with_content_type(&mut self, content_type: impl Into<String>) -> &mut Self {
+        self.content_types.push(content_type.into());
+        self
+    }
+    /// Send all requests through the proxy at `url`.
+    pub fn with_proxy(&mut self, url: impl Into<String>) -> &mut Self {
+        self.proxy = Some(url.into());
+        self
+    }
+    /// Build an [`HttpRetriever`] with the current configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a header or the proxy URL is invalid, or the HTTP client can't be
+    /// created.
+    pub fn build(&self) -> Result<HttpRetriever, HttpRetrieverError> {
+        let mut headers = header_map(
+            self.headers
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_str())),
+        )?;
+        if !self.content_types.is_empty() {
+            let accept = HeaderValue::from_str(&self.content_types.join(", ")).map_err(|_| {
+                HttpRetrieverError::InvalidHeader {
+                    name: ACCEPT.to_string(),
+                }
+            })?;
+            headers.insert(ACCEPT, accept);
+        }
+        // Redirects and headers are handled by `HttpRetriever::fetch`
+        let mut client = Client::builder().redirect(redirect::Policy::none());
+        if let Some(timeout) = self.timeout {
+            client = client.timeout(timeout);
+        }
+        if let Some(timeout) = self.connect_timeout {
+            client = client.connect_timeout(timeout);
+        }
+        if let Some(proxy) = &self.proxy {
+            client = client.proxy(Proxy::all(proxy)?);
+        }
+        let mut host_headers: AHashMap<String, Vec<(&str, &str)>> = AHashMap::new();
+        for (host, name, value) in &self.host_headers {
+            host_headers
+                .entry(host.clone())
+                .or_default()
+                .push((name.as_str(), value.as_str()));
+        }
+        let host_headers = host_headers
+            .into_iter()
+            .map(|(host, headers)| Ok((host, header_map(headers.into_iter())?)))
+            .collect::<Result<_, HttpRetrieverError>>()?;
+        Ok(HttpRetriever {
+            client: client.build()?,
+            headers,
+            host_headers,
+            redirect_limit: self.redirect_limit.unwrap_or(10),
+            max_body_size: self.max_body_size,
+            policy: self.policy.clone(),
+            content_types: self.content_types.clone(),
+        })
+    }
+}
+
+fn header_map<'a>(
+    headers: impl Iterator<Item = (&'a str, &'a str)>,
+) -> Result<HeaderMap, HttpRetrieverError> {
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        let invalid = || HttpRetrieverError::InvalidHeader {
+            name: name.to_string(),
+        };
+        let name = HeaderName::from_bytes(name.as_bytes()).map_err(|_| invalid())?;
+        let mut value = HeaderValue::from_str(value).map_err(|_| invalid())?;
+        // Keep credentials out of debug output
+        value.set_sensitive(true);
+        map.append(name, value);
+    }
+    Ok(map)
+}
+
+/// An error that can occur when configuring an [`HttpRetriever`] or retrieving a resource with it.
+#[derive(Debug)]
+pub enum HttpRetrieverError {
+    /// A header name or value is invalid.
+    InvalidHeader {
+        /// Name of the header.
+        name: String,
+    },
+    /// The HTTP client could not be created, the request failed or the server responded with an
+    /// error status.
+    Http(reqwest::Error),
+    /// The server responded with a redirect, but following redirects is disabled or there is no
+    /// location to follow.
+    Redirect {
+        /// Status code of the response.
+        status: u16,
+    },
+    /// The server responded with more redirects than the configured limit.
+    TooManyRedirects {
+        /// Maximum number of redirects to follow.
+        limit: usize,
+    },
+    /// The server responded with a redirect to an invalid location.
+    InvalidRedirect {
+        /// Value of the `Location` header.
+        location: String,
+    },
+    /// Requesting a URL is not allowed by the configured retrieval policy.
+    PolicyViolation {
+        /// The denied URL.
+        url: String,
+        /// The violated rule.
+        violation: PolicyViolation,
+    },
+    /// The response body could not be read.
+    Io(std::io::Error),
+    /// The response body is larger than the configured limit.
+    BodyTooLarge {
+        /// Maximum allowed size in bytes.
+        limit: u64,
+    },
+    /// The response has a content type that is not accepted.
+    UnexpectedContentType {
+        /// Content type of the response, if any.
+        content_type: Option<String>,
+    },
+    /// The response body is not valid JSON.
+    Json(serde_json::Error),
+}
+
+impl error::Error for HttpRetrieverError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            HttpRetrieverError::Http(error) => Some(error),
+            HttpRetrieverError::Io(error) => Some(error),
+            HttpRetrieverError::Json(error) => Some(error),
+            HttpRetrieverError::PolicyViolation { violation, .. } => Some(violation),
+            HttpRetrieverError::InvalidHeader { .. }
+            | HttpRetrieverError::Redirect { .. }
+            | HttpRetrieverError::TooManyRedirects { .. }
+            | HttpRetrieverError::InvalidRedirect { .. }
+            | HttpRetrieverError::BodyTooLarge { .. }
+            | HttpRetrieverError::UnexpectedContentType { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for HttpRetrieverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HttpRetrieverError::InvalidHeader { name } => write!(f, "Invalid HTTP header '{name}'"),
+            HttpRetrieverError::Http(error) => error.fmt(f),
+            HttpRetrieverError::Redirect { status } => {
+                write!(f, "Redirect with status {status} was not followed")
+            }
+            HttpRetrieverError::TooManyRedirects { limit } => {
+                write!(f, "Exceeded the limit of {limit} redirects")
+            }
+            HttpRetrieverError::InvalidRedirect { location } => {
+                write!(f, "Invalid redirect location '{location}'")
+            }
+            HttpRetrieverError::PolicyViolation { url, violation } => write!(
+                f,
+                "Retrieving '{url}' is not allowed by the retrieval policy: {violation}"
+            ),
+            HttpRetrieverError::Io(error) => error.fmt(f),
+            HttpRetrieverError::BodyTooLarge { limit } => {
+                write!(f, "Response body is larger than {limit} bytes")
+            }
+            HttpRetrieverError::UnexpectedContentType {
+                content_type: Some(content_type),
+            } => write!(f, "Unexpected content type '{content_type}'"),
+            HttpRetrieverError::UnexpectedContentType { content_type: None } => {
+                f.write_str("Response has no content type")
+            }
+            HttpRetrieverError::Json(error) => error.fmt(f),
+        }
+    }
+}
+
+impl From<reqwest::Error> for HttpRetrieverError {
+    fn from(error: reqwest::Error) -> Self {
+        HttpRetrieverError::Http(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HttpRetriever, HttpRetrieverBuilder, HttpRetrieverError};
+    use mockito::Matcher;
+    use referencing::{uri, RetrievalPolicy, Retrieve};
+    use serde_json::{json, Value};
+    use std::time::Duration;
+
+    fn retrieve(retriever: &HttpRetriever, url: &str) -> Result<Value, String> {
+        let uri = uri::from_str(url).expect("Invalid URI");
+        retriever
+            .retrieve(&uri.borrow())
+            .map_err(|error| error.to_string())
+    }
+
+    fn build(builder: &HttpRetrieverBuilder) -> HttpRetriever {
+        builder.build().expect("Invalid configuration")
+    }
+
+    #[test]
+    fn headers() {
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("GET", "/schema.json")
+            .match_header("x-client", "jsonschema")
+            .match_header("authorization", "Bearer secret")
+            .with_body(r#"{"type": "string"}"#)
+            .create();
+        let retriever = build(
+            HttpRetriever::builder()
+                .with_header("X-Client", "jsonschema")
+                .with_header("Authorization", "Bearer public")
+                .with_host_header("127.0.0.1", "Authorization", "Bearer secret"),
+        );
+        let url = format!("{}/schema.json", server.url());
+        assert_eq!(retrieve(&retriever, &url), Ok(json!({"type": "string"})));
+        mock.assert();
+    }
+
+    #[test]
+    fn host_headers_for_other_hosts() {
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("GET", "/schema.json")
+            .match_header("authorization", Matcher::Missing)
+            .with_body("{}")
+            .create();
+        let retriever = build(HttpRetriever::builder().with_host_header(
+            "schemas.example.com",
+            "Authorization",
+            "Bearer secret",
+        ));
+        let url = format!("{}/schema.json", server.url());
+        assert_eq!(retrieve(&retriever, &url), Ok(json!({})));
+        mock.assert();
+    }
+
+    #[test]
+    fn body_size() {
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", "/large.json")
+            .with_body(format!("[{}]", "1,".repeat(100) + "1"))
+            .create();
+        server.mock("GET", "/small.json").with_body("[1]").create();
+        let retriever = build(HttpRetriever::builder().with_max_body_size(16));
+        assert_eq!(
+            retrieve(&retriever, &format!("{}/large.json", server.url())),
+            Err("Response body is larger than 16 bytes".to_string())
+        );
+        assert_eq!(
+            retrieve(&retriever, &format!("{}/small.json", server.url())),
+            Ok(json!([1]))
+        );
+    }
+
+    #[test]
+    fn body_size_without_content_length() {
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", "/large.json")
+            .with_chunked_body(|writer| writer.write_all(b"[1, 2, 3, 4, 5, 6, 7, 8, 9]"))
+            .create();
+        let retriever = build(HttpRetriever::builder().with_max_body_size(8));
+        assert_eq!(
+            retrieve(&retriever, &format!("{}/large.json", server.url())),
+            Err("Response body is larger than 8 bytes".to_string())
+        );
+    }
+
+    #[test]
+    fn content_types() {
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", "/schema.json")
+            .match_header("accept", "application/schema+json, application/json")
+            .with_header("content-type", "application/schema+json; charset=utf-8")
+            .with_body("{}")
+            .create();
+        server
+            .mock("GET", "/page.html")
+            .with_header("content-type", "text/html")
+            .with_body("<html></html>")
+            .create();
+        let retriever = build(
+            HttpRetriever::builder()
+                .with_content_type("application/schema+json")
+                .with_content_type("application/json"),
+        );
+        assert_eq!(
+            retrieve(&retriever, &format!("{}/schema.json", server.url())),
+            Ok(json!({}))
+        );
+        assert_eq!(
+            retrieve(&retriever, &format!("{}/page.html", server.url())),
+            Err("Unexpected content type 'text/html'".to_string())
+        );
+    }
+
+    #[test]
+    fn redirects() {
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", "/old.json")
+            .with_status(301)
+            .with_header("location", "/older.json")
+            .create();
+        server
+            .mock("GET", "/older.json")
+            .with_status(301)
+            .with_header("location", "/schema.json")
+            .create();
+        server.mock("GET", "/schema.json").with_body("{}").create();
+        let url = format!("{}/old.json", server.url());
+        let retriever = build(HttpRetriever::builder().with_redirect_limit(2));
+        assert_eq!(retrieve(&retriever, &url), Ok(json!({})));
+        let retriever = build(HttpRetriever::builder().with_redirect_limit(1));
+        assert_eq!(
+            retrieve(&retriever, &url),
+            Err("Exceeded the limit of 1 redirects".to_string())
+        );
+        let retriever = build(HttpRetriever::builder().with_redirect_limit(0));
+        assert_eq!(
+            retrieve(&retriever, &url),
+            Err("Redirect with status 301 was not followed".to_string())
+        );
+    }
+
+    #[test]
+    fn cross_host_redirects() {
+        let mut target = mockito::Server::new();
+        let mock = target
+            .mock("GET", "/schema.json")
+            .match_header("authorization", Matcher::Missing)
+            .match_header("x-token", Matcher::Missing)
+            .match_header("x-client", "jsonschema")
+            .with_body("{}")
+            .create();
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", "/old.json")
+            .match_header("x-token", "secret")
+            .with_status(302)
+            .with_header(
+                "location",
+                &format!(
+                    "http://localhost:{}/schema.json",
+                    target.socket_address().port()
+                ),
+            )
+            .create();
+        let retriever = build(
+            HttpRetriever::builder()
+                .with_header("X-Client", "jsonschema")
+                .with_header("Authorization", "Bearer public")
+                .with_host_header("127.0.0.1", "X-Token", "secret"),
+        );
+        assert_eq!(
+            retrieve(&retriever, &format!("{}/old.json", server.url())),
+            Ok(json!({}))
+        );
+        mock.assert();
+    }
+
+    #[test]
+    fn redirect_policy() {
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", "/schema.json")
+            .with_status(302)
+            .with_header("location", "/internal/secret.json")
+            .create();
+        let secret = server
+            .mock("GET", "/internal/secret.json")
+            .with_body("{}")
+            .expect(0)
+            .create();
+        let retriever = build(
+            HttpRetriever::builder()
+                .with_retrieval_policy(RetrievalPolicy::new().deny_path("/internal/")),
+        );
+        let url = server.url();
+        assert_eq!(
+            retrieve(&retriever, &format!("{url}/schema.json")),
+            Err(format!("Retrieving '{url}/internal/secret.json' is not allowed by the retrieval policy: path '/internal/secret.json' is not allowed"))
+        );
+        secret.assert();
+    }
+
+    #[test]
+    fn timeout() {
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", "/slow.json")
+            .with_chunked_body(|writer| {
+                std::thread::sleep(std::time::Duration::from_millis(500));
+                writer.write_all(b"{}")
+            })
+            .create();
+        let retriever = build(HttpRetriever::builder().with_timeout(Duration::from_millis(50)));
+        let uri = uri::from_str(&format!("{}/slow.json", server.url())).expect("Invalid URI");
+        let error = retriever.fetch(&uri.borrow()).expect_err("Should time out");
+        assert!(matches!(error, HttpRetrieverError::Http(error) if error.is_timeout()));
+    }
+
+    #[test]
+    fn error_status() {
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", "/missing.json")
+            .with_status(404)
+            .create();
+        let retriever = build(&HttpRetriever::builder());
+        assert!(
+            retrieve(&retriever, &format!("{}/missing.json", server.url()))
+                .expect_err("Not found")
+                .contains("404 Not Found")
+        );
+    }
+
+    #[test]
+    fn proxy() {
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("GET", Matcher::Any)
+            .with_body(r#"{"type": "integer"}"#)
+            .create();
+        let retriever = build(HttpRetriever::builder().with_proxy(server.url()));
+        assert_eq!(
+            retrieve(&retriever, "http://schemas.example.com/integer.json"),
+            Ok(json!({"type": "integer"}))
+        );
+        mock.assert();
+    }
+
+    #[test]
+    fn invalid_configuration() {
+        let error = HttpRetriever::builder()
+            .with_header("Invalid Name", "value")
+            .build()
+            .expect_err("Invalid header");
+        assert!(matches!(
+            error,
+            HttpRetrieverError::InvalidHeader { ref name } if name == "Invalid Name"
+        ));
+        assert_eq!(error.to_string(), "Invalid HTTP header 'Invalid Name'");
+        assert!(HttpRetriever::builder()
+            .with_host_header("example.com", "X-Token", "new\nline")
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn validator() {
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", "/person.json")
+            .match_header("authorization", "Bearer secret")
+            .with_body(r#"{"properties": {"name": {"type": "string"}}, "required": ["name"]}"#)
+            .create();
+        let retriever = build(HttpRetriever::builder().with_host_header(
+            "127.0.0.1",
+            "Authorization",
+            "Bearer secret",
+        ));
+        let schema = json!({"$ref": format!("{}/person.json", server.url())});
+        let validator = crate::options()
+            .with_retriever(retriever)
+            .build(&schema)
+            .expect("Invalid schema");
+        assert!(validator.is_valid(&json!({"name": "Alice"})));
+        assert!(!validator.is_valid(&json!({})));
+    }
+}
@@ -1,10 +1,13 @@
 use crate::{
+    coercion::Coercer,
     compiler,
     error::{no_error, ErrorIterator, ValidationError},
     keywords::{boolean::FalseValidator, CompilationResult},
     node::SchemaNode,
     paths::{LazyLocation, Location},
     primitive_type::{PrimitiveType, PrimitiveTypesBitMap},
+    properties::prune_items,
+    reader::StreamedSubschemas,
     validator::Validate,
 };
 use serde_json::{Map, Value};
@@ -28,6 +31,38 @@ impl AdditionalItemsObjectValidator {
     }
 }
 impl Validate for AdditionalItemsObjectValidator {
+    fn streamed_subschemas(&self) -> Option<StreamedSubschemas<'_>> {
+        Some(StreamedSubschemas::Items {
+            prefix: &[],
+            skip: self.items_count,
+            rest: Some(&self.node),
+        })
+    }
+
+    fn prune(&self, instance: &mut Value, location: &Location, pruned: &mut Vec<Location>) {
+        prune_items(
+            instance,
+            location,
+            |idx| (idx >= self.items_count).then_some(&self.node),
+            pruned,
+        );
+    }
+
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        coercer.coerce_items(instance, location, |idx| {
+            (idx >= self.items_count).then_some(&self.node)
+        });
+    }
+
+    fn apply_defaults(&self, instance: &mut Value, original: &Value) {
+        if let (Value::Array(items), Value::Array(originals)) = (instance, original) {
+            // Items appended as defaults have no original and are used as they are
+            for (item, original) in items.iter_mut().zip(originals).skip(self.items_count) {
+                self.node.apply_defaults(item, original);
+            }
+        }
+    }
+
     #[allow(clippy::needless_collect)]
     fn iter_errors<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
         if let Value::Array(items) = instance {
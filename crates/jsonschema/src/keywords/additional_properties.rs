@@ -7,14 +7,18 @@
 //!
 //! Each valid combination of these keywords has a validator here.
 use crate::{
+    coercion::Coercer,
     compiler,
     error::{no_error, ErrorIterator, ValidationError},
+    formatter::ErrorFormatter,
     keywords::CompilationResult,
     node::SchemaNode,
-    output::{Annotations, BasicOutput, OutputUnit},
+    output::{ErrorDescription, HierarchicalOutput},
     paths::{LazyLocation, Location},
     primitive_type::PrimitiveType,
     properties::*,
+    reader::StreamedSubschemas,
+    suggestions::Candidates,
     validator::{PartialApplication, Validate},
 };
 use referencing::Uri;
@@ -90,6 +94,18 @@ impl AdditionalPropertiesValidator {
     }
 }
 impl Validate for AdditionalPropertiesValidator {
+    fn streamed_subschemas(&self) -> Option<StreamedSubschemas<'_>> {
+        Some(StreamedSubschemas::Properties)
+    }
+
+    fn prune(&self, instance: &mut Value, location: &Location, pruned: &mut Vec<Location>) {
+        prune_properties(instance, location, Some(&self.node), |_| Vec::new(), pruned);
+    }
+
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        coercer.coerce_properties(instance, location, |_| vec![&self.node]);
+    }
+
     #[allow(clippy::needless_collect)]
     fn iter_errors<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
         if let Value::Object(item) = instance {
@@ -124,13 +140,18 @@ impl Validate for AdditionalPropertiesValidator {
         Ok(())
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        formatter: Option<&dyn ErrorFormatter>,
+    ) -> PartialApplication<'a> {
         if let Value::Object(item) = instance {
             let mut matched_props = Vec::with_capacity(item.len());
-            let mut output = BasicOutput::default();
+            let mut output = Vec::new();
             for (name, value) in item {
                 let path = location.push(name.as_str());
-                output += self.node.apply_rooted(value, &path);
+                output.push(self.node.apply_rooted(value, &path, formatter));
                 matched_props.push(name.clone());
             }
             let mut result: PartialApplication = output.into();
@@ -165,6 +186,10 @@ impl AdditionalPropertiesFalseValidator {
     }
 }
 impl Validate for AdditionalPropertiesFalseValidator {
+    fn prune(&self, instance: &mut Value, location: &Location, pruned: &mut Vec<Location>) {
+        prune_properties(instance, location, None, |_| Vec::new(), pruned);
+    }
+
     fn is_valid(&self, instance: &Value) -> bool {
         if let Value::Object(item) = instance {
             item.iter().next().is_none()
@@ -211,6 +236,8 @@ impl Validate for AdditionalPropertiesFalseValidator {
 /// ```
 pub(crate) struct AdditionalPropertiesNotEmptyFalseValidator<M: PropertiesValidatorsMap> {
     properties: M,
+    // Defined property names that unexpected ones might be misspellings of
+    candidates: Candidates,
     location: Location,
 }
 impl AdditionalPropertiesNotEmptyFalseValidator<SmallValidatorsMap> {
@@ -221,6 +248,7 @@ impl AdditionalPropertiesNotEmptyFalseValidator<SmallValidatorsMap> {
     ) -> CompilationResult<'a> {
         Ok(Box::new(AdditionalPropertiesNotEmptyFalseValidator {
             properties: compile_small_map(ctx, map)?,
+            candidates: Candidates::new(map.keys().map(String::as_str)),
             location: ctx.location().join("additionalProperties"),
         }))
     }
@@ -233,11 +261,40 @@ impl AdditionalPropertiesNotEmptyFalseValidator<BigValidatorsMap> {
     ) -> CompilationResult<'a> {
         Ok(Box::new(AdditionalPropertiesNotEmptyFalseValidator {
             properties: compile_big_map(ctx, map)?,
+            candidates: Candidates::new(map.keys().map(String::as_str)),
             location: ctx.location().join("additionalProperties"),
         }))
     }
 }
 impl<M: PropertiesValidatorsMap> Validate for AdditionalPropertiesNotEmptyFalseValidator<M> {
+    fn prune(&self, instance: &mut Value, location: &Location, pruned: &mut Vec<Location>) {
+        prune_properties(
+            instance,
+            location,
+            None,
+            |property| {
+                self.properties
+                    .get_validator(property)
+                    .into_iter()
+                    .collect()
+            },
+            pruned,
+        );
+    }
+
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        coercer.coerce_properties(instance, location, |property| {
+            self.properties
+                .get_validator(property)
+                .into_iter()
+                .collect()
+        });
+    }
+
+    fn apply_defaults(&self, instance: &mut Value, original: &Value) {
+        apply_property_defaults(self.properties.iter_validators(), instance, original);
+    }
+
     fn iter_errors<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
         if let Value::Object(item) = instance {
             let mut errors = vec![];
@@ -252,11 +309,13 @@ impl<M: PropertiesValidatorsMap> Validate for AdditionalPropertiesNotEmptyFalseV
                 }
             }
             if !unexpected.is_empty() {
+                let suggestions = self.candidates.suggest_each(&unexpected);
                 errors.push(ValidationError::additional_properties(
                     self.location.clone(),
                     location.into(),
                     instance,
                     unexpected,
+                    suggestions,
                 ))
             }
             Box::new(errors.into_iter())
@@ -288,6 +347,7 @@ impl<M: PropertiesValidatorsMap> Validate for AdditionalPropertiesNotEmptyFalseV
                         location.into(),
                         instance,
                         vec![property.clone()],
+                        self.candidates.suggest_each(std::slice::from_ref(property)),
                     ));
                 }
             }
@@ -295,29 +355,36 @@ impl<M: PropertiesValidatorsMap> Validate for AdditionalPropertiesNotEmptyFalseV
         Ok(())
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        formatter: Option<&dyn ErrorFormatter>,
+    ) -> PartialApplication<'a> {
         if let Value::Object(item) = instance {
             let mut unexpected = Vec::with_capacity(item.len());
-            let mut output = BasicOutput::default();
+            let mut output = Vec::new();
             for (property, value) in item {
                 if let Some((_name, node)) = self.properties.get_key_validator(property) {
                     let path = location.push(property.as_str());
-                    output += node.apply_rooted(value, &path);
+                    output.push(node.apply_rooted(value, &path, formatter));
                 } else {
                     unexpected.push(property.clone())
                 }
             }
             let mut result: PartialApplication = output.into();
             if !unexpected.is_empty() {
-                result.mark_errored(
-                    ValidationError::additional_properties(
+                let suggestions = self.candidates.suggest_each(&unexpected);
+                result.mark_errored(ErrorDescription::new(
+                    &ValidationError::additional_properties(
                         self.location.clone(),
                         location.into(),
                         instance,
                         unexpected,
-                    )
-                    .into(),
-                );
+                        suggestions,
+                    ),
+                    formatter,
+                ));
             }
             result
         } else {
@@ -386,6 +453,38 @@ impl AdditionalPropertiesNotEmptyValidator<BigValidatorsMap> {
     }
 }
 impl<M: PropertiesValidatorsMap> Validate for AdditionalPropertiesNotEmptyValidator<M> {
+    fn streamed_subschemas(&self) -> Option<StreamedSubschemas<'_>> {
+        Some(StreamedSubschemas::Properties)
+    }
+
+    fn prune(&self, instance: &mut Value, location: &Location, pruned: &mut Vec<Location>) {
+        prune_properties(
+            instance,
+            location,
+            Some(&self.node),
+            |property| {
+                self.properties
+                    .get_validator(property)
+                    .into_iter()
+                    .collect()
+            },
+            pruned,
+        );
+    }
+
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        coercer.coerce_properties(instance, location, |property| {
+            vec![self
+                .properties
+                .get_validator(property)
+                .unwrap_or(&self.node)]
+        });
+    }
+
+    fn apply_defaults(&self, instance: &mut Value, original: &Value) {
+        apply_property_defaults(self.properties.iter_validators(), instance, original);
+    }
+
     fn iter_errors<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
         if let Value::Object(map) = instance {
             let mut errors = vec![];
@@ -431,18 +530,23 @@ impl<M: PropertiesValidatorsMap> Validate for AdditionalPropertiesNotEmptyValida
         Ok(())
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        formatter: Option<&dyn ErrorFormatter>,
+    ) -> PartialApplication<'a> {
         if let Value::Object(map) = instance {
             let mut matched_propnames = Vec::with_capacity(map.len());
-            let mut output = BasicOutput::default();
+            let mut output = Vec::new();
             for (property, value) in map {
                 let path = location.push(property.as_str());
                 if let Some((_name, property_validators)) =
                     self.properties.get_key_validator(property)
                 {
-                    output += property_validators.apply_rooted(value, &path);
+                    output.push(property_validators.apply_rooted(value, &path, formatter));
                 } else {
-                    output += self.node.apply_rooted(value, &path);
+                    output.push(self.node.apply_rooted(value, &path, formatter));
                     matched_propnames.push(property.clone());
                 }
             }
@@ -505,6 +609,30 @@ impl AdditionalPropertiesWithPatternsValidator {
     }
 }
 impl Validate for AdditionalPropertiesWithPatternsValidator {
+    fn streamed_subschemas(&self) -> Option<StreamedSubschemas<'_>> {
+        Some(StreamedSubschemas::Properties)
+    }
+
+    fn prune(&self, instance: &mut Value, location: &Location, pruned: &mut Vec<Location>) {
+        prune_properties(
+            instance,
+            location,
+            Some(&self.node),
+            |property| matching_patterns(&self.patterns, property).collect(),
+            pruned,
+        );
+    }
+
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        coercer.coerce_properties(instance, location, |property| {
+            let mut nodes: Vec<_> = matching_patterns(&self.patterns, property).collect();
+            if nodes.is_empty() {
+                nodes.push(&self.node);
+            }
+            nodes
+        });
+    }
+
     fn iter_errors<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
         if let Value::Object(item) = instance {
             let mut errors = vec![];
@@ -569,9 +697,14 @@ impl Validate for AdditionalPropertiesWithPatternsValidator {
         Ok(())
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        formatter: Option<&dyn ErrorFormatter>,
+    ) -> PartialApplication<'a> {
         if let Value::Object(item) = instance {
-            let mut output = BasicOutput::default();
+            let mut output = Vec::new();
             let mut pattern_matched_propnames = Vec::with_capacity(item.len());
             let mut additional_matched_propnames = Vec::with_capacity(item.len());
             for (property, value) in item {
@@ -581,22 +714,21 @@ impl Validate for AdditionalPropertiesWithPatternsValidator {
                     if pattern.is_match(property).unwrap_or(false) {
                         has_match = true;
                         pattern_matched_propnames.push(property.clone());
-                        output += node.apply_rooted(value, &path)
+                        output.push(node.apply_rooted(value, &path, formatter));
                     }
                 }
                 if !has_match {
                     additional_matched_propnames.push(property.clone());
-                    output += self.node.apply_rooted(value, &path)
+                    output.push(self.node.apply_rooted(value, &path, formatter));
                 }
             }
             if !pattern_matched_propnames.is_empty() {
-                output += OutputUnit::<Annotations<'_>>::annotations(
+                output.push(HierarchicalOutput::annotated(
                     self.pattern_keyword_path.clone(),
                     location.into(),
                     self.pattern_keyword_absolute_location.clone(),
                     Value::from(pattern_matched_propnames).into(),
-                )
-                .into();
+                ));
             }
             let mut result: PartialApplication = output.into();
             if !additional_matched_propnames.is_empty() {
@@ -651,6 +783,22 @@ impl AdditionalPropertiesWithPatternsFalseValidator {
     }
 }
 impl Validate for AdditionalPropertiesWithPatternsFalseValidator {
+    fn prune(&self, instance: &mut Value, location: &Location, pruned: &mut Vec<Location>) {
+        prune_properties(
+            instance,
+            location,
+            None,
+            |property| matching_patterns(&self.patterns, property).collect(),
+            pruned,
+        );
+    }
+
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        coercer.coerce_properties(instance, location, |property| {
+            matching_patterns(&self.patterns, property).collect()
+        });
+    }
+
     fn iter_errors<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
         if let Value::Object(item) = instance {
             let mut errors = vec![];
@@ -676,6 +824,7 @@ impl Validate for AdditionalPropertiesWithPatternsFalseValidator {
                     location.into(),
                     instance,
                     unexpected,
+                    Vec::new(),
                 ))
             }
             Box::new(errors.into_iter())
@@ -714,6 +863,7 @@ impl Validate for AdditionalPropertiesWithPatternsFalseValidator {
                         location.into(),
                         instance,
                         vec![property.clone()],
+                        Vec::new(),
                     ));
                 }
             }
@@ -721,9 +871,14 @@ impl Validate for AdditionalPropertiesWithPatternsFalseValidator {
         Ok(())
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        formatter: Option<&dyn ErrorFormatter>,
+    ) -> PartialApplication<'a> {
         if let Value::Object(item) = instance {
-            let mut output = BasicOutput::default();
+            let mut output = Vec::new();
             let mut unexpected = Vec::with_capacity(item.len());
             let mut pattern_matched_props = Vec::with_capacity(item.len());
             for (property, value) in item {
@@ -733,7 +888,7 @@ impl Validate for AdditionalPropertiesWithPatternsFalseValidator {
                     if pattern.is_match(property).unwrap_or(false) {
                         has_match = true;
                         pattern_matched_props.push(property.clone());
-                        output += node.apply_rooted(value, &path);
+                        output.push(node.apply_rooted(value, &path, formatter));
                     }
                 }
                 if !has_match {
@@ -741,25 +896,25 @@ impl Validate for AdditionalPropertiesWithPatternsFalseValidator {
                 }
             }
             if !pattern_matched_props.is_empty() {
-                output += OutputUnit::<Annotations<'_>>::annotations(
+                output.push(HierarchicalOutput::annotated(
                     self.pattern_keyword_path.clone(),
                     location.into(),
                     self.pattern_keyword_absolute_location.clone(),
                     Value::from(pattern_matched_props).into(),
-                )
-                .into();
+                ));
             }
             let mut result: PartialApplication = output.into();
             if !unexpected.is_empty() {
-                result.mark_errored(
-                    ValidationError::additional_properties(
+                result.mark_errored(ErrorDescription::new(
+                    &ValidationError::additional_properties(
                         self.location.clone(),
                         location.into(),
                         instance,
                         unexpected,
-                    )
-                    .into(),
-                );
+                        Vec::new(),
+                    ),
+                    formatter,
+                ));
             }
             result
         } else {
@@ -836,6 +991,45 @@ impl AdditionalPropertiesWithPatternsNotEmptyValidator<BigValidatorsMap> {
     }
 }
 impl<M: PropertiesValidatorsMap> Validate for AdditionalPropertiesWithPatternsNotEmptyValidator<M> {
+    fn streamed_subschemas(&self) -> Option<StreamedSubschemas<'_>> {
+        Some(StreamedSubschemas::Properties)
+    }
+
+    fn prune(&self, instance: &mut Value, location: &Location, pruned: &mut Vec<Location>) {
+        prune_properties(
+            instance,
+            location,
+            Some(&self.node),
+            |property| {
+                self.properties
+                    .get_validator(property)
+                    .into_iter()
+                    .chain(matching_patterns(&self.patterns, property))
+                    .collect()
+            },
+            pruned,
+        );
+    }
+
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        coercer.coerce_properties(instance, location, |property| {
+            let mut nodes: Vec<_> = self
+                .properties
+                .get_validator(property)
+                .into_iter()
+                .chain(matching_patterns(&self.patterns, property))
+                .collect();
+            if nodes.is_empty() {
+                nodes.push(&self.node);
+            }
+            nodes
+        });
+    }
+
+    fn apply_defaults(&self, instance: &mut Value, original: &Value) {
+        apply_property_defaults(self.properties.iter_validators(), instance, original);
+    }
+
     fn iter_errors<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
         if let Value::Object(item) = instance {
             let mut errors = vec![];
@@ -938,17 +1132,22 @@ impl<M: PropertiesValidatorsMap> Validate for AdditionalPropertiesWithPatternsNo
         Ok(())
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        formatter: Option<&dyn ErrorFormatter>,
+    ) -> PartialApplication<'a> {
         if let Value::Object(item) = instance {
-            let mut output = BasicOutput::default();
+            let mut output = Vec::new();
             let mut additional_matches = Vec::with_capacity(item.len());
             for (property, value) in item {
                 let path = location.push(property.as_str());
                 if let Some((_name, node)) = self.properties.get_key_validator(property) {
-                    output += node.apply_rooted(value, &path);
+                    output.push(node.apply_rooted(value, &path, formatter));
                     for (pattern, node) in &self.patterns {
                         if pattern.is_match(property).unwrap_or(false) {
-                            output += node.apply_rooted(value, &path);
+                            output.push(node.apply_rooted(value, &path, formatter));
                         }
                     }
                 } else {
@@ -956,12 +1155,12 @@ impl<M: PropertiesValidatorsMap> Validate for AdditionalPropertiesWithPatternsNo
                     for (pattern, node) in &self.patterns {
                         if pattern.is_match(property).unwrap_or(false) {
                             has_match = true;
-                            output += node.apply_rooted(value, &path);
+                            output.push(node.apply_rooted(value, &path, formatter));
                         }
                     }
                     if !has_match {
                         additional_matches.push(property.clone());
-                        output += self.node.apply_rooted(value, &path);
+                        output.push(self.node.apply_rooted(value, &path, formatter));
                     }
                 }
             }
@@ -1003,6 +1202,8 @@ pub(crate) struct AdditionalPropertiesWithPatternsNotEmptyFalseValidator<M: Prop
 {
     properties: M,
     patterns: PatternedValidators,
+    // Defined property names that unexpected ones might be misspellings of
+    candidates: Candidates,
     location: Location,
 }
 impl AdditionalPropertiesWithPatternsNotEmptyFalseValidator<SmallValidatorsMap> {
@@ -1016,6 +1217,7 @@ impl AdditionalPropertiesWithPatternsNotEmptyFalseValidator<SmallValidatorsMap>
             AdditionalPropertiesWithPatternsNotEmptyFalseValidator::<SmallValidatorsMap> {
                 properties: compile_small_map(ctx, map)?,
                 patterns,
+                candidates: Candidates::new(map.keys().map(String::as_str)),
                 location: ctx.location().join("additionalProperties"),
             },
         ))
@@ -1032,6 +1234,7 @@ impl AdditionalPropertiesWithPatternsNotEmptyFalseValidator<BigValidatorsMap> {
             AdditionalPropertiesWithPatternsNotEmptyFalseValidator {
                 properties: compile_big_map(ctx, map)?,
                 patterns,
+                candidates: Candidates::new(map.keys().map(String::as_str)),
                 location: ctx.location().join("additionalProperties"),
             },
         ))
@@ -1041,6 +1244,36 @@ impl AdditionalPropertiesWithPatternsNotEmptyFalseValidator<BigValidatorsMap> {
 impl<M: PropertiesValidatorsMap> Validate
     for AdditionalPropertiesWithPatternsNotEmptyFalseValidator<M>
 {
+    fn prune(&self, instance: &mut Value, location: &Location, pruned: &mut Vec<Location>) {
+        prune_properties(
+            instance,
+            location,
+            None,
+            |property| {
+                self.properties
+                    .get_validator(property)
+                    .into_iter()
+                    .chain(matching_patterns(&self.patterns, property))
+                    .collect()
+            },
+            pruned,
+        );
+    }
+
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        coercer.coerce_properties(instance, location, |property| {
+            self.properties
+                .get_validator(property)
+                .into_iter()
+                .chain(matching_patterns(&self.patterns, property))
+                .collect()
+        });
+    }
+
+    fn apply_defaults(&self, instance: &mut Value, original: &Value) {
+        apply_property_defaults(self.properties.iter_validators(), instance, original);
+    }
+
     fn iter_errors<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
         if let Value::Object(item) = instance {
             let mut errors = vec![];
@@ -1072,11 +1305,13 @@ impl<M: PropertiesValidatorsMap> Validate
                 }
             }
             if !unexpected.is_empty() {
+                let suggestions = self.candidates.suggest_each(&unexpected);
                 errors.push(ValidationError::additional_properties(
                     self.location.clone(),
                     location.into(),
                     instance,
                     unexpected,
+                    suggestions,
                 ))
             }
             Box::new(errors.into_iter())
@@ -1139,6 +1374,7 @@ impl<M: PropertiesValidatorsMap> Validate
                             location.into(),
                             instance,
                             vec![property.clone()],
+                            self.candidates.suggest_each(std::slice::from_ref(property)),
                         ));
                     }
                 }
@@ -1147,18 +1383,23 @@ impl<M: PropertiesValidatorsMap> Validate
         Ok(())
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        formatter: Option<&dyn ErrorFormatter>,
+    ) -> PartialApplication<'a> {
         if let Value::Object(item) = instance {
-            let mut output = BasicOutput::default();
+            let mut output = Vec::new();
             let mut unexpected = vec![];
             // No properties are allowed, except ones defined in `properties` or `patternProperties`
             for (property, value) in item {
                 let path = location.push(property.as_str());
                 if let Some((_name, node)) = self.properties.get_key_validator(property) {
-                    output += node.apply_rooted(value, &path);
+                    output.push(node.apply_rooted(value, &path, formatter));
                     for (pattern, node) in &self.patterns {
                         if pattern.is_match(property).unwrap_or(false) {
-                            output += node.apply_rooted(value, &path);
+                            output.push(node.apply_rooted(value, &path, formatter));
                         }
                     }
                 } else {
@@ -1166,7 +1407,7 @@ impl<M: PropertiesValidatorsMap> Validate
                     for (pattern, node) in &self.patterns {
                         if pattern.is_match(property).unwrap_or(false) {
                             has_match = true;
-                            output += node.apply_rooted(value, &path);
+                            output.push(node.apply_rooted(value, &path, formatter));
                         }
                     }
                     if !has_match {
@@ -1176,15 +1417,17 @@ impl<M: PropertiesValidatorsMap> Validate
             }
             let mut result: PartialApplication = output.into();
             if !unexpected.is_empty() {
-                result.mark_errored(
-                    ValidationError::additional_properties(
+                let suggestions = self.candidates.suggest_each(&unexpected);
+                result.mark_errored(ErrorDescription::new(
+                    &ValidationError::additional_properties(
                         self.location.clone(),
                         location.into(),
                         instance,
                         unexpected,
-                    )
-                    .into(),
-                )
+                        suggestions,
+                    ),
+                    formatter,
+                ))
             }
             result
         } else {
@@ -1696,4 +1939,21 @@ mod tests {
         tests_util::expect_errors(&schema, instance, expected);
         tests_util::assert_locations(&schema, instance, locations)
     }
+
+    #[test_case(&json!({"properties": {"email": {}}, "additionalProperties": false}), &json!({"emial": 1}), "Additional properties are not allowed ('emial' was unexpected). Did you mean 'email'?")]
+    #[test_case(&json!({"properties": {"email": {}}, "patternProperties": {"^x-": {}}, "additionalProperties": false}), &json!({"emial": 1}), "Additional properties are not allowed ('emial' was unexpected). Did you mean 'email'?"; "with patterns")]
+    #[test_case(&json!({"properties": {"email": {}}, "additionalProperties": false}), &json!({"phone": 1}), "Additional properties are not allowed ('phone' was unexpected)"; "too different")]
+    fn suggestions(schema: &Value, instance: &Value, expected: &str) {
+        tests_util::expect_errors(schema, instance, &[expected]);
+        assert_eq!(tests_util::validate(schema, instance).to_string(), expected);
+    }
+
+    #[test]
+    fn several_suggestions() {
+        tests_util::expect_errors(
+            &json!({"properties": {"email": {}, "name": {}}, "additionalProperties": false}),
+            &json!({"emial": 1, "nmae": 2}),
+            &["Additional properties are not allowed ('emial', 'nmae' were unexpected). Did you mean 'email' or 'name'?"],
+        );
+    }
 }
@@ -1,10 +1,12 @@
 use crate::{
+    coercion::Coercer,
     compiler,
     error::{ErrorIterator, ValidationError},
+    formatter::ErrorFormatter,
     node::SchemaNode,
-    output::BasicOutput,
     paths::{LazyLocation, Location},
     primitive_type::PrimitiveType,
+    reader::StreamedSubschemas,
     validator::{PartialApplication, Validate},
 };
 use serde_json::{Map, Value};
@@ -33,6 +35,28 @@ impl AllOfValidator {
 }
 
 impl Validate for AllOfValidator {
+    fn streamed_subschemas(&self) -> Option<StreamedSubschemas<'_>> {
+        Some(StreamedSubschemas::InPlace(self.schemas.iter().collect()))
+    }
+
+    fn prune(&self, instance: &mut Value, location: &Location, pruned: &mut Vec<Location>) {
+        for node in &self.schemas {
+            node.prune(instance, location, pruned);
+        }
+    }
+
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        for node in &self.schemas {
+            node.coerce(instance, location, coercer);
+        }
+    }
+
+    fn apply_defaults(&self, instance: &mut Value, original: &Value) {
+        for node in &self.schemas {
+            node.apply_defaults(instance, original);
+        }
+    }
+
     #[allow(clippy::needless_collect)]
     fn iter_errors<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
         let errors: Vec<_> = self
@@ -58,12 +82,16 @@ impl Validate for AllOfValidator {
         Ok(())
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        formatter: Option<&dyn ErrorFormatter>,
+    ) -> PartialApplication<'a> {
         self.schemas
             .iter()
-            .map(move |node| node.apply_rooted(instance, location))
-            .sum::<BasicOutput<'_>>()
-            .into()
+            .map(move |node| node.apply_rooted(instance, location, formatter))
+            .collect()
     }
 }
 
@@ -82,6 +110,22 @@ impl SingleValueAllOfValidator {
 }
 
 impl Validate for SingleValueAllOfValidator {
+    fn streamed_subschemas(&self) -> Option<StreamedSubschemas<'_>> {
+        Some(StreamedSubschemas::InPlace(vec![&self.node]))
+    }
+
+    fn prune(&self, instance: &mut Value, location: &Location, pruned: &mut Vec<Location>) {
+        self.node.prune(instance, location, pruned);
+    }
+
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        self.node.coerce(instance, location, coercer);
+    }
+
+    fn apply_defaults(&self, instance: &mut Value, original: &Value) {
+        self.node.apply_defaults(instance, original);
+    }
+
     fn iter_errors<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
         self.node.iter_errors(instance, location)
     }
@@ -98,8 +142,13 @@ impl Validate for SingleValueAllOfValidator {
         self.node.validate(instance, location)
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
-        self.node.apply_rooted(instance, location).into()
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        formatter: Option<&dyn ErrorFormatter>,
+    ) -> PartialApplication<'a> {
+        vec![self.node.apply_rooted(instance, location, formatter)].into()
     }
 }
 
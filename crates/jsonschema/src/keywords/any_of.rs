@@ -1,6 +1,8 @@
 use crate::{
+    coercion::Coercer,
     compiler,
     error::{error, no_error, ErrorIterator, ValidationError},
+    formatter::ErrorFormatter,
     node::SchemaNode,
     paths::{LazyLocation, Location},
     primitive_type::PrimitiveType,
@@ -50,6 +52,7 @@ impl Validate for AnyOfValidator {
                 self.location.clone(),
                 location.into(),
                 instance,
+                branch_errors(&self.schemas, instance, location),
             ))
         }
     }
@@ -66,19 +69,57 @@ impl Validate for AnyOfValidator {
         if self.is_valid(instance) {
             Ok(())
         } else {
+            // Branch errors are only collected by `iter_errors`
             Err(ValidationError::any_of(
                 self.location.clone(),
                 location.into(),
                 instance,
+                Vec::new(),
             ))
         }
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        for node in &self.schemas {
+            if coercer.try_coerce(node, instance, location) {
+                break;
+            }
+        }
+    }
+
+    fn prune(&self, instance: &mut Value, location: &Location, pruned: &mut Vec<Location>) {
+        if self.is_valid(instance) {
+            return;
+        }
+        for node in &self.schemas {
+            let mut candidate = instance.clone();
+            let mut removed = Vec::new();
+            node.prune(&mut candidate, location, &mut removed);
+            if node.is_valid(&candidate) {
+                *instance = candidate;
+                pruned.extend(removed);
+                return;
+            }
+        }
+    }
+
+    fn apply_defaults(&self, instance: &mut Value, original: &Value) {
+        // Only the first branch the instance matches before any defaults are inserted
+        if let Some(node) = self.schemas.iter().find(|node| node.is_valid(original)) {
+            node.apply_defaults(instance, original);
+        }
+    }
+
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        formatter: Option<&dyn ErrorFormatter>,
+    ) -> PartialApplication<'a> {
         let mut successes = Vec::new();
         let mut failures = Vec::new();
         for node in &self.schemas {
-            let result = node.apply_rooted(instance, location);
+            let result = node.apply_rooted(instance, location, formatter);
             if result.is_valid() {
                 successes.push(result);
             } else {
@@ -93,6 +134,22 @@ impl Validate for AnyOfValidator {
     }
 }
 
+/// Errors from each of `schemas`, in the same order.
+pub(crate) fn branch_errors(
+    schemas: &[SchemaNode],
+    instance: &Value,
+    location: &LazyLocation,
+) -> Vec<Vec<ValidationError<'static>>> {
+    schemas
+        .iter()
+        .map(|node| {
+            node.iter_errors(instance, location)
+                .map(ValidationError::to_owned)
+                .collect()
+        })
+        .collect()
+}
+
 #[inline]
 pub(crate) fn compile<'a>(
     ctx: &compiler::Context,
@@ -104,7 +161,7 @@ pub(crate) fn compile<'a>(
 
 #[cfg(test)]
 mod tests {
-    use crate::tests_util;
+    use crate::{error::ValidationErrorKind, tests_util};
     use serde_json::{json, Value};
     use test_case::test_case;
 
@@ -113,4 +170,31 @@ mod tests {
     fn location(schema: &Value, instance: &Value, expected: &str) {
         tests_util::assert_schema_location(schema, instance, expected)
     }
+
+    #[test]
+    fn context() {
+        let validator = crate::validator_for(&json!({
+            "anyOf": [
+                {"type": "string"},
+                {"properties": {"a": {"type": "integer"}}, "required": ["b"]}
+            ]
+        }))
+        .expect("Invalid schema");
+        let instance = json!({"a": "x"});
+        let error = validator
+            .iter_errors(&instance)
+            .next()
+            .expect("Should fail");
+        let ValidationErrorKind::AnyOf { context } = error.kind else {
+            panic!("Unexpected kind: {:?}", error.kind);
+        };
+        let paths: Vec<Vec<_>> = context
+            .iter()
+            .map(|errors| errors.iter().map(|e| e.instance_path.to_string()).collect())
+            .collect();
+        assert_eq!(paths, [vec![""], vec!["/a", ""]]);
+        // `validate` stops at the first error without collecting branch errors
+        let error = validator.validate(&instance).expect_err("Should fail");
+        assert!(matches!(error.kind, ValidationErrorKind::AnyOf { context } if context.is_empty()));
+    }
 }
@@ -3,6 +3,7 @@ use crate::{
     error::ValidationError,
     keywords::{helpers, CompilationResult},
     paths::Location,
+    suggestions::Candidates,
     validator::Validate,
 };
 use serde_json::{Map, Number, Value};
@@ -209,6 +210,7 @@ impl Validate for ConstObjectValidator {
 
 pub(crate) struct ConstStringValidator {
     value: String,
+    candidates: Candidates,
     location: Location,
 }
 
@@ -217,6 +219,7 @@ impl ConstStringValidator {
     pub(crate) fn compile(value: &str, location: Location) -> CompilationResult {
         Ok(Box::new(ConstStringValidator {
             value: value.to_string(),
+            candidates: Candidates::new([value]),
             location,
         }))
     }
@@ -236,6 +239,7 @@ impl Validate for ConstStringValidator {
                 location.into(),
                 instance,
                 &self.value,
+                self.candidates.suggest_for(instance),
             ))
         }
     }
@@ -267,7 +271,7 @@ pub(crate) fn compile<'a>(
 
 #[cfg(test)]
 mod tests {
-    use crate::tests_util;
+    use crate::{error::ValidationErrorKind, tests_util};
     use serde_json::{json, Value};
     use test_case::test_case;
 
@@ -280,4 +284,17 @@ mod tests {
     fn location(schema: &Value, instance: &Value, expected: &str) {
         tests_util::assert_schema_location(schema, instance, expected)
     }
+
+    #[test_case(&json!({"const": "Pending"}), &json!("Pendng"), &["Pending"])]
+    #[test_case(&json!({"const": "Pending"}), &json!("Unknown"), &[]; "too different")]
+    #[test_case(&json!({"const": "Pending"}), &json!(1), &[]; "not a string")]
+    fn suggestions(schema: &Value, instance: &Value, expected: &[&str]) {
+        let error = tests_util::validate(schema, instance);
+        // The suggestion would only repeat the expected value
+        assert_eq!(error.to_string(), "\"Pending\" was expected");
+        let ValidationErrorKind::Constant { suggestions, .. } = error.kind else {
+            panic!("Unexpected error kind: {:?}", error.kind);
+        };
+        assert_eq!(suggestions, expected);
+    }
 }
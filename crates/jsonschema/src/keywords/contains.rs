@@ -1,8 +1,10 @@
 use crate::{
     compiler,
-    error::ValidationError,
+    error::{error, no_error, ErrorIterator, ValidationError},
+    formatter::ErrorFormatter,
     keywords::CompilationResult,
     node::SchemaNode,
+    output::ErrorDescription,
     paths::LazyLocation,
     validator::{PartialApplication, Validate},
     Draft,
@@ -23,21 +25,13 @@ impl ContainsValidator {
             node: compiler::compile(&ctx, ctx.as_resource_ref(schema))?,
         }))
     }
-}
 
-impl Validate for ContainsValidator {
-    fn is_valid(&self, instance: &Value) -> bool {
-        if let Value::Array(items) = instance {
-            items.iter().any(|i| self.node.is_valid(i))
-        } else {
-            true
-        }
-    }
-
-    fn validate<'i>(
+    /// Validate `instance`, collecting the errors of each item only if `with_context` is set.
+    fn check<'i>(
         &self,
         instance: &'i Value,
         location: &LazyLocation,
+        with_context: bool,
     ) -> Result<(), ValidationError<'i>> {
         if let Value::Array(items) = instance {
             if items.iter().any(|i| self.node.is_valid(i)) {
@@ -47,19 +41,50 @@ impl Validate for ContainsValidator {
                 self.node.location().clone(),
                 location.into(),
                 instance,
+                item_errors(&self.node, items, location, with_context),
             ))
         } else {
             Ok(())
         }
     }
+}
+
+impl Validate for ContainsValidator {
+    fn is_valid(&self, instance: &Value) -> bool {
+        if let Value::Array(items) = instance {
+            items.iter().any(|i| self.node.is_valid(i))
+        } else {
+            true
+        }
+    }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn iter_errors<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
+        match self.check(instance, location, true) {
+            Ok(()) => no_error(),
+            Err(err) => error(err),
+        }
+    }
+
+    fn validate<'i>(
+        &self,
+        instance: &'i Value,
+        location: &LazyLocation,
+    ) -> Result<(), ValidationError<'i>> {
+        self.check(instance, location, false)
+    }
+
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        formatter: Option<&dyn ErrorFormatter>,
+    ) -> PartialApplication<'a> {
         if let Value::Array(items) = instance {
             let mut results = Vec::with_capacity(items.len());
             let mut indices = Vec::new();
             for (idx, item) in items.iter().enumerate() {
                 let path = location.push(idx);
-                let result = self.node.apply_rooted(item, &path);
+                let result = self.node.apply_rooted(item, &path, formatter);
                 if result.is_valid() {
                     indices.push(idx);
                     results.push(result);
@@ -67,14 +92,15 @@ impl Validate for ContainsValidator {
             }
             let mut result: PartialApplication = results.into_iter().collect();
             if indices.is_empty() {
-                result.mark_errored(
-                    ValidationError::contains(
+                result.mark_errored(ErrorDescription::new(
+                    &ValidationError::contains(
                         self.node.location().clone(),
                         location.into(),
                         instance,
-                    )
-                    .into(),
-                );
+                        item_errors(&self.node, items, location, true),
+                    ),
+                    formatter,
+                ));
             } else {
                 result.annotate(Value::from(indices).into());
             }
@@ -108,13 +134,13 @@ impl MinContainsValidator {
             min_contains,
         }))
     }
-}
 
-impl Validate for MinContainsValidator {
-    fn validate<'i>(
+    /// Validate `instance`, collecting the errors of each item only if `with_context` is set.
+    fn check<'i>(
         &self,
         instance: &'i Value,
         location: &LazyLocation,
+        with_context: bool,
     ) -> Result<(), ValidationError<'i>> {
         if let Value::Array(items) = instance {
             let mut matches = 0;
@@ -135,6 +161,7 @@ impl Validate for MinContainsValidator {
                     self.node.location().clone(),
                     location.into(),
                     instance,
+                    item_errors(&self.node, items, location, with_context),
                 ))
             } else {
                 Ok(())
@@ -143,6 +170,23 @@ impl Validate for MinContainsValidator {
             Ok(())
         }
     }
+}
+
+impl Validate for MinContainsValidator {
+    fn iter_errors<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
+        match self.check(instance, location, true) {
+            Ok(()) => no_error(),
+            Err(err) => error(err),
+        }
+    }
+
+    fn validate<'i>(
+        &self,
+        instance: &'i Value,
+        location: &LazyLocation,
+    ) -> Result<(), ValidationError<'i>> {
+        self.check(instance, location, false)
+    }
 
     fn is_valid(&self, instance: &Value) -> bool {
         if let Value::Array(items) = instance {
@@ -187,13 +231,13 @@ impl MaxContainsValidator {
             max_contains,
         }))
     }
-}
 
-impl Validate for MaxContainsValidator {
-    fn validate<'i>(
+    /// Validate `instance`, collecting the errors of each item only if `with_context` is set.
+    fn check<'i>(
         &self,
         instance: &'i Value,
         location: &LazyLocation,
+        with_context: bool,
     ) -> Result<(), ValidationError<'i>> {
         if let Value::Array(items) = instance {
             let mut matches = 0;
@@ -209,6 +253,7 @@ impl Validate for MaxContainsValidator {
                             self.node.location().clone(),
                             location.into(),
                             instance,
+                            Vec::new(),
                         ));
                     }
                 }
@@ -220,12 +265,30 @@ impl Validate for MaxContainsValidator {
                     self.node.location().clone(),
                     location.into(),
                     instance,
+                    item_errors(&self.node, items, location, with_context),
                 ))
             }
         } else {
             Ok(())
         }
     }
+}
+
+impl Validate for MaxContainsValidator {
+    fn iter_errors<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
+        match self.check(instance, location, true) {
+            Ok(()) => no_error(),
+            Err(err) => error(err),
+        }
+    }
+
+    fn validate<'i>(
+        &self,
+        instance: &'i Value,
+        location: &LazyLocation,
+    ) -> Result<(), ValidationError<'i>> {
+        self.check(instance, location, false)
+    }
 
     fn is_valid(&self, instance: &Value) -> bool {
         if let Value::Array(items) = instance {
@@ -274,13 +337,13 @@ impl MinMaxContainsValidator {
             max_contains,
         }))
     }
-}
 
-impl Validate for MinMaxContainsValidator {
-    fn validate<'i>(
+    /// Validate `instance`, collecting the errors of each item only if `with_context` is set.
+    fn check<'i>(
         &self,
         instance: &'i Value,
         location: &LazyLocation,
+        with_context: bool,
     ) -> Result<(), ValidationError<'i>> {
         if let Value::Array(items) = instance {
             let mut matches = 0;
@@ -296,6 +359,7 @@ impl Validate for MinMaxContainsValidator {
                             self.node.location().join("maxContains"),
                             location.into(),
                             instance,
+                            Vec::new(),
                         ));
                     }
                 }
@@ -305,6 +369,7 @@ impl Validate for MinMaxContainsValidator {
                     self.node.location().join("minContains"),
                     location.into(),
                     instance,
+                    item_errors(&self.node, items, location, with_context),
                 ))
             } else {
                 Ok(())
@@ -313,6 +378,23 @@ impl Validate for MinMaxContainsValidator {
             Ok(())
         }
     }
+}
+
+impl Validate for MinMaxContainsValidator {
+    fn iter_errors<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
+        match self.check(instance, location, true) {
+            Ok(()) => no_error(),
+            Err(err) => error(err),
+        }
+    }
+
+    fn validate<'i>(
+        &self,
+        instance: &'i Value,
+        location: &LazyLocation,
+    ) -> Result<(), ValidationError<'i>> {
+        self.check(instance, location, false)
+    }
     fn is_valid(&self, instance: &Value) -> bool {
         if let Value::Array(items) = instance {
             let mut matches = 0;
@@ -335,6 +417,28 @@ impl Validate for MinMaxContainsValidator {
     }
 }
 
+/// Errors from each of `items`, in the same order, or nothing if `with_context` is not set.
+fn item_errors(
+    node: &SchemaNode,
+    items: &[Value],
+    location: &LazyLocation,
+    with_context: bool,
+) -> Vec<Vec<ValidationError<'static>>> {
+    if !with_context {
+        return Vec::new();
+    }
+    items
+        .iter()
+        .enumerate()
+        .map(|(idx, item)| {
+            let location = location.push(idx);
+            node.iter_errors(item, &location)
+                .map(ValidationError::to_owned)
+                .collect()
+        })
+        .collect()
+}
+
 #[inline]
 pub(crate) fn compile<'a>(
     ctx: &compiler::Context,
@@ -375,8 +479,9 @@ fn compile_contains<'a>(
 
 #[cfg(test)]
 mod tests {
-    use crate::tests_util;
-    use serde_json::json;
+    use crate::{error::ValidationErrorKind, tests_util};
+    use serde_json::{json, Value};
+    use test_case::test_case;
 
     #[test]
     fn location() {
@@ -386,4 +491,25 @@ mod tests {
             "/contains",
         )
     }
+
+    #[test_case(&json!({"contains": {"minimum": 5}}), &json!([1, 2]), &[1, 1])]
+    #[test_case(&json!({"contains": {"minimum": 5}, "minContains": 2}), &json!([1, 6]), &[1, 0])]
+    #[test_case(&json!({"contains": {"minimum": 5}, "maxContains": 1}), &json!([1, 2]), &[1, 1])]
+    #[test_case(&json!({"contains": {"minimum": 5}, "maxContains": 1}), &json!([6, 7]), &[])]
+    #[test_case(&json!({"contains": {"minimum": 5}, "minContains": 1, "maxContains": 1}), &json!([1]), &[1])]
+    fn context(schema: &Value, instance: &Value, expected: &[usize]) {
+        let validator = crate::validator_for(schema).expect("Invalid schema");
+        let error = validator.validate(instance).expect_err("Should fail");
+        assert!(
+            matches!(&error.kind, ValidationErrorKind::Contains { context } if context.is_empty())
+        );
+        let error = validator.iter_errors(instance).next().expect("Should fail");
+        let ValidationErrorKind::Contains { context } = &error.kind else {
+            panic!("Unexpected kind: {:?}", error.kind);
+        };
+        assert_eq!(context.iter().map(Vec::len).collect::<Vec<_>>(), expected);
+        if let Some(item_error) = context.first().and_then(|errors| errors.first()) {
+            assert_eq!(item_error.instance_path.as_str(), "/0");
+        }
+    }
 }
@@ -4,6 +4,7 @@ use crate::{
     keywords::{helpers, CompilationResult},
     paths::{LazyLocation, Location},
     primitive_type::{PrimitiveType, PrimitiveTypesBitMap},
+    suggestions::Candidates,
     validator::Validate,
 };
 use serde_json::{Map, Value};
@@ -14,6 +15,7 @@ pub(crate) struct EnumValidator {
     // Types that occur in items
     types: PrimitiveTypesBitMap,
     items: Vec<Value>,
+    candidates: Candidates,
     location: Location,
 }
 
@@ -32,6 +34,7 @@ impl EnumValidator {
             options: schema.clone(),
             items: items.to_vec(),
             types,
+            candidates: Candidates::strings(items),
             location,
         }))
     }
@@ -51,6 +54,7 @@ impl Validate for EnumValidator {
                 location.into(),
                 instance,
                 &self.options,
+                self.candidates.suggest_for(instance),
             ))
         }
     }
@@ -71,6 +75,7 @@ impl Validate for EnumValidator {
 pub(crate) struct SingleValueEnumValidator {
     value: Value,
     options: Value,
+    candidates: Candidates,
     location: Location,
 }
 
@@ -84,6 +89,7 @@ impl SingleValueEnumValidator {
         Ok(Box::new(SingleValueEnumValidator {
             options: schema.clone(),
             value: value.clone(),
+            candidates: Candidates::strings([value]),
             location,
         }))
     }
@@ -103,6 +109,7 @@ impl Validate for SingleValueEnumValidator {
                 location.into(),
                 instance,
                 &self.options,
+                self.candidates.suggest_for(instance),
             ))
         }
     }
@@ -147,4 +154,13 @@ mod tests {
     fn location(schema: &Value, instance: &Value, expected: &str) {
         tests_util::assert_schema_location(schema, instance, expected)
     }
+
+    #[test_case(&json!({"enum": ["Pending", "Done"]}), &json!("Pendng"), "\"Pendng\" is not one of [\"Pending\",\"Done\"]. Did you mean \"Pending\"?")]
+    #[test_case(&json!({"enum": ["Pending"]}), &json!("pending"), "\"pending\" is not one of [\"Pending\"]. Did you mean \"Pending\"?")]
+    #[test_case(&json!({"enum": ["Pending", "Done"]}), &json!("Unknown"), "\"Unknown\" is not one of [\"Pending\",\"Done\"]"; "too different")]
+    #[test_case(&json!({"enum": ["Pending", 1]}), &json!(2), "2 is not one of [\"Pending\",1]"; "not a string")]
+    fn suggestions(schema: &Value, instance: &Value, expected: &str) {
+        tests_util::expect_errors(schema, instance, &[expected]);
+        assert_eq!(tests_util::validate(schema, instance).to_string(), expected);
+    }
 }
@@ -1,9 +1,11 @@
 use crate::{
+    coercion::Coercer,
     compiler,
     error::{no_error, ErrorIterator},
+    formatter::ErrorFormatter,
     keywords::CompilationResult,
     node::SchemaNode,
-    paths::LazyLocation,
+    paths::{LazyLocation, Location},
     validator::{PartialApplication, Validate},
     ValidationError,
 };
@@ -65,16 +67,38 @@ impl Validate for IfThenValidator {
         }
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
-        let mut if_result = self.schema.apply_rooted(instance, location);
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        formatter: Option<&dyn ErrorFormatter>,
+    ) -> PartialApplication<'a> {
+        let if_result = self.schema.apply_rooted(instance, location, formatter);
         if if_result.is_valid() {
-            let then_result = self.then_schema.apply_rooted(instance, location);
-            if_result += then_result;
-            if_result.into()
+            let then_result = self.then_schema.apply_rooted(instance, location, formatter);
+            vec![if_result, then_result].into()
         } else {
             PartialApplication::valid_empty()
         }
     }
+
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        if coercer.try_coerce(&self.schema, instance, location) {
+            self.then_schema.coerce(instance, location, coercer);
+        }
+    }
+
+    fn prune(&self, instance: &mut Value, location: &Location, pruned: &mut Vec<Location>) {
+        if self.schema.is_valid(instance) {
+            self.then_schema.prune(instance, location, pruned);
+        }
+    }
+
+    fn apply_defaults(&self, instance: &mut Value, original: &Value) {
+        if self.schema.is_valid(original) {
+            self.then_schema.apply_defaults(instance, original);
+        }
+    }
 }
 
 pub(crate) struct IfElseValidator {
@@ -133,12 +157,35 @@ impl Validate for IfElseValidator {
         }
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
-        let if_result = self.schema.apply_rooted(instance, location);
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        formatter: Option<&dyn ErrorFormatter>,
+    ) -> PartialApplication<'a> {
+        let if_result = self.schema.apply_rooted(instance, location, formatter);
         if if_result.is_valid() {
-            if_result.into()
+            vec![if_result].into()
         } else {
-            self.else_schema.apply_rooted(instance, location).into()
+            vec![self.else_schema.apply_rooted(instance, location, formatter)].into()
+        }
+    }
+
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        if !coercer.try_coerce(&self.schema, instance, location) {
+            self.else_schema.coerce(instance, location, coercer);
+        }
+    }
+
+    fn prune(&self, instance: &mut Value, location: &Location, pruned: &mut Vec<Location>) {
+        if !self.schema.is_valid(instance) {
+            self.else_schema.prune(instance, location, pruned);
+        }
+    }
+
+    fn apply_defaults(&self, instance: &mut Value, original: &Value) {
+        if !self.schema.is_valid(original) {
+            self.else_schema.apply_defaults(instance, original);
         }
     }
 }
@@ -206,13 +253,42 @@ impl Validate for IfThenElseValidator {
         }
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
-        let mut if_result = self.schema.apply_rooted(instance, location);
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        formatter: Option<&dyn ErrorFormatter>,
+    ) -> PartialApplication<'a> {
+        let if_result = self.schema.apply_rooted(instance, location, formatter);
         if if_result.is_valid() {
-            if_result += self.then_schema.apply_rooted(instance, location);
-            if_result.into()
+            let then_result = self.then_schema.apply_rooted(instance, location, formatter);
+            vec![if_result, then_result].into()
+        } else {
+            vec![self.else_schema.apply_rooted(instance, location, formatter)].into()
+        }
+    }
+
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        if coercer.try_coerce(&self.schema, instance, location) {
+            self.then_schema.coerce(instance, location, coercer);
+        } else {
+            self.else_schema.coerce(instance, location, coercer);
+        }
+    }
+
+    fn prune(&self, instance: &mut Value, location: &Location, pruned: &mut Vec<Location>) {
+        if self.schema.is_valid(instance) {
+            self.then_schema.prune(instance, location, pruned);
+        } else {
+            self.else_schema.prune(instance, location, pruned);
+        }
+    }
+
+    fn apply_defaults(&self, instance: &mut Value, original: &Value) {
+        if self.schema.is_valid(original) {
+            self.then_schema.apply_defaults(instance, original);
         } else {
-            self.else_schema.apply_rooted(instance, location).into()
+            self.else_schema.apply_defaults(instance, original);
         }
     }
 }
@@ -1,9 +1,13 @@
 use crate::{
+    coercion::Coercer,
     compiler,
     error::{no_error, ErrorIterator},
+    formatter::ErrorFormatter,
     keywords::CompilationResult,
     node::SchemaNode,
-    paths::LazyLocation,
+    paths::{LazyLocation, Location},
+    properties::{apply_item_defaults, prune_items},
+    reader::StreamedSubschemas,
     validator::{PartialApplication, Validate},
     ValidationError,
 };
@@ -29,6 +33,26 @@ impl ItemsArrayValidator {
     }
 }
 impl Validate for ItemsArrayValidator {
+    fn streamed_subschemas(&self) -> Option<StreamedSubschemas<'_>> {
+        Some(StreamedSubschemas::Items {
+            prefix: &self.items,
+            skip: self.items.len(),
+            rest: None,
+        })
+    }
+
+    fn prune(&self, instance: &mut Value, location: &Location, pruned: &mut Vec<Location>) {
+        prune_items(instance, location, |idx| self.items.get(idx), pruned);
+    }
+
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        coercer.coerce_items(instance, location, |idx| self.items.get(idx));
+    }
+
+    fn apply_defaults(&self, instance: &mut Value, original: &Value) {
+        apply_item_defaults(&self.items, instance, original);
+    }
+
     #[allow(clippy::needless_collect)]
     fn iter_errors<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
         if let Value::Array(items) = instance {
@@ -82,6 +106,31 @@ impl ItemsObjectValidator {
     }
 }
 impl Validate for ItemsObjectValidator {
+    fn streamed_subschemas(&self) -> Option<StreamedSubschemas<'_>> {
+        Some(StreamedSubschemas::Items {
+            prefix: &[],
+            skip: 0,
+            rest: Some(&self.node),
+        })
+    }
+
+    fn prune(&self, instance: &mut Value, location: &Location, pruned: &mut Vec<Location>) {
+        prune_items(instance, location, |_| Some(&self.node), pruned);
+    }
+
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        coercer.coerce_items(instance, location, |_| Some(&self.node));
+    }
+
+    fn apply_defaults(&self, instance: &mut Value, original: &Value) {
+        if let (Value::Array(items), Value::Array(originals)) = (instance, original) {
+            // Items appended as defaults have no original and are used as they are
+            for (item, original) in items.iter_mut().zip(originals) {
+                self.node.apply_defaults(item, original);
+            }
+        }
+    }
+
     #[allow(clippy::needless_collect)]
     fn iter_errors<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
         if let Value::Array(items) = instance {
@@ -117,12 +166,17 @@ impl Validate for ItemsObjectValidator {
         Ok(())
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        formatter: Option<&dyn ErrorFormatter>,
+    ) -> PartialApplication<'a> {
         if let Value::Array(items) = instance {
             let mut results = Vec::with_capacity(items.len());
             for (idx, item) in items.iter().enumerate() {
                 let path = location.push(idx);
-                results.push(self.node.apply_rooted(item, &path));
+                results.push(self.node.apply_rooted(item, &path, formatter));
             }
             let mut output: PartialApplication = results.into_iter().collect();
             // Per draft 2020-12 section https://json-schema.org/draft/2020-12/json-schema-core.html#rfc.section.10.3.1.2
@@ -161,6 +215,38 @@ impl ItemsObjectSkipPrefixValidator {
 }
 
 impl Validate for ItemsObjectSkipPrefixValidator {
+    fn streamed_subschemas(&self) -> Option<StreamedSubschemas<'_>> {
+        Some(StreamedSubschemas::Items {
+            prefix: &[],
+            skip: self.skip_prefix,
+            rest: Some(&self.node),
+        })
+    }
+
+    fn prune(&self, instance: &mut Value, location: &Location, pruned: &mut Vec<Location>) {
+        prune_items(
+            instance,
+            location,
+            |idx| (idx >= self.skip_prefix).then_some(&self.node),
+            pruned,
+        );
+    }
+
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        coercer.coerce_items(instance, location, |idx| {
+            (idx >= self.skip_prefix).then_some(&self.node)
+        });
+    }
+
+    fn apply_defaults(&self, instance: &mut Value, original: &Value) {
+        if let (Value::Array(items), Value::Array(originals)) = (instance, original) {
+            // Items appended as defaults have no original and are used as they are
+            for (item, original) in items.iter_mut().zip(originals).skip(self.skip_prefix) {
+                self.node.apply_defaults(item, original);
+            }
+        }
+    }
+
     #[allow(clippy::needless_collect)]
     fn iter_errors<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
         if let Value::Array(items) = instance {
@@ -204,12 +290,17 @@ impl Validate for ItemsObjectSkipPrefixValidator {
         Ok(())
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        formatter: Option<&dyn ErrorFormatter>,
+    ) -> PartialApplication<'a> {
         if let Value::Array(items) = instance {
             let mut results = Vec::with_capacity(items.len().saturating_sub(self.skip_prefix));
             for (idx, item) in items.iter().enumerate().skip(self.skip_prefix) {
                 let path = location.push(idx);
-                results.push(self.node.apply_rooted(item, &path));
+                results.push(self.node.apply_rooted(item, &path, formatter));
             }
             let mut output: PartialApplication = results.into_iter().collect();
             // Per draft 2020-12 section https://json-schema.org/draft/2020-12/json-schema-core.html#rfc.section.10.3.1.2
@@ -1,9 +1,11 @@
 use crate::{
+    coercion::Coercer,
     compiler,
     error::ValidationError,
     keywords::{type_, CompilationResult},
     paths::{LazyLocation, Location},
     primitive_type::{PrimitiveType, PrimitiveTypesBitMap},
+    suggestions,
     validator::Validate,
 };
 use serde_json::{json, Map, Number, Value};
@@ -28,9 +30,8 @@ impl MultipleTypesValidator {
                             Location::new(),
                             location,
                             item,
-                            &json!([
-                                "array", "boolean", "integer", "null", "number", "object", "string"
-                            ]),
+                            &json!(type_::TYPES),
+                            suggestions::suggest(string, type_::TYPES),
                         ));
                     }
                 }
@@ -49,6 +50,9 @@ impl MultipleTypesValidator {
 }
 
 impl Validate for MultipleTypesValidator {
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        coercer.coerce_to(instance, location, self.types);
+    }
     fn is_valid(&self, instance: &Value) -> bool {
         match instance {
             Value::Array(_) => self.types.contains_type(PrimitiveType::Array),
@@ -92,6 +96,13 @@ impl IntegerTypeValidator {
 }
 
 impl Validate for IntegerTypeValidator {
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        coercer.coerce_to(
+            instance,
+            location,
+            PrimitiveTypesBitMap::new().add_type(PrimitiveType::Integer),
+        );
+    }
     fn is_valid(&self, instance: &Value) -> bool {
         if let Value::Number(num) = instance {
             is_integer(num)
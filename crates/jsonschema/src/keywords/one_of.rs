@@ -1,9 +1,10 @@
 use crate::{
+    coercion::Coercer,
     compiler,
-    error::ValidationError,
-    keywords::CompilationResult,
+    error::{error, no_error, ErrorIterator, ValidationError},
+    formatter::ErrorFormatter,
+    keywords::{any_of::branch_errors, CompilationResult},
     node::SchemaNode,
-    output::BasicOutput,
     paths::{LazyLocation, Location},
     primitive_type::PrimitiveType,
     validator::{PartialApplication, Validate},
@@ -51,6 +52,49 @@ impl OneOfValidator {
         first_valid_idx
     }
 
+    /// Validate `instance`, collecting the errors of each branch only if `with_context` is set.
+    fn check<'i>(
+        &self,
+        instance: &'i Value,
+        location: &LazyLocation,
+        with_context: bool,
+    ) -> Result<(), ValidationError<'i>> {
+        let context = || {
+            if with_context {
+                branch_errors(&self.schemas, instance, location)
+            } else {
+                Vec::new()
+            }
+        };
+        if let Some(idx) = self.get_first_valid(instance) {
+            if self.are_others_valid(instance, idx) {
+                let matched = self
+                    .schemas
+                    .iter()
+                    .enumerate()
+                    .skip(idx)
+                    .filter(|(_, node)| node.is_valid(instance))
+                    .map(|(idx, _)| idx)
+                    .collect();
+                return Err(ValidationError::one_of_multiple_valid(
+                    self.location.clone(),
+                    location.into(),
+                    instance,
+                    context(),
+                    matched,
+                ));
+            }
+            Ok(())
+        } else {
+            Err(ValidationError::one_of_not_valid(
+                self.location.clone(),
+                location.into(),
+                instance,
+                context(),
+            ))
+        }
+    }
+
     #[allow(clippy::arithmetic_side_effects)]
     fn are_others_valid(&self, instance: &Value, idx: usize) -> bool {
         // `idx + 1` will not overflow, because the maximum possible value there is `usize::MAX - 1`
@@ -68,45 +112,85 @@ impl Validate for OneOfValidator {
         let first_valid_idx = self.get_first_valid(instance);
         first_valid_idx.is_some_and(|idx| !self.are_others_valid(instance, idx))
     }
+    fn iter_errors<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
+        match self.check(instance, location, true) {
+            Ok(()) => no_error(),
+            Err(err) => error(err),
+        }
+    }
     fn validate<'i>(
         &self,
         instance: &'i Value,
         location: &LazyLocation,
     ) -> Result<(), ValidationError<'i>> {
-        let first_valid_idx = self.get_first_valid(instance);
-        if let Some(idx) = first_valid_idx {
-            if self.are_others_valid(instance, idx) {
-                return Err(ValidationError::one_of_multiple_valid(
-                    self.location.clone(),
-                    location.into(),
-                    instance,
-                ));
+        self.check(instance, location, false)
+    }
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        for node in &self.schemas {
+            if coercer.try_coerce(node, instance, location) {
+                break;
+            }
+        }
+    }
+
+    fn prune(&self, instance: &mut Value, location: &Location, pruned: &mut Vec<Location>) {
+        if self.is_valid(instance) {
+            return;
+        }
+        // Prune only if exactly one branch can be satisfied this way, otherwise it is ambiguous
+        // which properties are extra
+        let mut accepted = None;
+        for node in &self.schemas {
+            let mut candidate = instance.clone();
+            let mut removed = Vec::new();
+            node.prune(&mut candidate, location, &mut removed);
+            if self.is_valid(&candidate) {
+                if accepted.is_some() {
+                    return;
+                }
+                accepted = Some((candidate, removed));
+            }
+        }
+        if let Some((candidate, removed)) = accepted {
+            *instance = candidate;
+            pruned.extend(removed);
+        }
+    }
+
+    fn apply_defaults(&self, instance: &mut Value, original: &Value) {
+        // Only if exactly one branch matches the instance before any defaults are inserted
+        if let Some(idx) = self.get_first_valid(original) {
+            if !self.are_others_valid(original, idx) {
+                self.schemas[idx].apply_defaults(instance, original);
             }
-            Ok(())
-        } else {
-            Err(ValidationError::one_of_not_valid(
-                self.location.clone(),
-                location.into(),
-                instance,
-            ))
         }
     }
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        formatter: Option<&dyn ErrorFormatter>,
+    ) -> PartialApplication<'a> {
         let mut failures = Vec::new();
         let mut successes = Vec::new();
         for node in &self.schemas {
-            match node.apply_rooted(instance, location) {
-                output @ BasicOutput::Valid(..) => successes.push(output),
-                output @ BasicOutput::Invalid(..) => failures.push(output),
-            };
+            let output = node.apply_rooted(instance, location, formatter);
+            if output.is_valid() {
+                successes.push(output);
+            } else {
+                failures.push(output);
+            }
         }
         if successes.len() == 1 {
-            let success = successes.remove(0);
-            success.into()
+            successes.into()
         } else if successes.len() > 1 {
-            PartialApplication::invalid_empty(vec!["more than one subschema succeeded".into()])
+            PartialApplication::Invalid {
+                errors: vec!["more than one subschema succeeded".into()],
+                child_results: successes,
+            }
         } else if !failures.is_empty() {
-            failures.into_iter().sum::<BasicOutput<'_>>().into()
+            failures.into()
         } else {
             unreachable!("compilation should fail for oneOf with no subschemas")
         }
@@ -124,7 +208,7 @@ pub(crate) fn compile<'a>(
 
 #[cfg(test)]
 mod tests {
-    use crate::tests_util;
+    use crate::{error::ValidationErrorKind, tests_util};
     use serde_json::{json, Value};
     use test_case::test_case;
 
@@ -133,4 +217,49 @@ mod tests {
     fn location(schema: &Value, instance: &Value, expected: &str) {
         tests_util::assert_schema_location(schema, instance, expected)
     }
+
+    #[test]
+    fn not_valid_context() {
+        let validator =
+            crate::validator_for(&json!({"oneOf": [{"type": "string"}, {"minimum": 5}]}))
+                .expect("Invalid schema");
+        let instance = json!(1);
+        let error = validator
+            .iter_errors(&instance)
+            .next()
+            .expect("Should fail");
+        let ValidationErrorKind::OneOfNotValid { context } = error.kind else {
+            panic!("Unexpected kind: {:?}", error.kind);
+        };
+        let paths: Vec<Vec<_>> = context
+            .iter()
+            .map(|errors| errors.iter().map(|e| e.schema_path.to_string()).collect())
+            .collect();
+        assert_eq!(paths, [["/oneOf/0/type"], ["/oneOf/1/minimum"]]);
+    }
+
+    #[test]
+    fn multiple_valid_context() {
+        let validator = crate::validator_for(
+            &json!({"oneOf": [{"type": "integer"}, {"type": "string"}, {"minimum": 0}]}),
+        )
+        .expect("Invalid schema");
+        let instance = json!(1);
+        let error = validator
+            .iter_errors(&instance)
+            .next()
+            .expect("Should fail");
+        let ValidationErrorKind::OneOfMultipleValid { context, matched } = error.kind else {
+            panic!("Unexpected kind: {:?}", error.kind);
+        };
+        assert_eq!(matched, [0, 2]);
+        assert_eq!(context.iter().map(Vec::len).collect::<Vec<_>>(), [0, 1, 0]);
+        // `validate` stops at the first error without collecting branch errors
+        let error = validator.validate(&instance).expect_err("Should fail");
+        let ValidationErrorKind::OneOfMultipleValid { context, matched } = error.kind else {
+            panic!("Unexpected kind: {:?}", error.kind);
+        };
+        assert_eq!(matched, [0, 2]);
+        assert!(context.is_empty());
+    }
 }
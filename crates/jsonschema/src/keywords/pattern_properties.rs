@@ -1,11 +1,14 @@
 use crate::{
+    coercion::Coercer,
     compiler, ecma,
     error::{no_error, ErrorIterator, ValidationError},
+    formatter::ErrorFormatter,
     keywords::CompilationResult,
     node::SchemaNode,
-    output::BasicOutput,
     paths::{LazyLocation, Location},
     primitive_type::PrimitiveType,
+    properties::matching_patterns,
+    reader::StreamedSubschemas,
     validator::{PartialApplication, Validate},
 };
 use fancy_regex::Regex;
@@ -45,6 +48,26 @@ impl PatternPropertiesValidator {
 }
 
 impl Validate for PatternPropertiesValidator {
+    fn streamed_subschemas(&self) -> Option<StreamedSubschemas<'_>> {
+        Some(StreamedSubschemas::Properties)
+    }
+
+    fn prune(&self, instance: &mut Value, location: &Location, pruned: &mut Vec<Location>) {
+        if let Value::Object(object) = instance {
+            for (property, value) in object.iter_mut() {
+                for node in matching_patterns(&self.patterns, property) {
+                    node.prune(value, &location.join(property), pruned);
+                }
+            }
+        }
+    }
+
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        coercer.coerce_properties(instance, location, |property| {
+            matching_patterns(&self.patterns, property).collect()
+        });
+    }
+
     #[allow(clippy::needless_collect)]
     fn iter_errors<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
         if let Value::Object(item) = instance {
@@ -95,16 +118,21 @@ impl Validate for PatternPropertiesValidator {
         Ok(())
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        formatter: Option<&dyn ErrorFormatter>,
+    ) -> PartialApplication<'a> {
         if let Value::Object(item) = instance {
             let mut matched_propnames = Vec::with_capacity(item.len());
-            let mut sub_results = BasicOutput::default();
+            let mut sub_results = Vec::new();
             for (pattern, node) in &self.patterns {
                 for (key, value) in item {
                     if pattern.is_match(key).unwrap_or(false) {
                         let path = location.push(key.as_str());
                         matched_propnames.push(key.clone());
-                        sub_results += node.apply_rooted(value, &path);
+                        sub_results.push(node.apply_rooted(value, &path, formatter));
                     }
                 }
             }
@@ -151,6 +179,30 @@ impl SingleValuePatternPropertiesValidator {
 }
 
 impl Validate for SingleValuePatternPropertiesValidator {
+    fn streamed_subschemas(&self) -> Option<StreamedSubschemas<'_>> {
+        Some(StreamedSubschemas::Properties)
+    }
+
+    fn prune(&self, instance: &mut Value, location: &Location, pruned: &mut Vec<Location>) {
+        if let Value::Object(object) = instance {
+            for (property, value) in object.iter_mut() {
+                if self.pattern.is_match(property).unwrap_or(false) {
+                    self.node.prune(value, &location.join(property), pruned);
+                }
+            }
+        }
+    }
+
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        coercer.coerce_properties(instance, location, |property| {
+            if self.pattern.is_match(property).unwrap_or(false) {
+                vec![&self.node]
+            } else {
+                Vec::new()
+            }
+        });
+    }
+
     #[allow(clippy::needless_collect)]
     fn iter_errors<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
         if let Value::Object(item) = instance {
@@ -193,15 +245,20 @@ impl Validate for SingleValuePatternPropertiesValidator {
         Ok(())
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        formatter: Option<&dyn ErrorFormatter>,
+    ) -> PartialApplication<'a> {
         if let Value::Object(item) = instance {
             let mut matched_propnames = Vec::with_capacity(item.len());
-            let mut outputs = BasicOutput::default();
+            let mut outputs = Vec::new();
             for (key, value) in item {
                 if self.pattern.is_match(key).unwrap_or(false) {
                     let path = location.push(key.as_str());
                     matched_propnames.push(key.clone());
-                    outputs += self.node.apply_rooted(value, &path);
+                    outputs.push(self.node.apply_rooted(value, &path, formatter));
                 }
             }
             let mut result: PartialApplication = outputs.into();
@@ -1,9 +1,13 @@
 use crate::{
+    coercion::Coercer,
     compiler,
     error::{no_error, ErrorIterator, ValidationError},
+    formatter::ErrorFormatter,
     node::SchemaNode,
     paths::{LazyLocation, Location},
     primitive_type::PrimitiveType,
+    properties::{apply_item_defaults, prune_items},
+    reader::StreamedSubschemas,
     validator::{PartialApplication, Validate},
 };
 use serde_json::{Map, Value};
@@ -32,6 +36,26 @@ impl PrefixItemsValidator {
 }
 
 impl Validate for PrefixItemsValidator {
+    fn streamed_subschemas(&self) -> Option<StreamedSubschemas<'_>> {
+        Some(StreamedSubschemas::Items {
+            prefix: &self.schemas,
+            skip: usize::MAX,
+            rest: None,
+        })
+    }
+
+    fn prune(&self, instance: &mut Value, location: &Location, pruned: &mut Vec<Location>) {
+        prune_items(instance, location, |idx| self.schemas.get(idx), pruned);
+    }
+
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        coercer.coerce_items(instance, location, |idx| self.schemas.get(idx));
+    }
+
+    fn apply_defaults(&self, instance: &mut Value, original: &Value) {
+        apply_item_defaults(&self.schemas, instance, original);
+    }
+
     #[allow(clippy::needless_collect)]
     fn iter_errors<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
         if let Value::Array(items) = instance {
@@ -72,7 +96,12 @@ impl Validate for PrefixItemsValidator {
         Ok(())
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        formatter: Option<&dyn ErrorFormatter>,
+    ) -> PartialApplication<'a> {
         if let Value::Array(items) = instance {
             if !items.is_empty() {
                 let validate_total = self.schemas.len();
@@ -81,7 +110,7 @@ impl Validate for PrefixItemsValidator {
                 for (idx, (schema_node, item)) in self.schemas.iter().zip(items.iter()).enumerate()
                 {
                     let path = location.push(idx);
-                    results.push(schema_node.apply_rooted(item, &path));
+                    results.push(schema_node.apply_rooted(item, &path, formatter));
                     max_index_applied = idx;
                 }
                 // Per draft 2020-12 section https://json-schema.org/draft/2020-12/json-schema-core.html#rfc.section.10.3.1.1
@@ -218,11 +247,6 @@ mod tests {
         &json!({
             "valid": true,
             "annotations": [
-                {
-                    "keywordLocation": "/prefixItems",
-                    "instanceLocation": "",
-                    "annotations": 1
-                },
                 {
                     "keywordLocation": "/items",
                     "instanceLocation": "",
@@ -237,6 +261,11 @@ mod tests {
                     "annotations": {"annotation": "value" },
                     "instanceLocation": "/3",
                     "keywordLocation": "/items"
+                },
+                {
+                    "keywordLocation": "/prefixItems",
+                    "instanceLocation": "",
+                    "annotations": 1
                 }
             ]
         }); "valid prefixItems with mixed items"
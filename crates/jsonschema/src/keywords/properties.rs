@@ -1,11 +1,14 @@
 use crate::{
+    coercion::Coercer,
     compiler,
     error::{no_error, ErrorIterator, ValidationError},
+    formatter::ErrorFormatter,
     keywords::CompilationResult,
     node::SchemaNode,
-    output::BasicOutput,
     paths::{LazyLocation, Location},
     primitive_type::PrimitiveType,
+    properties::apply_property_defaults,
+    reader::StreamedSubschemas,
     validator::{PartialApplication, Validate},
 };
 use serde_json::{Map, Value};
@@ -41,6 +44,40 @@ impl PropertiesValidator {
 }
 
 impl Validate for PropertiesValidator {
+    fn streamed_subschemas(&self) -> Option<StreamedSubschemas<'_>> {
+        Some(StreamedSubschemas::Properties)
+    }
+
+    fn prune(&self, instance: &mut Value, location: &Location, pruned: &mut Vec<Location>) {
+        if let Value::Object(object) = instance {
+            for (property, node) in &self.properties {
+                if let Some(value) = object.get_mut(property) {
+                    node.prune(value, &location.join(property), pruned);
+                }
+            }
+        }
+    }
+
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        if let Value::Object(object) = instance {
+            for (property, node) in &self.properties {
+                if let Some(value) = object.get_mut(property) {
+                    node.coerce(value, &location.join(property), coercer);
+                }
+            }
+        }
+    }
+
+    fn apply_defaults(&self, instance: &mut Value, original: &Value) {
+        apply_property_defaults(
+            self.properties
+                .iter()
+                .map(|(property, node)| (property, node)),
+            instance,
+            original,
+        );
+    }
+
     #[allow(clippy::needless_collect)]
     fn iter_errors<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
         if let Value::Object(item) = instance {
@@ -87,15 +124,20 @@ impl Validate for PropertiesValidator {
         Ok(())
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        formatter: Option<&dyn ErrorFormatter>,
+    ) -> PartialApplication<'a> {
         if let Value::Object(props) = instance {
-            let mut result = BasicOutput::default();
+            let mut result = Vec::new();
             let mut matched_props = Vec::with_capacity(props.len());
             for (prop_name, node) in &self.properties {
                 if let Some(prop) = props.get(prop_name) {
                     let path = location.push(prop_name.as_str());
                     matched_props.push(prop_name.clone());
-                    result += node.apply_rooted(prop, &path);
+                    result.push(node.apply_rooted(prop, &path, formatter));
                 }
             }
             let mut application: PartialApplication = result.into();
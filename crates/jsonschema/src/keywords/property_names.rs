@@ -1,6 +1,7 @@
 use crate::{
     compiler,
     error::{no_error, ErrorIterator, ValidationError},
+    formatter::ErrorFormatter,
     keywords::CompilationResult,
     node::SchemaNode,
     paths::{LazyLocation, Location},
@@ -86,12 +87,17 @@ impl Validate for PropertyNamesObjectValidator {
         Ok(())
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        formatter: Option<&dyn ErrorFormatter>,
+    ) -> PartialApplication<'a> {
         if let Value::Object(item) = instance {
             item.keys()
                 .map(|key| {
                     let wrapper = Value::String(key.to_string());
-                    self.node.apply_rooted(&wrapper, location)
+                    self.node.apply_rooted(&wrapper, location, formatter)
                 })
                 .collect()
         } else {
@@ -163,4 +169,11 @@ mod tests {
     fn location(schema: &Value, instance: &Value, expected: &str) {
         tests_util::assert_schema_location(schema, instance, expected)
     }
+
+    #[test_case(&json!({"propertyNames": {"enum": ["email", "name"]}}), &json!({"emial": 1}), "\"emial\" is not one of [\"email\",\"name\"]. Did you mean \"email\"?")]
+    #[test_case(&json!({"propertyNames": {"const": "email"}}), &json!({"emial": 1}), "\"email\" was expected")]
+    fn suggestions(schema: &Value, instance: &Value, expected: &str) {
+        tests_util::expect_errors(schema, instance, &[expected]);
+        assert_eq!(tests_util::validate(schema, instance).to_string(), expected);
+    }
 }
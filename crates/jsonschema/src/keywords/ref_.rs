@@ -1,12 +1,15 @@
 use std::{rc::Rc, sync::Arc};
 
 use crate::{
+    coercion::Coercer,
     compiler,
     error::ErrorIterator,
+    formatter::ErrorFormatter,
     keywords::CompilationResult,
     node::SchemaNode,
     paths::{LazyLocation, Location},
     primitive_type::PrimitiveType,
+    reader::StreamedSubschemas,
     validator::{PartialApplication, Validate},
     ValidationError, ValidationOptions,
 };
@@ -156,8 +159,25 @@ impl Validate for LazyRefValidator {
     fn iter_errors<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
         self.lazy_compile().iter_errors(instance, location)
     }
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
-        self.lazy_compile().apply(instance, location)
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        formatter: Option<&dyn ErrorFormatter>,
+    ) -> PartialApplication<'a> {
+        self.lazy_compile().apply(instance, location, formatter)
+    }
+    fn apply_defaults(&self, instance: &mut Value, original: &Value) {
+        self.lazy_compile().apply_defaults(instance, original);
+    }
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        self.lazy_compile().coerce(instance, location, coercer);
+    }
+    fn prune(&self, instance: &mut Value, location: &Location, pruned: &mut Vec<Location>) {
+        self.lazy_compile().prune(instance, location, pruned);
+    }
+    fn streamed_subschemas(&self) -> Option<StreamedSubschemas<'_>> {
+        Some(StreamedSubschemas::InPlace(vec![self.lazy_compile()]))
     }
 }
 
@@ -184,10 +204,39 @@ impl Validate for RefValidator {
             RefValidator::Lazy(lazy) => lazy.iter_errors(instance, location),
         }
     }
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        formatter: Option<&dyn ErrorFormatter>,
+    ) -> PartialApplication<'a> {
+        match self {
+            RefValidator::Default { inner } => inner.apply(instance, location, formatter),
+            RefValidator::Lazy(lazy) => lazy.apply(instance, location, formatter),
+        }
+    }
+    fn apply_defaults(&self, instance: &mut Value, original: &Value) {
+        match self {
+            RefValidator::Default { inner } => inner.apply_defaults(instance, original),
+            RefValidator::Lazy(lazy) => lazy.apply_defaults(instance, original),
+        }
+    }
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        match self {
+            RefValidator::Default { inner } => inner.coerce(instance, location, coercer),
+            RefValidator::Lazy(lazy) => lazy.coerce(instance, location, coercer),
+        }
+    }
+    fn prune(&self, instance: &mut Value, location: &Location, pruned: &mut Vec<Location>) {
+        match self {
+            RefValidator::Default { inner } => inner.prune(instance, location, pruned),
+            RefValidator::Lazy(lazy) => lazy.prune(instance, location, pruned),
+        }
+    }
+    fn streamed_subschemas(&self) -> Option<StreamedSubschemas<'_>> {
         match self {
-            RefValidator::Default { inner } => inner.apply(instance, location),
-            RefValidator::Lazy(lazy) => lazy.apply(instance, location),
+            RefValidator::Default { inner } => Some(StreamedSubschemas::InPlace(vec![inner])),
+            RefValidator::Lazy(lazy) => lazy.streamed_subschemas(),
         }
     }
 }
@@ -4,6 +4,7 @@ use crate::{
     keywords::CompilationResult,
     paths::{LazyLocation, Location},
     primitive_type::PrimitiveType,
+    suggestions,
     validator::Validate,
 };
 use serde_json::{Map, Value};
@@ -32,6 +33,16 @@ impl RequiredValidator {
         }
         Ok(Box::new(RequiredValidator { required, location }))
     }
+
+    /// Properties of `item` that might be misspellings of the missing `property_name`.
+    fn suggestions(&self, property_name: &str, item: &Map<String, Value>) -> Vec<String> {
+        suggestions::suggest(
+            property_name,
+            item.keys()
+                .map(String::as_str)
+                .filter(|key| !self.required.iter().any(|required| required == key)),
+        )
+    }
 }
 
 impl Validate for RequiredValidator {
@@ -59,6 +70,7 @@ impl Validate for RequiredValidator {
                         instance,
                         // Value enum is needed for proper string escaping
                         Value::String(property_name.clone()),
+                        self.suggestions(property_name, item),
                     ));
                 }
             }
@@ -76,6 +88,7 @@ impl Validate for RequiredValidator {
                         instance,
                         // Value enum is needed for proper string escaping
                         Value::String(property_name.clone()),
+                        self.suggestions(property_name, item),
                     ));
                 }
             }
@@ -115,6 +128,12 @@ impl Validate for SingleItemRequiredValidator {
                 instance,
                 // Value enum is needed for proper string escaping
                 Value::String(self.value.clone()),
+                match instance {
+                    Value::Object(item) => {
+                        suggestions::suggest(&self.value, item.keys().map(String::as_str))
+                    }
+                    _ => Vec::new(),
+                },
             ));
         }
         Ok(())
@@ -180,4 +199,12 @@ mod tests {
     fn location(schema: &Value, instance: &Value, expected: &str) {
         tests_util::assert_schema_location(schema, instance, expected)
     }
+
+    #[test_case(&json!({"required": ["email"]}), &json!({"emial": 1}), &["\"email\" is a required property. Is \"emial\" misspelled?"])]
+    #[test_case(&json!({"required": ["email", "name"]}), &json!({"emial": 1, "nmae": 2}), &["\"email\" is a required property. Is \"emial\" misspelled?", "\"name\" is a required property. Is \"nmae\" misspelled?"])]
+    #[test_case(&json!({"required": ["email", "emails"]}), &json!({"emails": 1}), &["\"email\" is a required property"]; "required keys are not suggested")]
+    #[test_case(&json!({"required": ["email"]}), &json!({"phone": 1}), &["\"email\" is a required property"]; "too different")]
+    fn suggestions(schema: &Value, instance: &Value, expected: &[&str]) {
+        tests_util::expect_errors(schema, instance, expected);
+    }
 }
@@ -1,9 +1,11 @@
 use crate::{
+    coercion::Coercer,
     compiler,
     error::ValidationError,
     keywords::CompilationResult,
     paths::Location,
     primitive_type::{PrimitiveType, PrimitiveTypesBitMap},
+    suggestions,
     validator::Validate,
 };
 use serde_json::{json, Map, Number, Value};
@@ -11,6 +13,11 @@ use std::convert::TryFrom;
 
 use crate::paths::LazyLocation;
 
+/// Names of all primitive types.
+pub(crate) const TYPES: [&str; 7] = [
+    "array", "boolean", "integer", "null", "number", "object", "string",
+];
+
 pub(crate) struct MultipleTypesValidator {
     types: PrimitiveTypesBitMap,
     location: Location,
@@ -30,9 +37,8 @@ impl MultipleTypesValidator {
                             Location::new(),
                             location,
                             item,
-                            &json!([
-                                "array", "boolean", "integer", "null", "number", "object", "string"
-                            ]),
+                            &json!(TYPES),
+                            suggestions::suggest(string, TYPES),
                         ));
                     }
                 }
@@ -51,6 +57,9 @@ impl MultipleTypesValidator {
 }
 
 impl Validate for MultipleTypesValidator {
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        coercer.coerce_to(instance, location, self.types);
+    }
     fn is_valid(&self, instance: &Value) -> bool {
         match instance {
             Value::Array(_) => self.types.contains_type(PrimitiveType::Array),
@@ -94,6 +103,13 @@ impl NullTypeValidator {
 }
 
 impl Validate for NullTypeValidator {
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        coercer.coerce_to(
+            instance,
+            location,
+            PrimitiveTypesBitMap::new().add_type(PrimitiveType::Null),
+        );
+    }
     fn is_valid(&self, instance: &Value) -> bool {
         instance.is_null()
     }
@@ -127,6 +143,13 @@ impl BooleanTypeValidator {
 }
 
 impl Validate for BooleanTypeValidator {
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        coercer.coerce_to(
+            instance,
+            location,
+            PrimitiveTypesBitMap::new().add_type(PrimitiveType::Boolean),
+        );
+    }
     fn is_valid(&self, instance: &Value) -> bool {
         instance.is_boolean()
     }
@@ -160,6 +183,13 @@ impl StringTypeValidator {
 }
 
 impl Validate for StringTypeValidator {
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        coercer.coerce_to(
+            instance,
+            location,
+            PrimitiveTypesBitMap::new().add_type(PrimitiveType::String),
+        );
+    }
     fn is_valid(&self, instance: &Value) -> bool {
         instance.is_string()
     }
@@ -194,6 +224,13 @@ impl ArrayTypeValidator {
 }
 
 impl Validate for ArrayTypeValidator {
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        coercer.coerce_to(
+            instance,
+            location,
+            PrimitiveTypesBitMap::new().add_type(PrimitiveType::Array),
+        );
+    }
     fn is_valid(&self, instance: &Value) -> bool {
         instance.is_array()
     }
@@ -228,6 +265,13 @@ impl ObjectTypeValidator {
 }
 
 impl Validate for ObjectTypeValidator {
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        coercer.coerce_to(
+            instance,
+            location,
+            PrimitiveTypesBitMap::new().add_type(PrimitiveType::Object),
+        );
+    }
     fn is_valid(&self, instance: &Value) -> bool {
         instance.is_object()
     }
@@ -261,6 +305,13 @@ impl NumberTypeValidator {
 }
 
 impl Validate for NumberTypeValidator {
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        coercer.coerce_to(
+            instance,
+            location,
+            PrimitiveTypesBitMap::new().add_type(PrimitiveType::Number),
+        );
+    }
     fn is_valid(&self, instance: &Value) -> bool {
         instance.is_number()
     }
@@ -294,6 +345,13 @@ impl IntegerTypeValidator {
 }
 
 impl Validate for IntegerTypeValidator {
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        coercer.coerce_to(
+            instance,
+            location,
+            PrimitiveTypesBitMap::new().add_type(PrimitiveType::Integer),
+        );
+    }
     fn is_valid(&self, instance: &Value) -> bool {
         if let Value::Number(num) = instance {
             is_integer(num)
@@ -399,4 +457,15 @@ mod tests {
     fn location(schema: &Value, instance: &Value, expected: &str) {
         tests_util::assert_schema_location(schema, instance, expected)
     }
+
+    #[test]
+    fn invalid_type_name() {
+        let error =
+            crate::validator_for(&json!({"type": ["strng", "null"]})).expect_err("Invalid schema");
+        let error = crate::best_match(std::iter::once(error)).expect("Has errors");
+        assert_eq!(
+            error.to_string(),
+            r#""strng" is not one of ["array","boolean","integer","null","number","object","string"]. Did you mean "string"?"#
+        );
+    }
 }
@@ -3,9 +3,11 @@ use serde_json::{Map, Value};
 
 use crate::{
     compiler,
+    formatter::ErrorFormatter,
     node::SchemaNode,
+    output::ErrorDescription,
     paths::{LazyLocation, Location},
-    validator::Validate,
+    validator::{PartialApplication, Validate},
     ValidationError,
 };
 
@@ -25,7 +27,19 @@ pub(crate) trait ItemsFilter: Send + Sync + Sized + 'static {
             .unwrap_or(false)
     }
 
-    fn mark_evaluated_indexes(&self, instance: &Value, indexes: &mut Vec<bool>);
+    /// Mark items evaluated by the keywords adjacent to `unevaluatedItems`.
+    fn mark_adjacent_indexes(&self, instance: &Value, indexes: &mut Vec<bool>);
+
+    fn mark_evaluated_indexes(&self, instance: &Value, indexes: &mut Vec<bool>) {
+        self.mark_adjacent_indexes(instance, indexes);
+        if let (Some(unevaluated), Value::Array(items)) = (self.unevaluated(), instance) {
+            for (item, is_evaluated) in items.iter().zip(indexes.iter_mut()) {
+                if !*is_evaluated && unevaluated.is_valid(item) {
+                    *is_evaluated = true;
+                }
+            }
+        }
+    }
 }
 
 pub(crate) struct UnevaluatedItemsValidator<F: ItemsFilter> {
@@ -88,6 +102,46 @@ impl<F: ItemsFilter> Validate for UnevaluatedItemsValidator<F> {
         }
         Ok(())
     }
+
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        formatter: Option<&dyn ErrorFormatter>,
+    ) -> PartialApplication<'a> {
+        if let Value::Array(items) = instance {
+            let mut indexes = vec![false; items.len()];
+            self.filter.mark_adjacent_indexes(instance, &mut indexes);
+            let mut applied = false;
+            let mut unevaluated = vec![];
+            for (item, is_evaluated) in items.iter().zip(indexes) {
+                if is_evaluated {
+                    continue;
+                }
+                if self.filter.is_valid(item) {
+                    applied = true;
+                } else {
+                    unevaluated.push(item.to_string());
+                }
+            }
+            if !unevaluated.is_empty() {
+                return PartialApplication::invalid_empty(vec![ErrorDescription::new(
+                    &ValidationError::unevaluated_items(
+                        self.location.clone(),
+                        location.into(),
+                        instance,
+                        unevaluated,
+                    ),
+                    formatter,
+                )]);
+            }
+            let mut result = PartialApplication::valid_empty();
+            result.annotate(Value::Bool(applied).into());
+            result
+        } else {
+            PartialApplication::valid_empty()
+        }
+    }
 }
 
 struct Draft2019ItemsFilter {
@@ -193,7 +247,7 @@ impl ItemsFilter for Draft2019ItemsFilter {
     fn unevaluated(&self) -> Option<&SchemaNode> {
         self.unevaluated.as_ref()
     }
-    fn mark_evaluated_indexes(&self, instance: &Value, indexes: &mut Vec<bool>) {
+    fn mark_adjacent_indexes(&self, instance: &Value, indexes: &mut Vec<bool>) {
         if let Some(limit) = self.items {
             for idx in indexes.iter_mut().take(limit) {
                 *idx = true;
@@ -217,12 +271,6 @@ impl ItemsFilter for Draft2019ItemsFilter {
                     continue;
                 }
                 if let Some(validator) = &self.contains {
-                    if validator.is_valid(item) {
-                        *is_evaluated = true;
-                        continue;
-                    }
-                }
-                if let Some(validator) = &self.unevaluated {
                     if validator.is_valid(item) {
                         *is_evaluated = true;
                     }
@@ -365,7 +413,7 @@ impl ItemsFilter for DefaultItemsFilter {
         self.unevaluated.as_ref()
     }
 
-    fn mark_evaluated_indexes(&self, instance: &Value, indexes: &mut Vec<bool>) {
+    fn mark_adjacent_indexes(&self, instance: &Value, indexes: &mut Vec<bool>) {
         if self.items {
             for idx in indexes {
                 *idx = true;
@@ -395,12 +443,6 @@ impl ItemsFilter for DefaultItemsFilter {
                     continue;
                 }
                 if let Some(validator) = &self.contains {
-                    if validator.is_valid(item) {
-                        *is_evaluated = true;
-                        continue;
-                    }
-                }
-                if let Some(validator) = &self.unevaluated {
                     if validator.is_valid(item) {
                         *is_evaluated = true;
                     }
@@ -8,9 +8,11 @@ use serde_json::{Map, Value};
 
 use crate::{
     compiler, ecma,
+    formatter::ErrorFormatter,
     node::SchemaNode,
+    output::ErrorDescription,
     paths::{LazyLocation, Location},
-    validator::Validate,
+    validator::{PartialApplication, Validate},
     ValidationError, ValidationOptions,
 };
 
@@ -30,11 +32,27 @@ pub(crate) trait PropertiesFilter: Send + Sync + Sized + 'static {
             .unwrap_or(false)
     }
 
-    fn mark_evaluated_properties<'i>(
+    /// Mark properties evaluated by the keywords adjacent to `unevaluatedProperties`.
+    fn mark_adjacent_properties<'i>(
         &self,
         instance: &'i Value,
         properties: &mut AHashSet<&'i String>,
     );
+
+    fn mark_evaluated_properties<'i>(
+        &self,
+        instance: &'i Value,
+        properties: &mut AHashSet<&'i String>,
+    ) {
+        self.mark_adjacent_properties(instance, properties);
+        if let (Some(unevaluated), Value::Object(obj)) = (self.unevaluated(), instance) {
+            for (property, value) in obj {
+                if unevaluated.is_valid(value) {
+                    properties.insert(property);
+                }
+            }
+        }
+    }
 }
 
 pub(crate) struct UnevaluatedPropertiesValidator<F: PropertiesFilter> {
@@ -56,6 +74,29 @@ impl<F: PropertiesFilter> UnevaluatedPropertiesValidator<F> {
 }
 
 impl<F: PropertiesFilter> Validate for UnevaluatedPropertiesValidator<F> {
+    fn prune(&self, instance: &mut Value, location: &Location, pruned: &mut Vec<Location>) {
+        let unevaluated: Vec<_> = if let Value::Object(properties) = &*instance {
+            let mut evaluated = AHashSet::new();
+            self.filter
+                .mark_evaluated_properties(instance, &mut evaluated);
+            properties
+                .iter()
+                .filter(|(property, value)| {
+                    !evaluated.contains(property) && !self.filter.is_valid(value)
+                })
+                .map(|(property, _)| property.clone())
+                .collect()
+        } else {
+            return;
+        };
+        if let Value::Object(properties) = instance {
+            for property in unevaluated {
+                properties.remove(&property);
+                pruned.push(location.join(&property));
+            }
+        }
+    }
+
     fn validate<'i>(
         &self,
         instance: &'i Value,
@@ -98,6 +139,48 @@ impl<F: PropertiesFilter> Validate for UnevaluatedPropertiesValidator<F> {
         }
         true
     }
+
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        formatter: Option<&dyn ErrorFormatter>,
+    ) -> PartialApplication<'a> {
+        if let Value::Object(properties) = instance {
+            let mut evaluated = AHashSet::new();
+            self.filter
+                .mark_adjacent_properties(instance, &mut evaluated);
+
+            let mut applied = vec![];
+            let mut unevaluated = vec![];
+            for (property, value) in properties {
+                if evaluated.contains(property) {
+                    continue;
+                }
+                if self.filter.is_valid(value) {
+                    applied.push(property.clone());
+                } else {
+                    unevaluated.push(property.clone());
+                }
+            }
+            if !unevaluated.is_empty() {
+                return PartialApplication::invalid_empty(vec![ErrorDescription::new(
+                    &ValidationError::unevaluated_properties(
+                        self.location.clone(),
+                        location.into(),
+                        instance,
+                        unevaluated,
+                    ),
+                    formatter,
+                )]);
+            }
+            let mut result = PartialApplication::valid_empty();
+            result.annotate(Value::from(applied).into());
+            result
+        } else {
+            PartialApplication::valid_empty()
+        }
+    }
 }
 
 struct Draft2019PropertiesFilter {
@@ -319,7 +402,7 @@ impl PropertiesFilter for Draft2019PropertiesFilter {
         })
     }
 
-    fn mark_evaluated_properties<'i>(
+    fn mark_adjacent_properties<'i>(
         &self,
         instance: &'i Value,
         properties: &mut AHashSet<&'i String>,
@@ -348,12 +431,6 @@ impl PropertiesFilter for Draft2019PropertiesFilter {
                         continue;
                     }
                 }
-                if let Some(unevaluated) = self.unevaluated.as_ref() {
-                    if unevaluated.is_valid(value) {
-                        properties.insert(property);
-                        continue;
-                    }
-                }
                 for (pattern, _) in &self.pattern_properties {
                     if pattern.is_match(property).unwrap() {
                         properties.insert(property);
@@ -576,7 +653,7 @@ impl PropertiesFilter for DefaultPropertiesFilter {
         })
     }
 
-    fn mark_evaluated_properties<'i>(
+    fn mark_adjacent_properties<'i>(
         &self,
         instance: &'i Value,
         properties: &mut AHashSet<&'i String>,
@@ -603,12 +680,6 @@ impl PropertiesFilter for DefaultPropertiesFilter {
                         continue;
                     }
                 }
-                if let Some(unevaluated) = self.unevaluated.as_ref() {
-                    if unevaluated.is_valid(value) {
-                        properties.insert(property);
-                        continue;
-                    }
-                }
                 for (pattern, _) in &self.pattern_properties {
                     if pattern.is_match(property).unwrap() {
                         properties.insert(property);
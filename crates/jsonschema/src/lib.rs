@@ -209,6 +209,10 @@
 //! - Disable file resolving: `default-features = false, features = ["resolve-http"]`
 //! - Disable both: `default-features = false`
 //!
+//! The built-in HTTP resolving has no limits and sends no extra headers. To set timeouts, response
+//! size limits, headers for authentication, a redirect limit, accepted content types or a proxy,
+//! configure an [`HttpRetriever`] and pass it to [`ValidationOptions::with_retriever`].
+//!
 //! You can implement a custom retriever to handle external references. Here's an example that uses a static map of schemas:
 //!
 //! ```rust
@@ -268,6 +272,90 @@
 //! #    Ok(())
 //! # }
 //! ```
+//!
+//! ## Asynchronous Retrieval
+//!
+//! [`Retrieve`] blocks the current thread, which is a problem inside async runtimes. With the
+//! `resolve-async` feature, `ValidationOptions::build_async` retrieves external resources
+//! without blocking and fetches resources that don't depend on each other concurrently:
+//!
+//! ```toml
+//! jsonschema = { version = "x.y.z", features = ["resolve-async"] }
+//! ```
+//!
+//! HTTP references are fetched with the async `reqwest` client and file references are read with
+//! `tokio::fs`, so the validator has to be built inside a Tokio runtime. To use a different
+//! source, implement `AsyncRetrieve` and set it via `ValidationOptions::with_async_retriever`.
+//! A synchronous retriever set via [`ValidationOptions::with_retriever`], such as
+//! [`HttpRetriever`], is used too if there is no asynchronous one, and runs on Tokio's blocking
+//! thread pool.
+//!
+//! ## Restricting Retrieval
+//!
+//! Schemas from untrusted sources can reference any URI, including internal services, cloud
+//! metadata endpoints and local files. A [`RetrievalPolicy`] set via
+//! [`ValidationOptions::with_retrieval_policy`] restricts which resources may be retrieved by
+//! scheme, host and path, blocks private and link-local addresses, and limits the number of
+//! retrievals and how deep references to external resources may be nested:
+//!
+//! ```rust
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use jsonschema::RetrievalPolicy;
+//! use serde_json::json;
+//!
+//! let policy = RetrievalPolicy::new()
+//!     .allow_scheme("https")
+//!     .allow_host("schemas.example.com")
+//!     .block_private_networks(true)
+//!     .max_retrievals(50)
+//!     .max_depth(5);
+//!
+//! let validator = jsonschema::options()
+//!     .with_retrieval_policy(policy)
+//!     .build(&json!({"type": "string"}))?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Violations are reported as [`ReferencingError::PolicyViolation`], separately from failed
+//! retrievals. The built-in retrievers check the target of each redirect too. A custom retriever
+//! only gets the initial URI, so an [`HttpRetriever`] needs the same policy via
+//! [`HttpRetrieverBuilder::with_retrieval_policy`] to apply it to redirects.
+//!
+//! ## Sharing a Registry
+//!
+//! Building many validators that reference the same large set of schemas would index and
+//! retrieve that set for every validator. Build a [`Registry`] once instead and share it via
+//! [`ValidationOptions::with_registry`]:
+//!
+//! ```rust
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use std::sync::Arc;
+//! use jsonschema::{Registry, Resource};
+//! use serde_json::json;
+//!
+//! let registry = Arc::new(Registry::try_new(
+//!     "https://example.com/person.json",
+//!     Resource::from_contents(json!({
+//!         "$schema": "https://json-schema.org/draft/2020-12/schema",
+//!         "type": "object",
+//!         "required": ["name"]
+//!     }))?,
+//! )?);
+//!
+//! let person = jsonschema::options()
+//!     .with_registry(Arc::clone(&registry))
+//!     .build(&json!({"$ref": "https://example.com/person.json"}))?;
+//! let people = jsonschema::options()
+//!     .with_registry(Arc::clone(&registry))
+//!     .build(&json!({"items": {"$ref": "https://example.com/person.json"}}))?;
+//!
+//! assert!(!person.is_valid(&json!({})));
+//! assert!(people.is_valid(&json!([{"name": "Alice"}])));
+//! # Ok(())
+//! # }
+//! ```
+//!
 //! # Output Styles
 //!
 //! `jsonschema` supports the `basic` output style as defined in JSON Schema Draft 2019-09.
@@ -481,6 +569,12 @@
 //! - Format validation can be disabled globally or per-draft using [`ValidationOptions`].
 //!   Ensure format validation is enabled if you're using custom formats.
 //!
+//! # Parallel Validation
+//!
+//! With the `parallel` feature, [`Validator::validate_batch`] and [`Validator::par_iter_errors`]
+//! validate many instances at once on the [`rayon`](https://docs.rs/rayon) thread pool. Results
+//! are returned in the same order as the input instances.
+//!
 //! # WebAssembly support
 //!
 //! When using `jsonschema` in WASM environments, be aware that external references are
@@ -497,26 +591,55 @@
 //! For external references in WASM you may want to implement a custom retriever.
 //! See the [External References](#external-references) section for implementation details.
 
+mod cache;
+pub mod coercion;
 pub(crate) mod compiler;
 mod content_encoding;
 mod content_media_type;
 mod ecma;
 pub mod error;
+mod error_message;
+#[cfg(feature = "serde")]
+mod error_serde;
+mod error_tree;
+pub mod formatter;
+#[cfg(all(feature = "resolve-http", not(target_arch = "wasm32")))]
+mod http;
 mod keywords;
+mod mirror;
 mod node;
 mod options;
 pub mod output;
 pub mod paths;
 pub mod primitive_type;
+#[cfg(feature = "serde")]
+pub mod problem;
 pub(crate) mod properties;
+mod reader;
 mod retriever;
+mod serializer;
+pub mod spans;
+mod suggestions;
 mod validator;
 
-pub use error::{ErrorIterator, MaskedValidationError, ValidationError};
+pub use cache::CachingRetriever;
+pub use error::{
+    best_match, ErrorIterator, ErrorTree, MaskedValidationError, ReaderError, SerializeError,
+    ValidationError,
+};
+#[cfg(all(feature = "resolve-http", not(target_arch = "wasm32")))]
+pub use http::{HttpRetriever, HttpRetrieverBuilder, HttpRetrieverError};
 pub use keywords::custom::Keyword;
+pub use mirror::MirrorRetriever;
 pub use options::ValidationOptions;
 pub use output::BasicOutput;
-pub use referencing::{Draft, Error as ReferencingError, Resource, Retrieve, Uri};
+#[cfg(feature = "resolve-async")]
+pub use referencing::AsyncRetrieve;
+pub use referencing::{
+    AttemptsError, Draft, Error as ReferencingError, FallbackRetriever, FnRetriever, MapRetriever,
+    PolicyViolation, Registry, RegistryOptions, Resource, RetrievalPolicy, Retrieve, SchemeRouter,
+    Uri,
+};
 pub use validator::Validator;
 
 use serde_json::Value;
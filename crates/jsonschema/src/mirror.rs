@@ -0,0 +1,260 @@
+//! Retrieval of external resources from mirrors.
+use percent_encoding::percent_decode_str;
+use referencing::{uri, Retrieve, Uri};
+use serde_json::Value;
+use std::{error, path::PathBuf};
+
+/// Retriever that fetches resources from mirrors instead of their canonical locations.
+///
+/// URIs that start with a registered prefix are rewritten to another prefix, which is then
+/// retrieved with the wrapped retriever, or to a file in a local directory. If several prefixes
+/// match, the longest one wins. Other URIs are passed to the wrapped retriever unchanged.
+///
+/// Retrieved resources keep their canonical URI, so relative references inside them resolve
+/// against it rather than against the mirror.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(all(feature = "resolve-http", not(target_arch = "wasm32")))]
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use jsonschema::{HttpRetriever, MirrorRetriever};
+///
+/// let retriever = MirrorRetriever::new(HttpRetriever::builder().build()?)
+///     .with_mirror("https://schemas.example.com/", "https://mirror.internal/schemas/")
+///     .with_directory("https://schemas.example.com/v2/", "/opt/schemas/v2");
+///
+/// let validator = jsonschema::options()
+///     .with_retriever(retriever)
+///     .build(&serde_json::json!({"type": "string"}))?;
+/// # Ok(())
+/// # }
+/// # #[cfg(not(all(feature = "resolve-http", not(target_arch = "wasm32"))))]
+/// # fn main() {}
+/// ```
+#[derive(Debug, Clone)]
+pub struct MirrorRetriever<R> {
+    inner: R,
+    mappings: Vec<(String, Target)>,
+}
+
+#[derive(Debug, Clone)]
+enum Target {
+    Prefix(String),
+    Directory(PathBuf),
+}
+
+impl<R: Retrieve> MirrorRetriever<R> {
+    /// Create a retriever that fetches mirrored and unmapped resources with `inner`.
+    pub fn new(inner: R) -> Self {
+        MirrorRetriever {
+            inner,
+            mappings: Vec::new(),
+        }
+    }
+    /// Retrieve URIs starting with `prefix` from `mirror` instead, keeping the rest of the URI.
+    ///
+    /// Replaces an earlier mapping of the same prefix.
+    #[must_use]
+    pub fn with_mirror(self, prefix: impl Into<String>, mirror: impl Into<String>) -> Self {
+        self.with_target(prefix.into(), Target::Prefix(mirror.into()))
+    }
+    /// Read URIs starting with `prefix` from files in `directory`, with the rest of the URI as
+    /// the path relative to it.
+    ///
+    /// Replaces an earlier mapping of the same prefix.
+    #[must_use]
+    pub fn with_directory(self, prefix: impl Into<String>, directory: impl Into<PathBuf>) -> Self {
+        self.with_target(prefix.into(), Target::Directory(directory.into()))
+    }
+    fn with_target(mut self, prefix: String, target: Target) -> Self {
+        self.mappings.retain(|(existing, _)| *existing != prefix);
+        self.mappings.push((prefix, target));
+        self
+    }
+    /// The longest prefix matching `uri`, and the rest of `uri`.
+    fn lookup<'u>(&self, uri: &'u str) -> Option<(&Target, &'u str)> {
+        self.mappings
+            .iter()
+            .filter_map(|(prefix, target)| Some((prefix.len(), target, uri.strip_prefix(prefix)?)))
+            .max_by_key(|(length, _, _)| *length)
+            .map(|(_, target, rest)| (target, rest))
+    }
+}
+
+impl<R: Retrieve> Retrieve for MirrorRetriever<R> {
+    fn retrieve(&self, uri: &Uri<&str>) -> Result<Value, Box<dyn error::Error + Send + Sync>> {
+        match self.lookup(uri.as_str()) {
+            None => self.inner.retrieve(uri),
+            Some((Target::Prefix(mirror), rest)) => {
+                let mirrored = uri::from_str(&format!("{mirror}{rest}"))?;
+                self.inner.retrieve(&mirrored.borrow()).map_err(|error| {
+                    format!("Failed to retrieve mirror '{mirrored}': {error}").into()
+                })
+            }
+            Some((Target::Directory(directory), rest)) => {
+                let path = relative_path(directory, rest)?;
+                let contents = std::fs::read(&path)
+                    .map_err(|error| format!("Failed to read '{}': {error}", path.display()))?;
+                Ok(serde_json::from_slice(&contents)?)
+            }
+        }
+    }
+}
+
+/// The file in `directory` for the URI path `rest`.
+fn relative_path(
+    directory: &std::path::Path,
+    rest: &str,
+) -> Result<PathBuf, Box<dyn error::Error + Send + Sync>> {
+    if rest.contains(['?', '#']) {
+        return Err(format!("Can't map '{rest}' with a query or fragment to a file").into());
+    }
+    let mut path = directory.to_path_buf();
+    for segment in rest.split('/') {
+        let segment = percent_decode_str(segment).decode_utf8()?;
+        // Keep the path inside the directory
+        if matches!(&*segment, "" | "." | "..") || segment.contains(['/', '\\']) {
+            return Err(format!("Can't map '{rest}' to a file in a directory").into());
+        }
+        path.push(&*segment);
+    }
+    Ok(path)
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::MirrorRetriever;
+    use referencing::{uri, Retrieve, Uri};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    /// Returns the URI it was asked for.
+    struct EchoRetriever;
+
+    impl Retrieve for EchoRetriever {
+        fn retrieve(
+            &self,
+            uri: &Uri<&str>,
+        ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+            if uri.as_str().contains("missing") {
+                return Err("Not found".into());
+            }
+            Ok(json!({"const": uri.as_str()}))
+        }
+    }
+
+    fn retrieve(retriever: &impl Retrieve, uri: &str) -> Result<Value, String> {
+        let uri = uri::from_str(uri).expect("Invalid URI");
+        retriever
+            .retrieve(&uri.borrow())
+            .map_err(|error| error.to_string())
+    }
+
+    #[test_case(
+        "https://schemas.example.com/v1/a.json",
+        "https://mirror.internal/a/v1/a.json"
+    )]
+    #[test_case("https://schemas.example.com/v2/a.json", "https://mirror.internal/b/a.json"; "longest prefix")]
+    #[test_case("https://schemas.example.com/v2", "https://mirror.internal/a/v2"; "shorter than prefix")]
+    #[test_case("https://other.example.com/a.json", "https://other.example.com/a.json"; "unmapped")]
+    fn mirrors(uri: &str, expected: &str) {
+        // Registration order doesn't matter
+        for retriever in [
+            MirrorRetriever::new(EchoRetriever)
+                .with_mirror("https://schemas.example.com/", "https://mirror.internal/a/")
+                .with_mirror(
+                    "https://schemas.example.com/v2/",
+                    "https://mirror.internal/b/",
+                ),
+            MirrorRetriever::new(EchoRetriever)
+                .with_mirror(
+                    "https://schemas.example.com/v2/",
+                    "https://mirror.internal/b/",
+                )
+                .with_mirror("https://schemas.example.com/", "https://mirror.internal/a/"),
+        ] {
+            assert_eq!(retrieve(&retriever, uri), Ok(json!({"const": expected})));
+        }
+    }
+
+    #[test]
+    fn replaced_mirror() {
+        let retriever = MirrorRetriever::new(EchoRetriever)
+            .with_mirror("https://schemas.example.com/", "https://old.internal/")
+            .with_mirror("https://schemas.example.com/", "https://new.internal/");
+        assert_eq!(
+            retrieve(&retriever, "https://schemas.example.com/a.json"),
+            Ok(json!({"const": "https://new.internal/a.json"}))
+        );
+    }
+
+    #[test]
+    fn mirror_error() {
+        let retriever = MirrorRetriever::new(EchoRetriever)
+            .with_mirror("https://schemas.example.com/", "https://missing.internal/");
+        assert_eq!(
+            retrieve(&retriever, "https://schemas.example.com/a.json"),
+            Err(
+                "Failed to retrieve mirror 'https://missing.internal/a.json': Not found"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test_case("https://schemas.example.com/a%2F..%2F..%2Fsecret.json")]
+    #[test_case("https://schemas.example.com//etc/passwd")]
+    #[test_case("https://schemas.example.com/a.json?version=2")]
+    fn directory_escape(uri: &str) {
+        let directory = tempfile::tempdir().expect("Failed to create a directory");
+        let retriever = MirrorRetriever::new(EchoRetriever)
+            .with_directory("https://schemas.example.com/", directory.path());
+        assert!(retrieve(&retriever, uri)
+            .expect_err("Should fail")
+            .starts_with("Can't map"));
+    }
+
+    // Dot segments are normally removed when URIs are parsed
+    #[test_case("../secret.json")]
+    #[test_case("a/%2E%2E/%2E%2E/secret.json")]
+    #[test_case("./a.json")]
+    fn dot_segments(rest: &str) {
+        assert!(super::relative_path(std::path::Path::new("/opt/schemas"), rest).is_err());
+    }
+
+    #[test]
+    fn directory() {
+        let directory = tempfile::tempdir().expect("Failed to create a directory");
+        let v2 = directory.path().join("v2");
+        std::fs::create_dir_all(v2.join("common")).expect("Failed to create a directory");
+        std::fs::write(
+            v2.join("order.json"),
+            r#"{
+                "$id": "https://schemas.example.com/v2/order.json",
+                "properties": {"price": {"$ref": "common/price%20v2.json"}}
+            }"#,
+        )
+        .expect("Failed to write a file");
+        std::fs::write(
+            v2.join("common").join("price v2.json"),
+            r#"{"type": "number", "minimum": 0}"#,
+        )
+        .expect("Failed to write a file");
+        let retriever = MirrorRetriever::new(EchoRetriever)
+            .with_directory("https://schemas.example.com/v2/", &v2);
+        assert!(
+            retrieve(&retriever, "https://schemas.example.com/v2/missing.json")
+                .expect_err("Should fail")
+                .starts_with("Failed to read")
+        );
+
+        // The relative reference resolves against the canonical URI of `order.json`
+        let schema = json!({"$ref": "https://schemas.example.com/v2/order.json"});
+        let validator = crate::options()
+            .with_retriever(retriever)
+            .build(&schema)
+            .expect("Invalid schema");
+        assert!(validator.is_valid(&json!({"price": 10})));
+        assert!(!validator.is_valid(&json!({"price": -1})));
+    }
+}
@@ -1,8 +1,11 @@
 use crate::{
+    coercion::Coercer,
     compiler::Context,
     error::ErrorIterator,
-    keywords::{BoxedValidator, Keyword},
-    output::{Annotations, BasicOutput, ErrorDescription, OutputUnit},
+    error_message::{self, ErrorMessage},
+    formatter::ErrorFormatter,
+    keywords::{BoxedValidator, BuiltinKeyword, Keyword},
+    output::{Annotations, HierarchicalOutput},
     paths::{LazyLocation, Location, LocationSegment},
     validator::{PartialApplication, Validate},
     ValidationError,
@@ -10,7 +13,7 @@ use crate::{
 use ahash::AHashMap;
 use referencing::{uri, Uri};
 use serde_json::Value;
-use std::{cell::OnceCell, collections::VecDeque, fmt};
+use std::{cell::OnceCell, fmt};
 
 /// A node in the schema tree, returned by [`compiler::compile`]
 #[derive(Debug)]
@@ -56,6 +59,8 @@ struct KeywordValidators {
     // We should probably use AHashMap here but it breaks a bunch of test which assume
     // validators are in a particular order
     validators: Vec<(Keyword, BoxedValidator)>,
+    /// Custom messages for errors of this node's keywords
+    error_message: Option<ErrorMessage>,
 }
 
 impl SchemaNode {
@@ -71,6 +76,7 @@ impl SchemaNode {
         ctx: &Context<'_>,
         validators: Vec<(Keyword, BoxedValidator)>,
         unmatched_keywords: Option<AHashMap<String, Value>>,
+        error_message: Option<ErrorMessage>,
     ) -> SchemaNode {
         SchemaNode {
             location: ctx.location().clone(),
@@ -78,6 +84,7 @@ impl SchemaNode {
             validators: NodeValidators::Keyword(KeywordValidators {
                 unmatched_keywords,
                 validators,
+                error_message,
             }),
         }
     }
@@ -90,6 +97,21 @@ impl SchemaNode {
         }
     }
 
+    /// Validators of this node along with their keywords, if the node is an object schema.
+    pub(crate) fn keyword_validators(
+        &self,
+    ) -> Box<dyn Iterator<Item = (Option<&Keyword>, &BoxedValidator)> + '_> {
+        match &self.validators {
+            NodeValidators::Keyword(kvals) => Box::new(
+                kvals
+                    .validators
+                    .iter()
+                    .map(|(keyword, validator)| (Some(keyword), validator)),
+            ),
+            _ => Box::new(self.validators().map(|validator| (None, validator))),
+        }
+    }
+
     pub(crate) fn validators(&self) -> impl ExactSizeIterator<Item = &BoxedValidator> {
         match &self.validators {
             NodeValidators::Boolean { validator } => {
@@ -110,56 +132,19 @@ impl SchemaNode {
 
     /// This is similar to `Validate::apply` except that `SchemaNode` knows where it is in the
     /// validator tree and so rather than returning a `PartialApplication` it is able to return a
-    /// complete `BasicOutput`. This is the mechanism which compositional validators use to combine
-    /// results from sub-schemas
-    pub(crate) fn apply_rooted(&self, instance: &Value, location: &LazyLocation) -> BasicOutput {
-        match self.apply(instance, location) {
-            PartialApplication::Valid {
-                annotations,
-                mut child_results,
-            } => {
-                if let Some(annotations) = annotations {
-                    child_results.insert(0, self.annotation_at(location, annotations));
-                };
-                BasicOutput::Valid(child_results)
-            }
-            PartialApplication::Invalid {
-                errors,
-                mut child_results,
-            } => {
-                for error in errors {
-                    child_results.insert(0, self.error_at(location, error));
-                }
-                BasicOutput::Invalid(child_results)
-            }
-        }
-    }
-
-    /// Create an error output which is marked as occurring at this schema node
-    pub(crate) fn error_at(
-        &self,
-        location: &LazyLocation,
-        error: ErrorDescription,
-    ) -> OutputUnit<ErrorDescription> {
-        OutputUnit::<ErrorDescription>::error(
-            self.location.clone(),
-            location.into(),
-            self.absolute_path.clone(),
-            error,
-        )
-    }
-
-    /// Create an annotation output which is marked as occurring at this schema node
-    pub(crate) fn annotation_at<'a>(
-        &self,
+    /// complete `HierarchicalOutput` node. This is the mechanism which compositional validators
+    /// use to combine results from sub-schemas
+    pub(crate) fn apply_rooted<'a>(
+        &'a self,
+        instance: &Value,
         location: &LazyLocation,
-        annotations: Annotations<'a>,
-    ) -> OutputUnit<Annotations<'a>> {
-        OutputUnit::<Annotations<'_>>::annotations(
+        formatter: Option<&dyn ErrorFormatter>,
+    ) -> HierarchicalOutput<'a> {
+        HierarchicalOutput::from_application(
             self.location.clone(),
             location.into(),
             self.absolute_path.clone(),
-            annotations,
+            self.apply(instance, location, formatter),
         )
     }
 
@@ -171,17 +156,21 @@ impl SchemaNode {
         &self,
         instance: &Value,
         location: &LazyLocation,
+        formatter: Option<&dyn ErrorFormatter>,
         path_and_validators: I,
         annotations: Option<Annotations<'a>>,
+        error_message: Option<&ErrorMessage>,
     ) -> PartialApplication<'a>
     where
         I: Iterator<Item = (P, &'a Box<dyn Validate + Send + Sync + 'a>)> + 'a,
         P: Into<LocationSegment<'a>> + fmt::Display,
     {
-        let mut success_results: VecDeque<OutputUnit<Annotations>> = VecDeque::new();
-        let mut error_results = VecDeque::new();
+        let mut children = Vec::new();
+        let mut valid = true;
         let mut buffer = String::new();
         let instance_location: OnceCell<Location> = OnceCell::new();
+        // Messages from `errorMessage` that were already reported
+        let mut replaced = Vec::new();
 
         macro_rules! instance_location {
             () => {
@@ -190,6 +179,7 @@ impl SchemaNode {
         }
 
         for (path, validator) in path_and_validators {
+            let path: LocationSegment<'_> = path.into();
             macro_rules! make_absolute_location {
                 ($location:expr) => {
                     self.absolute_path.as_ref().map(|absolute_path| {
@@ -201,52 +191,48 @@ impl SchemaNode {
                     })
                 };
             }
-            match validator.apply(instance, location) {
-                PartialApplication::Valid {
-                    annotations,
-                    child_results,
-                } => {
-                    if let Some(annotations) = annotations {
-                        let location = self.location.join(path);
-                        let absolute_location = make_absolute_location!(location);
-                        success_results.push_front(OutputUnit::<Annotations<'a>>::annotations(
+            let application = validator.apply(instance, location, formatter);
+            let location = self.location.join(path);
+            let absolute_location = make_absolute_location!(location);
+            if let PartialApplication::Invalid { .. } = application {
+                valid = false;
+                let template = match (error_message, path) {
+                    (Some(error_message), LocationSegment::Property(keyword)) => {
+                        error_message.for_keyword(keyword)
+                    }
+                    _ => None,
+                };
+                if let Some(template) = template {
+                    // All errors are replaced by a single one for this instance
+                    if !replaced.contains(&template) {
+                        replaced.push(template);
+                        let message = error_message::render_to_string(template, instance);
+                        children.push(HierarchicalOutput::errored(
                             location,
                             instance_location!(),
                             absolute_location,
-                            annotations,
+                            message.as_str().into(),
                         ));
                     }
-                    success_results.extend(child_results);
-                }
-                PartialApplication::Invalid {
-                    errors: these_errors,
-                    child_results,
-                } => {
-                    let location = self.location.join(path);
-                    error_results.reserve(child_results.len() + these_errors.len());
-                    error_results.extend(child_results);
-                    error_results.extend(these_errors.into_iter().map(|error| {
-                        OutputUnit::<ErrorDescription>::error(
-                            location.clone(),
-                            instance_location!(),
-                            // Resolving & encoding is faster than cloning because one of the
-                            // values won't be used when cloning
-                            make_absolute_location!(location),
-                            error,
-                        )
-                    }));
+                    continue;
                 }
             }
+            children.push(HierarchicalOutput::from_application(
+                location,
+                instance_location!(),
+                absolute_location,
+                application,
+            ));
         }
-        if error_results.is_empty() {
+        if valid {
             PartialApplication::Valid {
                 annotations,
-                child_results: success_results,
+                child_results: children,
             }
         } else {
             PartialApplication::Invalid {
                 errors: Vec::new(),
-                child_results: error_results,
+                child_results: children,
             }
         }
     }
@@ -254,11 +240,46 @@ impl SchemaNode {
     pub(crate) fn location(&self) -> &Location {
         &self.location
     }
+
+    /// The value of the `default` keyword of this schema, if any
+    pub(crate) fn default_value(&self) -> Option<&Value> {
+        if let NodeValidators::Keyword(kvs) = &self.validators {
+            kvs.unmatched_keywords.as_ref()?.get("default")
+        } else {
+            None
+        }
+    }
 }
 
 impl Validate for SchemaNode {
     fn iter_errors<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
         match &self.validators {
+            NodeValidators::Keyword(KeywordValidators {
+                validators,
+                error_message: Some(error_message),
+                ..
+            }) => {
+                let mut errors = Vec::new();
+                // Errors with the same message are reported once
+                let mut replaced = Vec::new();
+                for (keyword, validator) in validators {
+                    let mut keyword_errors = validator.iter_errors(instance, location);
+                    match error_message.for_keyword(keyword.as_str()) {
+                        Some(template) => {
+                            if let Some(error) = keyword_errors.next() {
+                                if !replaced.contains(&template) {
+                                    replaced.push(template);
+                                    errors.push(
+                                        error_message.wrap(keyword, error, instance, location),
+                                    );
+                                }
+                            }
+                        }
+                        None => errors.extend(keyword_errors),
+                    }
+                }
+                Box::new(errors.into_iter())
+            }
             NodeValidators::Keyword(kvs) if kvs.validators.len() == 1 => {
                 kvs.validators[0].1.iter_errors(instance, location)
             }
@@ -292,8 +313,15 @@ impl Validate for SchemaNode {
     ) -> Result<(), ValidationError<'i>> {
         match &self.validators {
             NodeValidators::Keyword(kvs) => {
-                for (_, validator) in &kvs.validators {
-                    validator.validate(instance, location)?;
+                for (keyword, validator) in &kvs.validators {
+                    if let Err(error) = validator.validate(instance, location) {
+                        return Err(match &kvs.error_message {
+                            Some(error_message) => {
+                                error_message.wrap(keyword, error, instance, location)
+                            }
+                            None => error,
+                        });
+                    }
                 }
             }
             NodeValidators::Array { validators } => {
@@ -336,33 +364,77 @@ impl Validate for SchemaNode {
         }
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
-        match self.validators {
-            NodeValidators::Array { ref validators } => {
-                self.apply_subschemas(instance, location, validators.iter().enumerate(), None)
+    fn apply_defaults(&self, instance: &mut Value, original: &Value) {
+        for validator in self.validators() {
+            validator.apply_defaults(instance, original);
+        }
+    }
+
+    fn coerce(&self, instance: &mut Value, location: &Location, coercer: &mut Coercer) {
+        if let NodeValidators::Keyword(kvs) = &self.validators {
+            // `type` goes first, so the other keywords see the coerced value
+            let is_type =
+                |keyword: &Keyword| matches!(keyword, Keyword::Buildin(BuiltinKeyword::Type));
+            for (keyword, validator) in &kvs.validators {
+                if is_type(keyword) {
+                    validator.coerce(instance, location, coercer);
+                }
             }
+            for (keyword, validator) in &kvs.validators {
+                if !is_type(keyword) {
+                    validator.coerce(instance, location, coercer);
+                }
+            }
+        } else {
+            for validator in self.validators() {
+                validator.coerce(instance, location, coercer);
+            }
+        }
+    }
+
+    fn prune(&self, instance: &mut Value, location: &Location, pruned: &mut Vec<Location>) {
+        for validator in self.validators() {
+            validator.prune(instance, location, pruned);
+        }
+    }
+
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        formatter: Option<&dyn ErrorFormatter>,
+    ) -> PartialApplication<'a> {
+        match self.validators {
+            NodeValidators::Array { ref validators } => self.apply_subschemas(
+                instance,
+                location,
+                formatter,
+                validators.iter().enumerate(),
+                None,
+                None,
+            ),
             NodeValidators::Boolean { ref validator } => {
                 if let Some(validator) = validator {
-                    validator.apply(instance, location)
+                    validator.apply(instance, location, formatter)
                 } else {
-                    PartialApplication::Valid {
-                        annotations: None,
-                        child_results: VecDeque::new(),
-                    }
+                    PartialApplication::valid_empty()
                 }
             }
             NodeValidators::Keyword(ref kvals) => {
                 let KeywordValidators {
                     ref unmatched_keywords,
                     ref validators,
+                    ref error_message,
                 } = *kvals;
                 let annotations: Option<Annotations<'a>> =
                     unmatched_keywords.as_ref().map(Annotations::from);
                 self.apply_subschemas(
                     instance,
                     location,
+                    formatter,
                     validators.iter().map(|(p, v)| (p, v)),
                     annotations,
+                    error_message.as_ref(),
                 )
             }
         }
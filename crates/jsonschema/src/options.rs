@@ -1,17 +1,23 @@
+#[cfg(feature = "resolve-async")]
+use crate::retriever::BlockingRetriever;
 use crate::{
+    coercion::TypeCoercion,
     compiler,
     content_encoding::{
         ContentEncodingCheckType, ContentEncodingConverterType,
         DEFAULT_CONTENT_ENCODING_CHECKS_AND_CONVERTERS,
     },
     content_media_type::{ContentMediaTypeCheckType, DEFAULT_CONTENT_MEDIA_TYPE_CHECKS},
+    formatter::ErrorFormatter,
     keywords::{custom::KeywordFactory, format::Format},
     paths::Location,
     retriever::DefaultRetriever,
     Keyword, ValidationError, Validator,
 };
 use ahash::AHashMap;
-use referencing::{uri, Draft, Resource, Retrieve};
+#[cfg(feature = "resolve-async")]
+use referencing::AsyncRetrieve;
+use referencing::{uri, Draft, Registry, Resource, RetrievalPolicy, Retrieve, Uri};
 use serde_json::Value;
 use std::{fmt, sync::Arc};
 
@@ -22,15 +28,25 @@ pub struct ValidationOptions {
     content_media_type_checks: AHashMap<&'static str, Option<ContentMediaTypeCheckType>>,
     content_encoding_checks_and_converters:
         AHashMap<&'static str, Option<(ContentEncodingCheckType, ContentEncodingConverterType)>>,
-    /// Retriever for external resources
-    pub(crate) retriever: Arc<dyn Retrieve>,
+    /// Retriever for external resources, if not the default one
+    retriever: Option<Arc<dyn Retrieve>>,
+    /// Retriever for external resources used by [`ValidationOptions::build_async`], if set
+    #[cfg(feature = "resolve-async")]
+    async_retriever: Option<Arc<dyn AsyncRetrieve>>,
+    /// Restrictions on which external resources may be retrieved
+    pub(crate) retrieval_policy: RetrievalPolicy,
     /// Additional resources that should be addressable during validation.
     pub(crate) resources: AHashMap<String, Resource>,
+    /// Already processed resources shared between validators.
+    pub(crate) registry: Option<Arc<Registry>>,
     formats: AHashMap<String, Arc<dyn Format>>,
     validate_formats: Option<bool>,
     pub(crate) validate_schema: bool,
     ignore_unknown_formats: bool,
     keywords: AHashMap<String, Arc<dyn KeywordFactory>>,
+    type_coercion: Option<TypeCoercion>,
+    error_messages: bool,
+    error_formatter: Option<Arc<dyn ErrorFormatter>>,
 }
 
 impl Default for ValidationOptions {
@@ -39,13 +55,20 @@ impl Default for ValidationOptions {
             draft: None,
             content_media_type_checks: AHashMap::default(),
             content_encoding_checks_and_converters: AHashMap::default(),
-            retriever: Arc::new(DefaultRetriever),
+            retriever: None,
+            #[cfg(feature = "resolve-async")]
+            async_retriever: None,
+            retrieval_policy: RetrievalPolicy::new(),
             resources: AHashMap::default(),
+            registry: None,
             formats: AHashMap::default(),
             validate_formats: None,
             validate_schema: true,
             ignore_unknown_formats: true,
             keywords: AHashMap::default(),
+            type_coercion: None,
+            error_messages: false,
+            error_formatter: None,
         }
     }
 }
@@ -55,6 +78,22 @@ impl ValidationOptions {
     pub(crate) fn draft(&self) -> Draft {
         self.draft.unwrap_or_default()
     }
+    pub(crate) fn retriever(&self) -> Arc<dyn Retrieve> {
+        match &self.retriever {
+            Some(retriever) => Arc::clone(retriever),
+            None => Arc::new(DefaultRetriever::new(self.retrieval_policy.clone())),
+        }
+    }
+    /// The asynchronous retriever, falling back to running the synchronous one set via
+    /// [`ValidationOptions::with_retriever`] on the blocking thread pool.
+    #[cfg(feature = "resolve-async")]
+    pub(crate) fn async_retriever(&self) -> Arc<dyn AsyncRetrieve> {
+        match (&self.async_retriever, &self.retriever) {
+            (Some(retriever), _) => Arc::clone(retriever),
+            (None, Some(retriever)) => Arc::new(BlockingRetriever::new(Arc::clone(retriever))),
+            (None, None) => Arc::new(DefaultRetriever::new(self.retrieval_policy.clone())),
+        }
+    }
     pub(crate) fn draft_for(&self, contents: &Value) -> Result<Draft, ValidationError<'static>> {
         // Preference:
         //  - Explicitly set
@@ -68,13 +107,62 @@ impl ValidationOptions {
                 Ok(draft) => Ok(draft),
                 Err(referencing::Error::UnknownSpecification { specification }) => {
                     // Try to retrieve the specification and detect its draft
-                    if let Ok(Ok(retrieved)) = uri::from_str(&specification)
-                        .map(|uri| self.retriever.retrieve(&uri.borrow()))
-                    {
-                        Ok(default.detect(&retrieved)?)
-                    } else {
-                        Err(referencing::Error::UnknownSpecification { specification }.into())
+                    if let Ok(uri) = uri::from_str(&specification) {
+                        if let Some(contents) = self.registered(&uri) {
+                            return Ok(default.detect(contents)?);
+                        }
+                        self.check_retrieval_policy(&uri)?;
+                        if let Ok(retrieved) = self.retriever().retrieve(&uri.borrow()) {
+                            return Ok(default.detect(&retrieved)?);
+                        }
+                    }
+                    Err(referencing::Error::UnknownSpecification { specification }.into())
+                }
+                Err(error) => Err(error.into()),
+            }
+        }
+    }
+    /// Contents of `uri` in the shared registry, if there is one.
+    fn registered(&self, uri: &Uri<String>) -> Option<&Value> {
+        let registry = self.registry.as_ref()?;
+        let resolved = registry.resolver(uri.clone()).lookup("").ok()?;
+        Some(resolved.contents())
+    }
+    fn check_retrieval_policy(&self, uri: &Uri<String>) -> Result<(), ValidationError<'static>> {
+        self.retrieval_policy
+            .check(&uri.borrow())
+            .map_err(|violation| {
+                referencing::Error::PolicyViolation {
+                    uri: uri.to_string(),
+                    violation,
+                }
+                .into()
+            })
+    }
+    #[cfg(feature = "resolve-async")]
+    pub(crate) async fn draft_for_async(
+        &self,
+        contents: &Value,
+    ) -> Result<Draft, ValidationError<'static>> {
+        if let Some(draft) = self.draft {
+            Ok(draft)
+        } else {
+            let default = Draft::default();
+            match default.detect(contents) {
+                Ok(draft) => Ok(draft),
+                Err(referencing::Error::UnknownSpecification { specification }) => {
+                    // Try to retrieve the specification and detect its draft
+                    if let Ok(uri) = uri::from_str(&specification) {
+                        if let Some(contents) = self.registered(&uri) {
+                            return Ok(default.detect(contents)?);
+                        }
+                        self.check_retrieval_policy(&uri)?;
+                        if let Ok(retrieved) = self.async_retriever().retrieve(&uri.borrow()).await
+                        {
+                            return Ok(default.detect(&retrieved)?);
+                        }
                     }
+                    Err(referencing::Error::UnknownSpecification { specification }.into())
                 }
                 Err(error) => Err(error.into()),
             }
@@ -98,6 +186,36 @@ impl ValidationOptions {
     pub fn build(&self, schema: &Value) -> Result<Validator, ValidationError<'static>> {
         compiler::build_validator(self.clone(), schema)
     }
+    /// Build a JSON Schema validator using the current options, retrieving external resources
+    /// without blocking.
+    ///
+    /// External resources are fetched with the retriever set via
+    /// [`ValidationOptions::with_async_retriever`]. Resources that don't depend on each other are
+    /// fetched concurrently. If only a synchronous retriever is set via
+    /// [`ValidationOptions::with_retriever`], it is used instead and runs on Tokio's blocking
+    /// thread pool.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// use serde_json::json;
+    ///
+    /// let schema = json!({"type": "string"});
+    /// let validator = jsonschema::options().build_async(&schema).await?;
+    ///
+    /// assert!(validator.is_valid(&json!("Hello")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the schema is invalid or an external resource can't be retrieved.
+    #[cfg(feature = "resolve-async")]
+    pub async fn build_async(&self, schema: &Value) -> Result<Validator, ValidationError<'static>> {
+        compiler::build_validator_async(self.clone(), schema).await
+    }
     /// Sets the JSON Schema draft version.
     ///
     /// ```rust
@@ -144,8 +262,80 @@ impl ValidationOptions {
         self
     }
     /// Set a retriever to fetch external resources.
+    ///
+    /// [`ValidationOptions::build_async`] uses it as well, on Tokio's blocking thread pool,
+    /// unless an asynchronous retriever is set via `with_async_retriever`.
     pub fn with_retriever(&mut self, retriever: impl Retrieve + 'static) -> &mut Self {
-        self.retriever = Arc::new(retriever);
+        self.retriever = Some(Arc::new(retriever));
+        self
+    }
+    /// Set a retriever to fetch external resources in [`ValidationOptions::build_async`].
+    ///
+    /// It takes precedence over the retriever set via [`ValidationOptions::with_retriever`].
+    #[cfg(feature = "resolve-async")]
+    pub fn with_async_retriever(&mut self, retriever: impl AsyncRetrieve + 'static) -> &mut Self {
+        self.async_retriever = Some(Arc::new(retriever));
+        self
+    }
+    /// Restrict which external resources may be retrieved, e.g. to only allow HTTPS URIs of
+    /// trusted hosts when building validators for untrusted schemas.
+    ///
+    /// The policy applies to both [`ValidationOptions::build`] and `build_async`, whichever
+    /// retriever is used. The built-in retrievers also check the target of each redirect, while
+    /// custom retrievers that follow redirects have to check them themselves, e.g. via
+    /// `HttpRetrieverBuilder::with_retrieval_policy`. Violations are reported as
+    /// [`referencing::Error::PolicyViolation`].
+    ///
+    /// ```rust
+    /// use jsonschema::RetrievalPolicy;
+    /// use serde_json::json;
+    ///
+    /// let policy = RetrievalPolicy::new()
+    ///     .allow_scheme("https")
+    ///     .block_private_networks(true);
+    /// let schema = json!({"$ref": "file:///etc/passwd"});
+    /// let error = jsonschema::options()
+    ///     .with_retrieval_policy(policy)
+    ///     .build(&schema)
+    ///     .expect_err("Should be denied");
+    /// assert_eq!(
+    ///     error.to_string(),
+    ///     "Retrieving 'file:///etc/passwd' is not allowed by the retrieval policy: scheme 'file' is not allowed"
+    /// );
+    /// ```
+    pub fn with_retrieval_policy(&mut self, policy: RetrievalPolicy) -> &mut Self {
+        self.retrieval_policy = policy;
+        self
+    }
+    /// Resolve references against an already populated [`Registry`] that is shared between
+    /// validators.
+    ///
+    /// Resources in the shared registry are not copied or retrieved again, so indexing a large
+    /// set of schemas only happens once. The shared registry is not modified; the schema and
+    /// resources added with [`ValidationOptions::with_resource`] are layered on top of it.
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use jsonschema::{Registry, Resource};
+    /// use serde_json::json;
+    ///
+    /// let registry = Arc::new(Registry::try_new(
+    ///     "https://example.com/common.json",
+    ///     Resource::from_contents(json!({
+    ///         "$schema": "https://json-schema.org/draft/2020-12/schema",
+    ///         "$defs": {"name": {"type": "string"}}
+    ///     }))?,
+    /// )?);
+    /// let schema = json!({"$ref": "https://example.com/common.json#/$defs/name"});
+    /// let validator = jsonschema::options()
+    ///     .with_registry(Arc::clone(&registry))
+    ///     .build(&schema)?;
+    /// assert!(validator.is_valid(&json!("Alice")));
+    /// assert!(!validator.is_valid(&json!(42)));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_registry(&mut self, registry: Arc<Registry>) -> &mut Self {
+        self.registry = Some(registry);
         self
     }
     /// Remove support for a specific content media type validation.
@@ -450,12 +640,111 @@ impl ValidationOptions {
     pub(crate) fn get_keyword_factory(&self, name: &str) -> Option<&Arc<dyn KeywordFactory>> {
         self.keywords.get(name)
     }
+    /// Enable type coercion via [`Validator::coerce`] and [`Validator::validate_coerced`].
+    ///
+    /// Without this option, both leave instances intact. Validation itself doesn't coerce
+    /// instances, so call one of them instead of validating the original instance.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use jsonschema::coercion::TypeCoercion;
+    /// # use serde_json::json;
+    /// let validator = jsonschema::options()
+    ///     .with_type_coercion(TypeCoercion::Arrays)
+    ///     .build(&json!({"type": "array", "items": {"type": "number"}}))
+    ///     .expect("A valid schema");
+    ///
+    /// let mut instance = json!("1.5");
+    /// validator.coerce(&mut instance);
+    /// assert_eq!(instance, json!([1.5]));
+    /// ```
+    pub fn with_type_coercion(&mut self, coercion: TypeCoercion) -> &mut Self {
+        self.type_coercion = Some(coercion);
+        self
+    }
+    pub(crate) const fn type_coercion(&self) -> Option<TypeCoercion> {
+        self.type_coercion
+    }
+    /// Set whether to use custom messages from the `errorMessage` keyword.
+    ///
+    /// The keyword takes either a single message for all errors of the schema it is defined in,
+    /// or an object with a message per keyword of that schema. `${0}` in a message is replaced
+    /// with the instance that schema applies to, and `${0/path}` with a value inside it. Errors
+    /// that get the same message are reported once, for that instance. By default,
+    /// `errorMessage` is treated as an annotation.
+    ///
+    /// The original error kind stays available inside
+    /// [`ValidationErrorKind::ErrorMessage`](crate::error::ValidationErrorKind::ErrorMessage).
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use serde_json::json;
+    /// let schema = json!({
+    ///     "properties": {
+    ///         "age": {
+    ///             "type": "integer",
+    ///             "errorMessage": {"type": "Age must be a whole number, got ${0}"}
+    ///         }
+    ///     }
+    /// });
+    /// let validator = jsonschema::options()
+    ///     .should_use_error_messages(true)
+    ///     .build(&schema)
+    ///     .expect("A valid schema");
+    ///
+    /// let instance = json!({"age": 1.5});
+    /// let error = validator.validate(&instance).expect_err("Should fail");
+    /// assert_eq!(error.to_string(), "Age must be a whole number, got 1.5");
+    /// ```
+    pub fn should_use_error_messages(&mut self, yes: bool) -> &mut Self {
+        self.error_messages = yes;
+        self
+    }
+    pub(crate) const fn error_messages(&self) -> bool {
+        self.error_messages
+    }
+    /// Set the formatter used to render error messages by [`Validator::format_error`] and in
+    /// the output formats of [`Validator::apply`].
+    ///
+    /// By default, errors are rendered with
+    /// [`DefaultFormatter`](crate::formatter::DefaultFormatter), i.e. the same English text as
+    /// their [`Display`](std::fmt::Display) output. See the [`formatter`](crate::formatter)
+    /// module for loading message catalogs.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use jsonschema::ValidationError;
+    /// # use serde_json::json;
+    /// let validator = jsonschema::options()
+    ///     .with_error_formatter(|error: &ValidationError<'_>| {
+    ///         format!("Invalid value at '{}'", error.instance_path)
+    ///     })
+    ///     .build(&json!({"properties": {"name": {"type": "string"}}}))
+    ///     .expect("A valid schema");
+    ///
+    /// let instance = json!({"name": 42});
+    /// let error = validator.validate(&instance).expect_err("Should fail");
+    /// assert_eq!(validator.format_error(&error), "Invalid value at '/name'");
+    /// ```
+    pub fn with_error_formatter(&mut self, formatter: impl ErrorFormatter + 'static) -> &mut Self {
+        self.error_formatter = Some(Arc::new(formatter));
+        self
+    }
+    pub(crate) fn error_formatter(&self) -> Option<&dyn ErrorFormatter> {
+        self.error_formatter.as_deref()
+    }
 }
 
 impl fmt::Debug for ValidationOptions {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt.debug_struct("CompilationConfig")
             .field("draft", &self.draft)
+            .field("type_coercion", &self.type_coercion)
+            .field("error_messages", &self.error_messages)
+            .field("error_formatter", &self.error_formatter.is_some())
             .field("content_media_type", &self.content_media_type_checks.keys())
             .field(
                 "content_encoding",
@@ -484,4 +773,205 @@ mod tests {
         assert!(!validator.is_valid(&json!("foo")));
         assert!(validator.is_valid(&json!("foo42!")));
     }
+
+    #[test]
+    fn retrieval_policy_meta_schema() {
+        let schema = json!({"$schema": "http://127.0.0.1/meta.json", "type": "string"});
+        let error = crate::options()
+            .with_retrieval_policy(crate::RetrievalPolicy::new().block_private_networks(true))
+            .build(&schema)
+            .expect_err("Should fail");
+        assert!(matches!(
+            error.kind,
+            crate::error::ValidationErrorKind::Referencing(
+                referencing::Error::PolicyViolation { .. }
+            )
+        ));
+        assert_eq!(
+            error.to_string(),
+            "Retrieving 'http://127.0.0.1/meta.json' is not allowed by the retrieval policy: host '127.0.0.1' has the non-public address 127.0.0.1"
+        );
+    }
+
+    #[test]
+    fn shared_registry() {
+        use std::sync::Arc;
+
+        use crate::{FnRetriever, Registry, Resource};
+
+        let registry = Arc::new(
+            Registry::try_from_resources(
+                [
+                    (
+                        "https://example.com/meta.json",
+                        Resource::from_contents(json!({
+                            "$schema": "https://json-schema.org/draft/2020-12/schema"
+                        }))
+                        .expect("Invalid resource"),
+                    ),
+                    (
+                        "https://example.com/library.json",
+                        Resource::from_contents(json!({
+                            "$schema": "https://json-schema.org/draft/2020-12/schema",
+                            "$defs": {
+                                "name": {"$anchor": "name", "type": "string"},
+                                "age": {"type": "integer", "minimum": 0}
+                            }
+                        }))
+                        .expect("Invalid resource"),
+                    ),
+                ]
+                .into_iter(),
+            )
+            .expect("Invalid resources"),
+        );
+        let unreachable = || {
+            FnRetriever::new(|uri: &crate::Uri<&str>| {
+                Err(format!("Unexpected retrieval of {uri}").into())
+            })
+        };
+        let name = crate::options()
+            .with_registry(Arc::clone(&registry))
+            .with_retriever(unreachable())
+            .build(&json!({
+                "$schema": "https://example.com/meta.json",
+                "$ref": "https://example.com/library.json#name"
+            }))
+            .expect("Invalid schema");
+        assert!(name.is_valid(&json!("Alice")));
+        assert!(!name.is_valid(&json!(42)));
+        let age = crate::options()
+            .with_registry(Arc::clone(&registry))
+            .with_retriever(unreachable())
+            .build(&json!({"$ref": "https://example.com/library.json#/$defs/age"}))
+            .expect("Invalid schema");
+        assert!(age.is_valid(&json!(42)));
+        assert!(!age.is_valid(&json!(-1)));
+        // Resources missing from the shared registry are still retrieved
+        let error = crate::options()
+            .with_registry(registry)
+            .with_retriever(unreachable())
+            .build(&json!({"$ref": "https://example.com/other.json"}))
+            .expect_err("Should fail");
+        assert!(error
+            .to_string()
+            .contains("Unexpected retrieval of https://example.com/other.json"));
+    }
+
+    #[cfg(feature = "resolve-async")]
+    mod async_retrieval {
+        use referencing::{AsyncRetrieve, Uri};
+        use serde_json::{json, Value};
+
+        struct TestAsyncRetriever;
+
+        #[async_trait::async_trait]
+        impl AsyncRetrieve for TestAsyncRetriever {
+            async fn retrieve(
+                &self,
+                uri: &Uri<&str>,
+            ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+                match uri.as_str() {
+                    "https://example.com/person.json" => Ok(json!({
+                        "type": "object",
+                        "properties": {"age": {"$ref": "https://example.com/age.json"}},
+                        "required": ["name"]
+                    })),
+                    "https://example.com/age.json" => Ok(json!({"type": "integer", "minimum": 0})),
+                    "https://example.com/meta.json" => Ok(json!({
+                        "$schema": "http://json-schema.org/draft-07/schema#"
+                    })),
+                    _ => Err(format!("Unknown URI: {uri}").into()),
+                }
+            }
+        }
+
+        #[tokio::test]
+        async fn build_async() {
+            let schema = json!({"$ref": "https://example.com/person.json"});
+            let validator = crate::options()
+                .with_async_retriever(TestAsyncRetriever)
+                .build_async(&schema)
+                .await
+                .expect("Invalid schema");
+            assert!(validator.is_valid(&json!({"name": "Alice", "age": 30})));
+            assert!(!validator.is_valid(&json!({"name": "Alice", "age": -1})));
+            assert!(!validator.is_valid(&json!({"age": 30})));
+        }
+
+        #[tokio::test]
+        async fn build_async_custom_meta_schema() {
+            let schema = json!({"$schema": "https://example.com/meta.json", "type": "string"});
+            let validator = crate::options()
+                .with_async_retriever(TestAsyncRetriever)
+                .build_async(&schema)
+                .await
+                .expect("Invalid schema");
+            assert!(validator.is_valid(&json!("Alice")));
+            assert!(!validator.is_valid(&json!(42)));
+        }
+
+        #[tokio::test]
+        async fn build_async_unretrievable() {
+            let schema = json!({"$ref": "https://example.com/missing.json"});
+            let error = crate::options()
+                .with_async_retriever(TestAsyncRetriever)
+                .build_async(&schema)
+                .await
+                .expect_err("Should fail");
+            assert_eq!(
+                error.to_string(),
+                "Resource 'https://example.com/missing.json' is not present in a registry and retrieving it failed: Unknown URI: https://example.com/missing.json"
+            );
+        }
+
+        #[tokio::test]
+        async fn build_async_retrieval_policy() {
+            let schema = json!({"$ref": "https://example.com/person.json"});
+            let error = crate::options()
+                .with_async_retriever(TestAsyncRetriever)
+                .with_retrieval_policy(crate::RetrievalPolicy::new().max_depth(1))
+                .build_async(&schema)
+                .await
+                .expect_err("Should fail");
+            assert_eq!(
+                error.to_string(),
+                "Retrieving 'https://example.com/age.json' is not allowed by the retrieval policy: the resource is more than 1 references away"
+            );
+        }
+
+        #[tokio::test]
+        async fn build_async_sync_retriever() {
+            let retriever = crate::MapRetriever::new().with_resource(
+                referencing::uri::from_str("https://example.com/person.json").expect("Invalid URI"),
+                json!({"properties": {"name": {"type": "string"}}}),
+            );
+            let schema = json!({"$ref": "https://example.com/person.json"});
+            let validator = crate::options()
+                .with_retriever(retriever)
+                .build_async(&schema)
+                .await
+                .expect("Invalid schema");
+            assert!(validator.is_valid(&json!({"name": "Alice"})));
+            assert!(!validator.is_valid(&json!({"name": 42})));
+            // The asynchronous retriever takes precedence
+            let error = crate::options()
+                .with_retriever(crate::MapRetriever::new())
+                .with_async_retriever(TestAsyncRetriever)
+                .build_async(&json!({"$ref": "https://example.com/missing.json"}))
+                .await
+                .expect_err("Should fail");
+            assert!(error
+                .to_string()
+                .ends_with("Unknown URI: https://example.com/missing.json"));
+        }
+
+        #[test]
+        fn build_async_is_send() {
+            fn assert_send<T: Send>(_: &T) {}
+            let schema = json!({"type": "string"});
+            let options = crate::options();
+            assert_send(&options.build_async(&schema));
+        }
+    }
 }
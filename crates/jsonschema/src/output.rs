@@ -1,17 +1,20 @@
 //! Implementation of json schema output formats specified in <https://json-schema.org/draft/2020-12/json-schema-core.html#rfc.section.12.2>
 //!
-//! Currently the "basic" formats is supported. The main contribution of this module is [`Output::basic`].
-//! See the documentation of that method for more information.
+//! The "flag", "basic", "detailed" and "verbose" formats are supported. The main contributions of
+//! this module are [`Output::basic`] and [`Output::detailed`]. See the documentation of those
+//! methods for more information.
 
 use std::{
     borrow::Cow,
-    collections::VecDeque,
+    collections::{btree_map, BTreeMap, BTreeSet, VecDeque},
     fmt,
     iter::{FromIterator, Sum},
     ops::AddAssign,
 };
 
-use crate::{paths::Location, validator::PartialApplication, ValidationError};
+use crate::{
+    formatter::ErrorFormatter, paths::Location, validator::PartialApplication, ValidationError,
+};
 use ahash::AHashMap;
 use referencing::Uri;
 use serde::ser::SerializeMap;
@@ -23,7 +26,7 @@ use crate::{node::SchemaNode, paths::LazyLocation, Validator};
 /// This can be converted into various representations based on the definitions in
 /// <https://json-schema.org/draft/2020-12/json-schema-core.html#rfc.section.12.2>
 ///
-/// Currently only the "flag" and "basic" output formats are supported
+/// The "flag", "basic", "detailed" and "verbose" output formats are supported
 #[derive(Debug, Clone)]
 pub struct Output<'a, 'b> {
     schema: &'a Validator,
@@ -101,10 +104,93 @@ impl<'a> Output<'a, '_> {
     ///     }
     /// }
     /// ```
+    ///
+    /// Error descriptions are rendered with the formatter set via
+    /// [`ValidationOptions::with_error_formatter`](crate::ValidationOptions::with_error_formatter),
+    /// if any.
     #[must_use]
     pub fn basic(&self) -> BasicOutput<'a> {
-        self.root_node
-            .apply_rooted(self.instance, &LazyLocation::new())
+        self.evaluate().into_basic()
+    }
+
+    /// Output a tree of errors and annotations according to the "detailed" output format.
+    ///
+    /// This is the tree produced by [`Output::verbose`], condensed: if the instance is invalid
+    /// only the failed schemas and keywords are kept, and nodes without an error or annotation
+    /// of their own are replaced by their only child. Hence it contains the same units as
+    /// [`Output::basic`], nested under the subschema (e.g. the `anyOf` branch or the `$ref`
+    /// target) which produced them. The root node always stands for the whole schema.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use serde_json::json;
+    ///
+    /// let schema = json!({
+    ///     "properties": {
+    ///         "age": {"anyOf": [{"type": "integer"}, {"type": "null"}]}
+    ///     }
+    /// });
+    /// let validator = jsonschema::validator_for(&schema)?;
+    ///
+    /// let output = validator.apply(&json!({"age": "old"})).detailed();
+    /// assert_eq!(
+    ///     serde_json::to_value(output)?,
+    ///     json!({
+    ///         "valid": false,
+    ///         "keywordLocation": "",
+    ///         "instanceLocation": "",
+    ///         "errors": [
+    ///             {
+    ///                 "valid": false,
+    ///                 "keywordLocation": "/properties/age/anyOf",
+    ///                 "instanceLocation": "/age",
+    ///                 "errors": [
+    ///                     {
+    ///                         "valid": false,
+    ///                         "keywordLocation": "/properties/age/anyOf/0/type",
+    ///                         "instanceLocation": "/age",
+    ///                         "error": "\"old\" is not of type \"integer\""
+    ///                     },
+    ///                     {
+    ///                         "valid": false,
+    ///                         "keywordLocation": "/properties/age/anyOf/1/type",
+    ///                         "instanceLocation": "/age",
+    ///                         "error": "\"old\" is not of type \"null\""
+    ///                     }
+    ///                 ]
+    ///             }
+    ///         ]
+    ///     })
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn detailed(&self) -> HierarchicalOutput<'a> {
+        let mut output = self.evaluate();
+        output.condense();
+        output
+    }
+
+    /// Output a tree of errors and annotations according to the "verbose" output format.
+    ///
+    /// Every schema and keyword which was evaluated gets a node, nested as they were applied,
+    /// so the result mirrors the evaluated part of the schema. Each node tells whether its
+    /// schema or keyword was satisfied, and the nodes which were satisfied are kept even if
+    /// the instance is invalid overall.
+    #[must_use]
+    pub fn verbose(&self) -> HierarchicalOutput<'a> {
+        self.evaluate()
+    }
+
+    fn evaluate(&self) -> HierarchicalOutput<'a> {
+        self.root_node.apply_rooted(
+            self.instance,
+            &LazyLocation::new(),
+            self.schema.config.error_formatter(),
+        )
     }
 }
 
@@ -170,24 +256,25 @@ impl Default for BasicOutput<'_> {
     }
 }
 
-impl<'a> From<BasicOutput<'a>> for PartialApplication<'a> {
-    fn from(output: BasicOutput<'a>) -> Self {
-        match output {
-            BasicOutput::Valid(anns) => PartialApplication::Valid {
+impl<'a> From<Vec<HierarchicalOutput<'a>>> for PartialApplication<'a> {
+    fn from(outputs: Vec<HierarchicalOutput<'a>>) -> Self {
+        if outputs.iter().all(HierarchicalOutput::is_valid) {
+            PartialApplication::Valid {
                 annotations: None,
-                child_results: anns,
-            },
-            BasicOutput::Invalid(errors) => PartialApplication::Invalid {
+                child_results: outputs,
+            }
+        } else {
+            PartialApplication::Invalid {
                 errors: Vec::new(),
-                child_results: errors,
-            },
+                child_results: outputs,
+            }
         }
     }
 }
 
-impl<'a> FromIterator<BasicOutput<'a>> for PartialApplication<'a> {
-    fn from_iter<T: IntoIterator<Item = BasicOutput<'a>>>(iter: T) -> Self {
-        iter.into_iter().sum::<BasicOutput<'_>>().into()
+impl<'a> FromIterator<HierarchicalOutput<'a>> for PartialApplication<'a> {
+    fn from_iter<T: IntoIterator<Item = HierarchicalOutput<'a>>>(iter: T) -> Self {
+        iter.into_iter().collect::<Vec<_>>().into()
     }
 }
 
@@ -233,6 +320,18 @@ impl<T> OutputUnit<T> {
         }
     }
 
+    fn into_parts(self) -> (OutputUnit<()>, T) {
+        (
+            OutputUnit {
+                keyword_location: self.keyword_location,
+                instance_location: self.instance_location,
+                absolute_keyword_location: self.absolute_keyword_location,
+                value: (),
+            },
+            self.value,
+        )
+    }
+
     /// The location in the schema of the keyword
     pub const fn keyword_location(&self) -> &Location {
         &self.keyword_location
@@ -313,11 +412,457 @@ impl From<serde_json::Value> for Annotations<'_> {
     }
 }
 
+/// The "detailed" and "verbose" output formats. See the documentation for [`Output::detailed`]
+/// and [`Output::verbose`] for examples of how to use this.
+///
+/// Each node stands for a schema or a keyword applied to an instance, with the nodes of the
+/// subschemas it applied as children.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HierarchicalOutput<'a> {
+    valid: bool,
+    keyword_location: Location,
+    instance_location: Location,
+    absolute_keyword_location: Option<Uri<String>>,
+    annotations: Option<Annotations<'a>>,
+    error: Option<ErrorDescription>,
+    children: Vec<HierarchicalOutput<'a>>,
+}
+
+impl<'a> HierarchicalOutput<'a> {
+    /// Create the node of a schema or keyword which was applied at the given locations.
+    ///
+    /// If the application failed with several errors, each of them becomes a child node at the
+    /// same locations, placed after the nodes of the subschemas.
+    pub(crate) fn from_application(
+        keyword_location: Location,
+        instance_location: Location,
+        absolute_keyword_location: Option<Uri<String>>,
+        application: PartialApplication<'a>,
+    ) -> HierarchicalOutput<'a> {
+        match application {
+            PartialApplication::Valid {
+                annotations,
+                child_results,
+            } => HierarchicalOutput {
+                valid: true,
+                keyword_location,
+                instance_location,
+                absolute_keyword_location,
+                annotations,
+                error: None,
+                children: child_results,
+            },
+            PartialApplication::Invalid {
+                mut errors,
+                child_results: mut children,
+            } => {
+                let error = if errors.len() == 1 {
+                    errors.pop()
+                } else {
+                    for error in errors {
+                        children.push(HierarchicalOutput::errored(
+                            keyword_location.clone(),
+                            instance_location.clone(),
+                            absolute_keyword_location.clone(),
+                            error,
+                        ));
+                    }
+                    None
+                };
+                HierarchicalOutput {
+                    valid: false,
+                    keyword_location,
+                    instance_location,
+                    absolute_keyword_location,
+                    annotations: None,
+                    error,
+                    children,
+                }
+            }
+        }
+    }
+
+    /// Create a valid node without children which carries the given annotations.
+    pub(crate) const fn annotated(
+        keyword_location: Location,
+        instance_location: Location,
+        absolute_keyword_location: Option<Uri<String>>,
+        annotations: Annotations<'a>,
+    ) -> HierarchicalOutput<'a> {
+        HierarchicalOutput {
+            valid: true,
+            keyword_location,
+            instance_location,
+            absolute_keyword_location,
+            annotations: Some(annotations),
+            error: None,
+            children: Vec::new(),
+        }
+    }
+
+    /// Create an invalid node without children which carries the given error.
+    pub(crate) const fn errored(
+        keyword_location: Location,
+        instance_location: Location,
+        absolute_keyword_location: Option<Uri<String>>,
+        error: ErrorDescription,
+    ) -> HierarchicalOutput<'a> {
+        HierarchicalOutput {
+            valid: false,
+            keyword_location,
+            instance_location,
+            absolute_keyword_location,
+            annotations: None,
+            error: Some(error),
+            children: Vec::new(),
+        }
+    }
+
+    /// Drop the valid children of invalid nodes and replace the children which have no error or
+    /// annotation of their own by their only child, or drop them if they have none.
+    fn condense(&mut self) {
+        for mut child in std::mem::take(&mut self.children) {
+            if child.valid != self.valid {
+                continue;
+            }
+            child.condense();
+            if child.annotations.is_none() && child.error.is_none() && child.children.len() < 2 {
+                self.children.extend(child.children.pop());
+            } else {
+                self.children.push(child);
+            }
+        }
+    }
+
+    /// Flatten this tree into the "basic" output format. Annotations are listed before the
+    /// annotations of the subschemas, whereas errors are listed after the errors of the
+    /// subschemas, i.e. the most specific error comes first.
+    fn into_basic(self) -> BasicOutput<'a> {
+        if self.valid {
+            let mut units = VecDeque::new();
+            self.collect_annotations(&mut units);
+            BasicOutput::Valid(units)
+        } else {
+            let mut units = VecDeque::new();
+            self.collect_errors(&mut units);
+            BasicOutput::Invalid(units)
+        }
+    }
+
+    fn collect_annotations(self, units: &mut VecDeque<OutputUnit<Annotations<'a>>>) {
+        if !self.valid {
+            return;
+        }
+        if let Some(annotations) = self.annotations {
+            units.push_back(OutputUnit::<Annotations<'a>>::annotations(
+                self.keyword_location,
+                self.instance_location,
+                self.absolute_keyword_location,
+                annotations,
+            ));
+        }
+        for child in self.children {
+            child.collect_annotations(units);
+        }
+    }
+
+    fn collect_errors(self, units: &mut VecDeque<OutputUnit<ErrorDescription>>) {
+        if self.valid {
+            return;
+        }
+        for child in self.children {
+            child.collect_errors(units);
+        }
+        if let Some(error) = self.error {
+            units.push_back(OutputUnit::<ErrorDescription>::error(
+                self.keyword_location,
+                self.instance_location,
+                self.absolute_keyword_location,
+                error,
+            ));
+        }
+    }
+
+    /// Whether the instance satisfied the schema or keyword of this node.
+    #[must_use]
+    pub const fn is_valid(&self) -> bool {
+        self.valid
+    }
+
+    /// The location in the schema of the keyword
+    #[must_use]
+    pub const fn keyword_location(&self) -> &Location {
+        &self.keyword_location
+    }
+
+    /// The absolute location in the schema of the keyword. This will be
+    /// different to `keyword_location` if the schema is a resolved reference.
+    #[must_use]
+    pub fn absolute_keyword_location(&self) -> Option<Uri<&str>> {
+        self.absolute_keyword_location
+            .as_ref()
+            .map(|uri| uri.borrow())
+    }
+
+    /// The location in the instance
+    #[must_use]
+    pub const fn instance_location(&self) -> &Location {
+        &self.instance_location
+    }
+
+    /// The annotations produced at this node, if any
+    #[must_use]
+    pub const fn annotations(&self) -> Option<&Annotations<'a>> {
+        self.annotations.as_ref()
+    }
+
+    /// The error produced at this node, if any
+    #[must_use]
+    pub const fn error_description(&self) -> Option<&ErrorDescription> {
+        self.error.as_ref()
+    }
+
+    /// Nested nodes
+    #[must_use]
+    pub fn children(&self) -> &[HierarchicalOutput<'a>] {
+        &self.children
+    }
+}
+
+/// Annotations collected from a successful validation, grouped by the instance location they
+/// apply to. See [`Validator::annotate`] for examples of how to use this.
+///
+/// Following the specification, annotations produced by subschemas which failed (e.g. a
+/// non-matching `anyOf` branch or a failed `if`) are dropped, and nothing is collected if the
+/// instance is invalid.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CollectedAnnotations<'a> {
+    locations: BTreeMap<Location, LocationAnnotations<'a>>,
+}
+
+impl<'a> CollectedAnnotations<'a> {
+    pub(crate) fn new(output: BasicOutput<'a>, instance: &serde_json::Value) -> Self {
+        let mut collected = CollectedAnnotations::default();
+        let BasicOutput::Valid(units) = output else {
+            return collected;
+        };
+        for unit in units {
+            let (unit, annotations) = unit.into_parts();
+            let location = collected
+                .locations
+                .entry(unit.instance_location.clone())
+                .or_default();
+            match annotations.0 {
+                AnnotationsInner::UnmatchedKeywords(keywords) => {
+                    for (keyword, value) in keywords {
+                        location.push(
+                            keyword,
+                            unit.keyword_location.join(keyword),
+                            unit.absolute_keyword_location.clone(),
+                            Cow::Borrowed(value),
+                        );
+                    }
+                }
+                AnnotationsInner::ValueRef(value) => {
+                    location.push_keyword_annotation(unit, Cow::Borrowed(value), instance);
+                }
+                AnnotationsInner::Value(value) => {
+                    location.push_keyword_annotation(unit, Cow::Owned(*value), instance);
+                }
+            }
+        }
+        collected
+    }
+
+    /// Annotations which apply to the given instance location, e.g. `/user/email`.
+    #[must_use]
+    pub fn get(&self, instance_location: &str) -> Option<&LocationAnnotations<'a>> {
+        self.locations
+            .get(&Location::from_escaped(instance_location))
+    }
+
+    /// Iterate over instance locations and the annotations which apply to them.
+    pub fn iter(&self) -> btree_map::Iter<'_, Location, LocationAnnotations<'a>> {
+        self.locations.iter()
+    }
+
+    /// Whether no annotations were collected.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.locations.is_empty()
+    }
+}
+
+impl<'b, 'a> IntoIterator for &'b CollectedAnnotations<'a> {
+    type Item = (&'b Location, &'b LocationAnnotations<'a>);
+    type IntoIter = btree_map::Iter<'b, Location, LocationAnnotations<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Annotations which apply to a single instance location, grouped by keyword.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LocationAnnotations<'a> {
+    keywords: BTreeMap<String, Vec<KeywordAnnotation<'a>>>,
+    evaluated_properties: BTreeSet<String>,
+    evaluated_items: BTreeSet<usize>,
+}
+
+impl<'a> LocationAnnotations<'a> {
+    fn push(
+        &mut self,
+        keyword: &str,
+        keyword_location: Location,
+        absolute_keyword_location: Option<Uri<String>>,
+        value: Cow<'a, serde_json::Value>,
+    ) {
+        self.keywords
+            .entry(keyword.to_string())
+            .or_default()
+            .push(KeywordAnnotation {
+                keyword_location,
+                absolute_keyword_location,
+                value,
+            });
+    }
+
+    /// Record an annotation produced by an applicator keyword and update the evaluated
+    /// properties and items accordingly.
+    fn push_keyword_annotation(
+        &mut self,
+        unit: OutputUnit<()>,
+        value: Cow<'a, serde_json::Value>,
+        instance: &serde_json::Value,
+    ) {
+        let keyword = unit
+            .keyword_location
+            .as_str()
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        let items_count = || {
+            instance
+                .pointer(unit.instance_location.as_str())
+                .and_then(serde_json::Value::as_array)
+                .map_or(0, Vec::len)
+        };
+        match (keyword.as_str(), value.as_ref()) {
+            (
+                "properties"
+                | "patternProperties"
+                | "additionalProperties"
+                | "unevaluatedProperties",
+                serde_json::Value::Array(names),
+            ) => {
+                self.evaluated_properties.extend(
+                    names
+                        .iter()
+                        .filter_map(serde_json::Value::as_str)
+                        .map(String::from),
+                );
+            }
+            ("prefixItems" | "items", serde_json::Value::Number(largest)) => {
+                if let Some(largest) = largest.as_u64() {
+                    self.evaluated_items
+                        .extend(0..=usize::try_from(largest).unwrap_or(usize::MAX));
+                }
+            }
+            ("prefixItems" | "items" | "unevaluatedItems", serde_json::Value::Bool(true)) => {
+                self.evaluated_items.extend(0..items_count());
+            }
+            ("contains", serde_json::Value::Array(indices)) => {
+                self.evaluated_items.extend(
+                    indices
+                        .iter()
+                        .filter_map(serde_json::Value::as_u64)
+                        .filter_map(|idx| usize::try_from(idx).ok()),
+                );
+            }
+            _ => {}
+        }
+        self.push(
+            &keyword,
+            unit.keyword_location,
+            unit.absolute_keyword_location,
+            value,
+        );
+    }
+
+    /// Annotations produced by the given keyword, e.g. `title` or `default`. There may be
+    /// several of them if the keyword appears in multiple subschemas applied to this location.
+    #[must_use]
+    pub fn get(&self, keyword: &str) -> &[KeywordAnnotation<'a>] {
+        self.keywords.get(keyword).map_or(&[], Vec::as_slice)
+    }
+
+    /// Iterate over keywords and the annotations they produced.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[KeywordAnnotation<'a>])> {
+        self.keywords
+            .iter()
+            .map(|(keyword, annotations)| (keyword.as_str(), annotations.as_slice()))
+    }
+
+    /// Object properties evaluated by `properties`, `patternProperties`,
+    /// `additionalProperties` or `unevaluatedProperties` at this location.
+    #[must_use]
+    pub const fn evaluated_properties(&self) -> &BTreeSet<String> {
+        &self.evaluated_properties
+    }
+
+    /// Array indices evaluated by `prefixItems`, `items`, `contains` or `unevaluatedItems` at
+    /// this location.
+    #[must_use]
+    pub const fn evaluated_items(&self) -> &BTreeSet<usize> {
+        &self.evaluated_items
+    }
+}
+
+/// A single annotation along with the place in the schema which produced it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeywordAnnotation<'a> {
+    keyword_location: Location,
+    absolute_keyword_location: Option<Uri<String>>,
+    value: Cow<'a, serde_json::Value>,
+}
+
+impl KeywordAnnotation<'_> {
+    /// The location in the schema of the keyword
+    #[must_use]
+    pub const fn keyword_location(&self) -> &Location {
+        &self.keyword_location
+    }
+
+    /// The absolute location in the schema of the keyword.
+    #[must_use]
+    pub fn absolute_keyword_location(&self) -> Option<Uri<&str>> {
+        self.absolute_keyword_location
+            .as_ref()
+            .map(|uri| uri.borrow())
+    }
+
+    /// The annotation value
+    #[must_use]
+    pub fn value(&self) -> &serde_json::Value {
+        &self.value
+    }
+}
+
 /// An error associated with an [`OutputUnit`]
 #[derive(serde::Serialize, Debug, Clone, PartialEq, Eq)]
 pub struct ErrorDescription(String);
 
 impl ErrorDescription {
+    /// Render `error` with `formatter`, or with its `Display` implementation if there is none.
+    pub(crate) fn new(error: &ValidationError<'_>, formatter: Option<&dyn ErrorFormatter>) -> Self {
+        match formatter {
+            Some(formatter) => ErrorDescription(formatter.format(error)),
+            None => ErrorDescription(error.to_string()),
+        }
+    }
+
     /// Returns the inner [`String`] of the error description.
     #[inline]
     pub fn into_inner(self) -> String {
@@ -407,3 +952,32 @@ impl serde::Serialize for OutputUnit<ErrorDescription> {
         map_ser.end()
     }
 }
+
+impl serde::Serialize for HierarchicalOutput<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut map_ser = serializer.serialize_map(None)?;
+        map_ser.serialize_entry("valid", &self.valid)?;
+        map_ser.serialize_entry("keywordLocation", self.keyword_location.as_str())?;
+        if let Some(absolute) = &self.absolute_keyword_location {
+            map_ser.serialize_entry("absoluteKeywordLocation", &absolute)?;
+        }
+        map_ser.serialize_entry("instanceLocation", self.instance_location.as_str())?;
+        if let Some(error) = &self.error {
+            map_ser.serialize_entry("error", error)?;
+        }
+        if let Some(annotations) = &self.annotations {
+            map_ser.serialize_entry("annotation", annotations)?;
+        }
+        if !self.children.is_empty() {
+            if self.valid {
+                map_ser.serialize_entry("annotations", &self.children)?;
+            } else {
+                map_ser.serialize_entry("errors", &self.children)?;
+            }
+        }
+        map_ser.end()
+    }
+}
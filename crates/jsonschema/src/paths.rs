@@ -136,7 +136,7 @@ impl From<usize> for LocationSegment<'_> {
 }
 
 /// A cheap to clone JSON pointer that represents location with a JSON value.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Location(Arc<String>);
 
 impl Location {
@@ -161,6 +161,10 @@ impl Location {
             }
         }
     }
+    /// Create a location from an already escaped JSON pointer.
+    pub(crate) fn from_escaped(pointer: &str) -> Self {
+        Self(Arc::new(pointer.to_string()))
+    }
     /// Get a string slice representing the location.
     pub fn as_str(&self) -> &str {
         &self.0
@@ -221,6 +225,20 @@ impl fmt::Display for Location {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for Location {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Location {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(|pointer| Self(Arc::new(pointer)))
+    }
+}
+
 impl<'a> IntoIterator for &'a Location {
     type Item = LocationSegment<'a>;
     type IntoIter = std::vec::IntoIter<LocationSegment<'a>>;
@@ -6,6 +6,11 @@ use std::{convert::TryFrom, fmt, ops::BitOrAssign};
 /// For faster error handling in "type" keyword validator we have this enum, to match
 /// with it instead of a string.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
 #[allow(missing_docs)]
 pub enum PrimitiveType {
     Array,
@@ -120,6 +125,23 @@ impl IntoIterator for PrimitiveTypesBitMap {
         PrimitiveTypesBitMapIterator { bit_map: self }
     }
 }
+#[cfg(feature = "serde")]
+impl serde::Serialize for PrimitiveTypesBitMap {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(*self)
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for PrimitiveTypesBitMap {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let types = Vec::<PrimitiveType>::deserialize(deserializer)?;
+        Ok(types
+            .into_iter()
+            .fold(Self::new(), |bit_map, primitive_type| {
+                bit_map.add_type(primitive_type)
+            }))
+    }
+}
 #[cfg(test)]
 impl From<Vec<PrimitiveType>> for PrimitiveTypesBitMap {
     fn from(value: Vec<PrimitiveType>) -> Self {
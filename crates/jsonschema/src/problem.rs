@@ -0,0 +1,485 @@
+//! Validation failures as [RFC 9457](https://www.rfc-editor.org/rfc/rfc9457) problem details.
+//!
+//! [`ProblemOptions`] builds a [`ProblemDetails`] document from validation errors or from the
+//! "basic" output format. Besides the standard members, the document lists every error in the
+//! `errors` extension member:
+//!
+//! ```rust
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use jsonschema::problem::ProblemOptions;
+//! use serde_json::json;
+//!
+//! let schema = json!({"properties": {"age": {"minimum": 18}}});
+//! let validator = jsonschema::validator_for(&schema)?;
+//! let instance = json!({"age": 16});
+//!
+//! let problem = ProblemOptions::new()
+//!     .with_type("https://example.com/problems/invalid-body")
+//!     .with_title("Invalid request body")
+//!     .with_status(422)
+//!     .for_errors(validator.iter_errors(&instance));
+//! assert_eq!(
+//!     serde_json::to_value(&problem)?,
+//!     json!({
+//!         "type": "https://example.com/problems/invalid-body",
+//!         "title": "Invalid request body",
+//!         "status": 422,
+//!         "errors": [{
+//!             "pointer": "/age",
+//!             "keywordLocation": "/properties/age/minimum",
+//!             "message": "16 is less than the minimum of 18",
+//!             "kind": {"kind": "minimum", "limit": 18}
+//!         }]
+//!     })
+//! );
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The `kind` member has the same representation as [`crate::error::ValidationErrorKind`] has
+//! with `serde`.
+use crate::{
+    error::ValidationErrorKind, formatter::ErrorFormatter, output::BasicOutput, ValidationError,
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::{borrow::Cow, fmt, sync::Arc};
+
+/// Media type of problem details documents.
+pub const CONTENT_TYPE: &str = "application/problem+json";
+
+/// Settings for building [`ProblemDetails`].
+#[derive(Clone)]
+pub struct ProblemOptions {
+    type_uri: String,
+    title: String,
+    status: Option<u16>,
+    detail: Option<String>,
+    instance: Option<String>,
+    mask: Option<String>,
+    error_formatter: Option<Arc<dyn ErrorFormatter>>,
+}
+
+impl Default for ProblemOptions {
+    fn default() -> Self {
+        ProblemOptions {
+            type_uri: "about:blank".to_string(),
+            title: "Validation failed".to_string(),
+            status: None,
+            detail: None,
+            instance: None,
+            mask: None,
+            error_formatter: None,
+        }
+    }
+}
+
+impl fmt::Debug for ProblemOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProblemOptions")
+            .field("type_uri", &self.type_uri)
+            .field("title", &self.title)
+            .field("status", &self.status)
+            .field("detail", &self.detail)
+            .field("instance", &self.instance)
+            .field("mask", &self.mask)
+            .field("error_formatter", &self.error_formatter.is_some())
+            .finish()
+    }
+}
+
+impl ProblemOptions {
+    /// Create options with the `about:blank` problem type and the "Validation failed" title.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Set the URI identifying the problem type.
+    pub fn with_type(&mut self, uri: impl Into<String>) -> &mut Self {
+        self.type_uri = uri.into();
+        self
+    }
+    /// Set the short summary of the problem type.
+    pub fn with_title(&mut self, title: impl Into<String>) -> &mut Self {
+        self.title = title.into();
+        self
+    }
+    /// Set the HTTP status code.
+    pub fn with_status(&mut self, status: u16) -> &mut Self {
+        self.status = Some(status);
+        self
+    }
+    /// Set the explanation specific to this occurrence of the problem.
+    pub fn with_detail(&mut self, detail: impl Into<String>) -> &mut Self {
+        self.detail = Some(detail.into());
+        self
+    }
+    /// Set the URI identifying this occurrence of the problem.
+    pub fn with_instance(&mut self, uri: impl Into<String>) -> &mut Self {
+        self.instance = Some(uri.into());
+        self
+    }
+    /// Replace instance values with `placeholder`, as [`ValidationError::masked_with`] does.
+    ///
+    /// Messages of the "basic" output format are already rendered and can't be masked, so they
+    /// are left out. So are error kinds which carry data decoded from the instance, such as the
+    /// bytes of [`ValidationErrorKind::FromUtf8`].
+    pub fn with_mask(&mut self, placeholder: impl Into<String>) -> &mut Self {
+        self.mask = Some(placeholder.into());
+        self
+    }
+    /// Render the messages of [`ProblemOptions::for_errors`] with `formatter` instead of the
+    /// English [`Display`](std::fmt::Display) output of errors.
+    ///
+    /// With a mask, the formatter receives errors whose instances are replaced with the
+    /// placeholder string. Messages of the "basic" output format are rendered with the formatter
+    /// of the validator that produced them.
+    pub fn with_error_formatter(&mut self, formatter: impl ErrorFormatter + 'static) -> &mut Self {
+        self.error_formatter = Some(Arc::new(formatter));
+        self
+    }
+    /// Build a problem document listing `errors`.
+    pub fn for_errors<'a>(
+        &self,
+        errors: impl IntoIterator<Item = ValidationError<'a>>,
+    ) -> ProblemDetails {
+        let errors = errors
+            .into_iter()
+            .map(|mut error| {
+                if let Some(placeholder) = &self.mask {
+                    mask_error(&mut error, placeholder);
+                }
+                let message = match (&self.error_formatter, &self.mask) {
+                    (Some(formatter), _) => formatter.format(&error),
+                    (None, Some(placeholder)) => {
+                        error.masked_with(placeholder.as_str()).to_string()
+                    }
+                    (None, None) => error.to_string(),
+                };
+                let kind = (self.mask.is_none() || !holds_instance_data(&error.kind)).then(|| {
+                    serde_json::to_value(&error.kind).expect("Error kinds are always serializable")
+                });
+                ProblemError {
+                    pointer: error.instance_path.as_str().to_string(),
+                    keyword_location: error.schema_path.as_str().to_string(),
+                    message: Some(message),
+                    kind,
+                }
+            })
+            .collect();
+        self.document(errors)
+    }
+    /// Build a problem document listing the errors of the "basic" output format, or `None` if
+    /// the output is valid.
+    ///
+    /// The output doesn't keep error kinds, so errors have no `kind` member.
+    #[must_use]
+    pub fn for_output(&self, output: &BasicOutput<'_>) -> Option<ProblemDetails> {
+        let BasicOutput::Invalid(units) = output else {
+            return None;
+        };
+        let errors = units
+            .iter()
+            .map(|unit| ProblemError {
+                pointer: unit.instance_location().as_str().to_string(),
+                keyword_location: unit.keyword_location().as_str().to_string(),
+                message: self
+                    .mask
+                    .is_none()
+                    .then(|| unit.error_description().to_string()),
+                kind: None,
+            })
+            .collect();
+        Some(self.document(errors))
+    }
+
+    fn document(&self, errors: Vec<ProblemError>) -> ProblemDetails {
+        ProblemDetails {
+            type_uri: self.type_uri.clone(),
+            title: self.title.clone(),
+            status: self.status,
+            detail: self.detail.clone(),
+            instance: self.instance.clone(),
+            errors,
+        }
+    }
+}
+
+/// Replace the instance of `error` and of the errors nested in it with `placeholder`.
+fn mask_error(error: &mut ValidationError<'_>, placeholder: &str) {
+    error.instance = Cow::Owned(Value::String(placeholder.to_string()));
+    mask_nested_errors(&mut error.kind, placeholder);
+}
+
+fn mask_nested_errors(kind: &mut ValidationErrorKind, placeholder: &str) {
+    match kind {
+        ValidationErrorKind::AnyOf { context }
+        | ValidationErrorKind::Contains { context }
+        | ValidationErrorKind::OneOfMultipleValid { context, .. }
+        | ValidationErrorKind::OneOfNotValid { context } => {
+            for error in context.iter_mut().flatten() {
+                mask_error(error, placeholder);
+            }
+        }
+        ValidationErrorKind::PropertyNames { error } => mask_error(error, placeholder),
+        ValidationErrorKind::ErrorMessage { kind, .. } => mask_nested_errors(kind, placeholder),
+        _ => {}
+    }
+}
+
+/// Whether `kind`, or the kind of an error nested in it, carries data taken from the instance
+/// which `mask_error` can't replace.
+fn holds_instance_data(kind: &ValidationErrorKind) -> bool {
+    match kind {
+        ValidationErrorKind::FromUtf8 { .. } => true,
+        ValidationErrorKind::AnyOf { context }
+        | ValidationErrorKind::Contains { context }
+        | ValidationErrorKind::OneOfMultipleValid { context, .. }
+        | ValidationErrorKind::OneOfNotValid { context } => context
+            .iter()
+            .flatten()
+            .any(|error| holds_instance_data(&error.kind)),
+        ValidationErrorKind::PropertyNames { error } => holds_instance_data(&error.kind),
+        ValidationErrorKind::ErrorMessage { kind, .. } => holds_instance_data(kind),
+        _ => false,
+    }
+}
+
+/// An RFC 9457 problem details document for a validation failure.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    type_uri: String,
+    title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    instance: Option<String>,
+    errors: Vec<ProblemError>,
+}
+
+impl ProblemDetails {
+    /// The URI identifying the problem type.
+    #[must_use]
+    pub fn type_uri(&self) -> &str {
+        &self.type_uri
+    }
+    /// The short summary of the problem type.
+    #[must_use]
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+    /// The HTTP status code.
+    #[must_use]
+    pub fn status(&self) -> Option<u16> {
+        self.status
+    }
+    /// The explanation specific to this occurrence of the problem.
+    #[must_use]
+    pub fn detail(&self) -> Option<&str> {
+        self.detail.as_deref()
+    }
+    /// The URI identifying this occurrence of the problem.
+    #[must_use]
+    pub fn instance(&self) -> Option<&str> {
+        self.instance.as_deref()
+    }
+    /// The validation errors.
+    #[must_use]
+    pub fn errors(&self) -> &[ProblemError] {
+        &self.errors
+    }
+}
+
+/// A single validation error in the `errors` member of [`ProblemDetails`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProblemError {
+    pointer: String,
+    keyword_location: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kind: Option<Value>,
+}
+
+impl ProblemError {
+    /// JSON pointer to the invalid part of the instance.
+    #[must_use]
+    pub fn pointer(&self) -> &str {
+        &self.pointer
+    }
+    /// JSON pointer to the failed keyword in the schema.
+    #[must_use]
+    pub fn keyword_location(&self) -> &str {
+        &self.keyword_location
+    }
+    /// The error message.
+    #[must_use]
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+    /// The serialized error kind.
+    #[must_use]
+    pub fn kind(&self) -> Option<&Value> {
+        self.kind.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProblemOptions;
+    use crate::formatter::MessageCatalog;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test]
+    fn defaults() {
+        let schema = json!({"required": ["a"]});
+        let validator = crate::validator_for(&schema).expect("Invalid schema");
+        let instance = json!({});
+        let problem = ProblemOptions::new().for_errors(validator.iter_errors(&instance));
+        assert_eq!(
+            serde_json::to_value(&problem).expect("Serializable"),
+            json!({
+                "type": "about:blank",
+                "title": "Validation failed",
+                "errors": [{
+                    "pointer": "",
+                    "keywordLocation": "/required",
+                    "message": "\"a\" is a required property",
+                    "kind": {"kind": "required", "property": "a"}
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn all_members() {
+        let problem = ProblemOptions::new()
+            .with_type("https://example.com/invalid")
+            .with_title("Invalid")
+            .with_status(400)
+            .with_detail("The body is invalid")
+            .with_instance("/requests/1")
+            .for_errors(Vec::new());
+        assert_eq!(problem.type_uri(), "https://example.com/invalid");
+        assert_eq!(problem.title(), "Invalid");
+        assert_eq!(problem.status(), Some(400));
+        assert_eq!(problem.detail(), Some("The body is invalid"));
+        assert_eq!(problem.instance(), Some("/requests/1"));
+        assert!(problem.errors().is_empty());
+    }
+
+    #[test_case(&json!({"maxLength": 2}), &json!("secret"), "[hidden] is longer than 2 characters", &json!({"kind": "max_length", "limit": 2}); "message")]
+    #[test_case(
+        &json!({"anyOf": [{"maxLength": 2}]}),
+        &json!("secret"),
+        "[hidden] is not valid under any of the schemas listed in the 'anyOf' keyword",
+        &json!({"kind": "any_of", "context": [[{"kind": "max_length", "limit": 2, "instance": "[hidden]", "instance_path": "", "schema_path": "/anyOf/0/maxLength"}]]});
+        "nested errors"
+    )]
+    #[test_case(
+        &json!({"propertyNames": {"anyOf": [{"maxLength": 2}]}}),
+        &json!({"secret": 1}),
+        "[hidden] is not valid under any of the schemas listed in the 'anyOf' keyword",
+        &json!({"kind": "property_names", "error": {"kind": "any_of", "context": [[{"kind": "max_length", "limit": 2, "instance": "[hidden]", "instance_path": "", "schema_path": "/propertyNames/anyOf/0/maxLength"}]], "instance": "[hidden]", "instance_path": "", "schema_path": "/propertyNames/anyOf"}});
+        "property names"
+    )]
+    fn masked(schema: &Value, instance: &Value, message: &str, kind: &Value) {
+        let validator = crate::validator_for(schema).expect("Invalid schema");
+        let problem = ProblemOptions::new()
+            .with_mask("[hidden]")
+            .for_errors(validator.iter_errors(instance));
+        let error = &problem.errors()[0];
+        assert_eq!(error.message(), Some(message));
+        assert_eq!(error.kind(), Some(kind));
+        assert!(!serde_json::to_string(&problem)
+            .expect("Serializable")
+            .contains("secret\""));
+    }
+
+    #[test]
+    fn masked_decoded_content() {
+        let validator = crate::options()
+            .with_draft(crate::Draft::Draft7)
+            .build(&json!({"contentEncoding": "base64", "contentMediaType": "application/json"}))
+            .expect("Invalid schema");
+        let instance = json!("c2VjcmV0/w==");
+        let problem = ProblemOptions::new()
+            .with_mask("***")
+            .for_errors(validator.iter_errors(&instance));
+        let error = &problem.errors()[0];
+        assert_eq!(error.kind(), None);
+        let document = serde_json::to_string(&problem).expect("Serializable");
+        assert!(!document.contains("c2VjcmV0"));
+        assert!(!document.contains("115,101,99,114,101,116"));
+        let problem = ProblemOptions::new().for_errors(validator.iter_errors(&instance));
+        assert!(problem.errors()[0].kind().is_some());
+    }
+
+    #[test]
+    fn error_formatter() {
+        let validator = crate::options()
+            .with_error_formatter(MessageCatalog::new([(
+                "maxLength",
+                "{instance} ist zu lang",
+            )]))
+            .build(&json!({"maxLength": 2}))
+            .expect("Invalid schema");
+        let instance = json!("secret");
+        let catalog = MessageCatalog::new([("maxLength", "{instance} ist zu lang")]);
+        let problem = ProblemOptions::new()
+            .with_error_formatter(catalog.clone())
+            .for_errors(validator.iter_errors(&instance));
+        assert_eq!(
+            problem.errors()[0].message(),
+            Some(r#""secret" ist zu lang"#)
+        );
+        let problem = ProblemOptions::new()
+            .with_error_formatter(catalog)
+            .with_mask("[hidden]")
+            .for_errors(validator.iter_errors(&instance));
+        assert_eq!(
+            problem.errors()[0].message(),
+            Some(r#""[hidden]" ist zu lang"#)
+        );
+        let output = validator.apply(&instance).basic();
+        let problem = ProblemOptions::new()
+            .for_output(&output)
+            .expect("Invalid output");
+        assert_eq!(
+            problem.errors()[0].message(),
+            Some(r#""secret" ist zu lang"#)
+        );
+    }
+
+    #[test]
+    fn basic_output() {
+        let schema = json!({"properties": {"a": {"type": "string"}}});
+        let validator = crate::validator_for(&schema).expect("Invalid schema");
+        let instance = json!({"a": 1});
+        let output = validator.apply(&instance).basic();
+        let problem = ProblemOptions::new()
+            .for_output(&output)
+            .expect("Invalid output");
+        assert_eq!(
+            serde_json::to_value(problem.errors()).expect("Serializable"),
+            json!([{
+                "pointer": "/a",
+                "keywordLocation": "/properties/a/type",
+                "message": "1 is not of type \"string\""
+            }])
+        );
+        let masked = ProblemOptions::new()
+            .with_mask("value")
+            .for_output(&output)
+            .expect("Invalid output");
+        assert_eq!(masked.errors()[0].message(), None);
+        let instance = json!({"a": "b"});
+        let valid = validator.apply(&instance).basic();
+        assert!(ProblemOptions::new().for_output(&valid).is_none());
+    }
+}
@@ -11,6 +11,7 @@ pub(crate) type PatternedValidators = Vec<(Regex, SchemaNode)>;
 pub(crate) trait PropertiesValidatorsMap: Send + Sync {
     fn get_validator(&self, property: &str) -> Option<&SchemaNode>;
     fn get_key_validator(&self, property: &str) -> Option<(&String, &SchemaNode)>;
+    fn iter_validators(&self) -> Box<dyn Iterator<Item = (&String, &SchemaNode)> + '_>;
 }
 
 // We're defining two different property validator map implementations, one for small map sizes and
@@ -42,6 +43,10 @@ impl PropertiesValidatorsMap for SmallValidatorsMap {
         }
         None
     }
+    #[inline]
+    fn iter_validators(&self) -> Box<dyn Iterator<Item = (&String, &SchemaNode)> + '_> {
+        Box::new(self.iter().map(|(prop, node)| (prop, node)))
+    }
 }
 
 impl PropertiesValidatorsMap for BigValidatorsMap {
@@ -54,6 +59,10 @@ impl PropertiesValidatorsMap for BigValidatorsMap {
     fn get_key_validator(&self, property: &str) -> Option<(&String, &SchemaNode)> {
         self.get_key_value(property)
     }
+    #[inline]
+    fn iter_validators(&self) -> Box<dyn Iterator<Item = (&String, &SchemaNode)> + '_> {
+        Box::new(self.iter())
+    }
 }
 
 pub(crate) fn compile_small_map<'a>(
@@ -102,6 +111,106 @@ where
     })
 }
 
+/// Insert defaults for missing properties and apply defaults within the present ones.
+/// Inserted defaults are used as they are, without applying nested defaults to them.
+pub(crate) fn apply_property_defaults<'a>(
+    properties: impl Iterator<Item = (&'a String, &'a SchemaNode)>,
+    instance: &mut Value,
+    original: &Value,
+) {
+    if let Value::Object(object) = instance {
+        for (property, node) in properties {
+            if let Some(value) = object.get_mut(property) {
+                // Properties inserted as defaults by other keywords have no original
+                if let Some(original) = original.get(property) {
+                    node.apply_defaults(value, original);
+                }
+            } else if let Some(default) = node.default_value() {
+                object.insert(property.clone(), default.clone());
+            }
+        }
+    }
+}
+
+/// Apply defaults within existing array items and append defaults for the missing trailing
+/// positions, stopping at the first position without a default.
+pub(crate) fn apply_item_defaults(nodes: &[SchemaNode], instance: &mut Value, original: &Value) {
+    if let (Value::Array(items), Value::Array(originals)) = (instance, original) {
+        for ((item, original), node) in items.iter_mut().zip(originals).zip(nodes) {
+            node.apply_defaults(item, original);
+        }
+        for node in nodes.iter().skip(items.len()) {
+            if let Some(default) = node.default_value() {
+                items.push(default.clone());
+            } else {
+                break;
+            }
+        }
+    }
+}
+
+/// Prune each property of `instance` with the subschemas that apply to it. Properties without any
+/// are pruned with `additional`, or removed if it is `None`, i.e. `additionalProperties: false`.
+pub(crate) fn prune_properties<'a, F>(
+    instance: &mut Value,
+    location: &Location,
+    additional: Option<&SchemaNode>,
+    subschemas: F,
+    pruned: &mut Vec<Location>,
+) where
+    F: Fn(&str) -> Vec<&'a SchemaNode>,
+{
+    if let Value::Object(object) = instance {
+        let mut removed = Vec::new();
+        for (property, value) in object.iter_mut() {
+            let location = location.join(property);
+            let nodes = subschemas(property);
+            if !nodes.is_empty() {
+                for node in nodes {
+                    node.prune(value, &location, pruned);
+                }
+            } else if let Some(node) = additional {
+                node.prune(value, &location, pruned);
+            } else {
+                removed.push(property.clone());
+                pruned.push(location);
+            }
+        }
+        for property in removed {
+            object.remove(&property);
+        }
+    }
+}
+
+/// Prune each item of `instance` with the subschema that applies to its index.
+pub(crate) fn prune_items<'a, F>(
+    instance: &mut Value,
+    location: &Location,
+    subschema: F,
+    pruned: &mut Vec<Location>,
+) where
+    F: Fn(usize) -> Option<&'a SchemaNode>,
+{
+    if let Value::Array(items) = instance {
+        for (idx, item) in items.iter_mut().enumerate() {
+            if let Some(node) = subschema(idx) {
+                node.prune(item, &location.join(idx), pruned);
+            }
+        }
+    }
+}
+
+/// Subschemas of `patterns` whose regex matches `property`.
+pub(crate) fn matching_patterns<'a: 'p, 'p>(
+    patterns: &'a PatternedValidators,
+    property: &'p str,
+) -> impl Iterator<Item = &'a SchemaNode> + 'p {
+    patterns
+        .iter()
+        .filter(move |(re, _)| re.is_match(property).unwrap_or(false))
+        .map(|(_, node)| node)
+}
+
 /// Create a vector of pattern-validators pairs.
 #[inline]
 pub(crate) fn compile_patterns<'a>(
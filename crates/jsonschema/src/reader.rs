@@ -0,0 +1,371 @@
+//! Validation of JSON documents read incrementally from [`std::io::Read`].
+//!
+//! The top-level array or object is read element by element, and only the current element is
+//! kept in memory. It works as long as every keyword of the root schema either applies to each
+//! element on its own, or does not apply to the container at all:
+//!
+//! - `type` is checked once, before reading any elements;
+//! - `items`, `prefixItems` and `additionalItems` are applied to each array item;
+//! - `properties`, `patternProperties` and `additionalProperties` with a subschema are applied
+//!   to each object property, and only invalid properties are kept until the end of the object;
+//! - `$ref` and `allOf` are followed.
+//!
+//! Any other keyword that applies to the container, like `uniqueItems`, `contains`, `required`,
+//! `propertyNames` or `additionalProperties: false`, needs the whole container, so it is
+//! buffered and validated as usual. Either way, errors are the same as from validating the whole
+//! document.
+use crate::{
+    error::ReaderError,
+    keywords::{BoxedValidator, BuiltinKeyword, Keyword},
+    node::SchemaNode,
+    paths::LazyLocation,
+    validator::Validate,
+    ValidationError,
+};
+use serde::de::{self, Deserializer as _, MapAccess, SeqAccess, Visitor};
+use serde_json::{Map, Value};
+use std::{fmt, io::Read};
+
+/// Subschemas a validator applies, as far as streaming validation is concerned.
+pub(crate) enum StreamedSubschemas<'a> {
+    /// Subschemas applied to the instance itself, like `$ref` or `allOf`.
+    InPlace(Vec<&'a SchemaNode>),
+    /// Subschemas applied to array items: `prefix[idx]` to the item at `idx`, and `rest` to all
+    /// items from `skip` on.
+    Items {
+        prefix: &'a [SchemaNode],
+        skip: usize,
+        rest: Option<&'a SchemaNode>,
+    },
+    /// Subschemas applied to object property values, each of them on its own. Errors are reported
+    /// at the property values, never at the object.
+    Properties,
+}
+
+pub(crate) fn validate<R: Read>(node: &SchemaNode, reader: R) -> Result<(), ReaderError> {
+    let mut error = None;
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    let result = deserializer.deserialize_any(RootVisitor {
+        node,
+        error: &mut error,
+    });
+    if let Some(error) = error {
+        return Err(ReaderError::Validation(error));
+    }
+    result?;
+    deserializer.end()?;
+    Ok(())
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Container {
+    Array,
+    Object,
+}
+
+/// How the keywords of a schema apply to the elements of a container.
+#[derive(Default)]
+pub(crate) struct Plan<'a> {
+    types: Vec<&'a BoxedValidator>,
+    properties: Vec<&'a BoxedValidator>,
+    items: Vec<(&'a [SchemaNode], usize, Option<&'a SchemaNode>)>,
+}
+
+impl<'a> Plan<'a> {
+    /// Build a plan, or return `None` if `node` needs the whole container, or does not accept
+    /// this type of container at all.
+    pub(crate) fn new(node: &'a SchemaNode, container: Container) -> Option<Plan<'a>> {
+        let mut plan = Plan::default();
+        let empty = match container {
+            Container::Array => Value::Array(Vec::new()),
+            Container::Object => Value::Object(Map::new()),
+        };
+        if plan.collect(node, container) && plan.accepts_type(&empty) {
+            Some(plan)
+        } else {
+            None
+        }
+    }
+
+    fn collect(&mut self, node: &'a SchemaNode, container: Container) -> bool {
+        for (keyword, validator) in node.keyword_validators() {
+            if let Some(Keyword::Buildin(keyword)) = keyword {
+                if matches!(keyword, BuiltinKeyword::Type) {
+                    self.types.push(validator);
+                    continue;
+                }
+                if ignores(keyword, container) {
+                    continue;
+                }
+            }
+            match validator.streamed_subschemas() {
+                Some(StreamedSubschemas::InPlace(nodes)) => {
+                    for node in nodes {
+                        if !self.collect(node, container) {
+                            return false;
+                        }
+                    }
+                }
+                Some(StreamedSubschemas::Items { prefix, skip, rest }) => {
+                    self.items.push((prefix, skip, rest));
+                }
+                Some(StreamedSubschemas::Properties) => self.properties.push(validator),
+                None => return false,
+            }
+        }
+        true
+    }
+
+    fn accepts_type(&self, empty: &Value) -> bool {
+        self.types.iter().all(|validator| validator.is_valid(empty))
+    }
+
+    /// Validate the array item at `idx`.
+    pub(crate) fn validate_item<'i>(
+        &self,
+        idx: usize,
+        item: &'i Value,
+    ) -> Result<(), ValidationError<'i>> {
+        let root = LazyLocation::new();
+        let location = root.push(idx);
+        for (prefix, skip, rest) in &self.items {
+            if let Some(node) = prefix.get(idx).or(rest.filter(|_| idx >= *skip)) {
+                node.validate(item, &location)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Check an object property, passed as an object with just this property, and keep it in
+    /// `invalid` if the plan rejects it. A valid property replaces an earlier invalid one with
+    /// the same name, as it would in the whole object.
+    pub(crate) fn check_property(&self, property: Value, invalid: &mut Map<String, Value>) {
+        let is_valid = self
+            .properties
+            .iter()
+            .all(|validator| validator.is_valid(&property));
+        if let Value::Object(property) = property {
+            for (name, value) in property {
+                if is_valid {
+                    invalid.remove(&name);
+                } else {
+                    invalid.insert(name, value);
+                }
+            }
+        }
+    }
+
+    /// Validate the properties kept by [`Plan::check_property`].
+    ///
+    /// The keywords in the plan only report errors at property values, and valid properties
+    /// report none, hence this gives the same first error as the whole object.
+    pub(crate) fn validate_properties<'i>(
+        &self,
+        invalid: &'i Value,
+    ) -> Result<(), ValidationError<'i>> {
+        for validator in &self.properties {
+            validator.validate(invalid, &LazyLocation::new())?;
+        }
+        Ok(())
+    }
+}
+
+/// Keywords which do nothing for the given container type.
+fn ignores(keyword: &BuiltinKeyword, container: Container) -> bool {
+    match keyword {
+        BuiltinKeyword::Maximum
+        | BuiltinKeyword::Minimum
+        | BuiltinKeyword::ExclusiveMaximum
+        | BuiltinKeyword::ExclusiveMinimum
+        | BuiltinKeyword::MultipleOf
+        | BuiltinKeyword::MaxLength
+        | BuiltinKeyword::MinLength
+        | BuiltinKeyword::Pattern
+        | BuiltinKeyword::Format
+        | BuiltinKeyword::ContentMediaType
+        | BuiltinKeyword::ContentEncoding => true,
+        BuiltinKeyword::Properties
+        | BuiltinKeyword::PatternProperties
+        | BuiltinKeyword::AdditionalProperties
+        | BuiltinKeyword::PropertyNames
+        | BuiltinKeyword::Required
+        | BuiltinKeyword::MaxProperties
+        | BuiltinKeyword::MinProperties
+        | BuiltinKeyword::Dependencies
+        | BuiltinKeyword::DependentRequired
+        | BuiltinKeyword::DependentSchemas
+        | BuiltinKeyword::UnevaluatedProperties => container == Container::Array,
+        BuiltinKeyword::Items
+        | BuiltinKeyword::PrefixItems
+        | BuiltinKeyword::AdditionalItems
+        | BuiltinKeyword::Contains
+        | BuiltinKeyword::MaxItems
+        | BuiltinKeyword::MinItems
+        | BuiltinKeyword::UniqueItems
+        | BuiltinKeyword::UnevaluatedItems => container == Container::Object,
+        _ => false,
+    }
+}
+
+struct RootVisitor<'a> {
+    node: &'a SchemaNode,
+    error: &'a mut Option<ValidationError<'static>>,
+}
+
+impl RootVisitor<'_> {
+    fn check<E: de::Error>(self, result: Result<(), ValidationError<'_>>) -> Result<(), E> {
+        result.map_err(|error| {
+            *self.error = Some(error.to_owned());
+            E::custom("instance is not valid")
+        })
+    }
+
+    fn scalar<E: de::Error>(self, instance: &Value) -> Result<(), E> {
+        let result = self.node.validate(instance, &LazyLocation::new());
+        self.check(result)
+    }
+}
+
+impl<'de> Visitor<'de> for RootVisitor<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a JSON value")
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<(), E> {
+        self.scalar(&Value::Bool(value))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<(), E> {
+        self.scalar(&Value::from(value))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<(), E> {
+        self.scalar(&Value::from(value))
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<(), E> {
+        self.scalar(&Value::from(value))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<(), E> {
+        self.scalar(&Value::from(value))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<(), E> {
+        self.scalar(&Value::Null)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let Some(plan) = Plan::new(self.node, Container::Array) else {
+            let mut items = Vec::new();
+            while let Some(item) = seq.next_element()? {
+                items.push(item);
+            }
+            return self.scalar(&Value::Array(items));
+        };
+        let mut idx = 0;
+        while let Some(item) = seq.next_element::<Value>()? {
+            if let Err(error) = plan.validate_item(idx, &item) {
+                return self.check(Err(error));
+            }
+            idx += 1;
+        }
+        Ok(())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        let Some(plan) = Plan::new(self.node, Container::Object) else {
+            let mut object = Map::new();
+            while let Some((key, value)) = map.next_entry()? {
+                object.insert(key, value);
+            }
+            return self.scalar(&Value::Object(object));
+        };
+        let mut invalid = Map::new();
+        while let Some((key, value)) = map.next_entry::<String, Value>()? {
+            let mut property = Map::with_capacity(1);
+            property.insert(key, value);
+            plan.check_property(Value::Object(property), &mut invalid);
+        }
+        let invalid = Value::Object(invalid);
+        let result = plan.validate_properties(&invalid);
+        self.check(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ReaderError;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    fn validate(schema: &Value, document: &str) -> Result<(), ReaderError> {
+        crate::validator_for(schema)
+            .expect("Invalid schema")
+            .validate_reader(document.as_bytes())
+    }
+
+    #[test_case(&json!({"type": "array", "items": {"type": "integer"}}), "[1, 2, 3]"; "streamed items")]
+    #[test_case(&json!({"additionalProperties": {"type": "integer"}}), r#"{"a": 1, "b": 2}"#; "streamed properties")]
+    #[test_case(&json!({"uniqueItems": true}), "[1, 2, 3]"; "buffered array")]
+    #[test_case(&json!({"required": ["a"]}), r#"{"a": 1}"#; "buffered object")]
+    #[test_case(&json!({"$ref": "#/$defs/ints", "$defs": {"ints": {"items": {"type": "integer"}}}}), "[1, 2]"; "reference")]
+    #[test_case(&json!({"type": "string"}), r#""foo""#; "scalar")]
+    fn valid(schema: &Value, document: &str) {
+        validate(schema, document).expect("Should be valid");
+    }
+
+    #[test_case(&json!({"items": {"type": "integer"}}), r#"[1, "2", 3]"#, "/1"; "streamed items")]
+    #[test_case(&json!({"prefixItems": [{"type": "integer"}], "items": {"type": "string"}}), r#"[1, "2", 3]"#, "/2"; "prefix items")]
+    #[test_case(&json!({"allOf": [{"items": {"maximum": 2}}]}), "[1, 2, 3]", "/2"; "all of")]
+    #[test_case(&json!({"properties": {"a": {"type": "integer"}}}), r#"{"b": 1, "a": "x"}"#, "/a"; "streamed properties")]
+    #[test_case(&json!({"items": {"type": "integer"}, "uniqueItems": true}), "[1, 1]", ""; "buffered array")]
+    #[test_case(&json!({"type": "object"}), "[1]", ""; "wrong container type")]
+    #[test_case(&json!({"type": "string"}), "42", ""; "scalar")]
+    fn invalid(schema: &Value, document: &str, expected: &str) {
+        match validate(schema, document) {
+            Err(ReaderError::Validation(error)) => {
+                assert_eq!(error.instance_path.as_str(), expected);
+            }
+            other => panic!("Unexpected result: {other:?}"),
+        }
+    }
+
+    #[test_case(&json!({"properties": {"a": {}}, "additionalProperties": false}), r#"{"a": 1, "b": 2, "c": 3}"#; "additional properties false")]
+    #[test_case(&json!({"propertyNames": {"maxLength": 1}}), r#"{"a": 1, "bb": 2}"#; "property names")]
+    #[test_case(&json!({"properties": {"a": {"type": "integer"}, "b": {"type": "integer"}}}), r#"{"b": "x", "a": "y"}"#; "properties order")]
+    #[test_case(&json!({"additionalProperties": {"type": "integer"}}), r#"{"b": "x", "a": "y"}"#; "additional properties order")]
+    #[test_case(&json!({"patternProperties": {"^a": {"type": "integer"}}}), r#"{"a": "x", "a": 1}"#; "duplicate names")]
+    fn same_error_as_validate(schema: &Value, document: &str) {
+        let validator = crate::validator_for(schema).expect("Invalid schema");
+        let instance: Value = serde_json::from_str(document).expect("Invalid JSON");
+        let expected = validator.validate(&instance).err();
+        let error = match validator.validate_reader(document.as_bytes()) {
+            Ok(()) => None,
+            Err(ReaderError::Validation(error)) => Some(error),
+            Err(error) => panic!("Unexpected error: {error}"),
+        };
+        let describe = |error: &crate::ValidationError<'_>| {
+            (
+                error.to_string(),
+                error.instance.clone().into_owned(),
+                error.instance_path.clone(),
+                error.schema_path.clone(),
+            )
+        };
+        assert_eq!(
+            error.as_ref().map(describe),
+            expected.as_ref().map(describe)
+        );
+    }
+
+    #[test_case("[1, 2"; "truncated")]
+    #[test_case("[1] 2"; "trailing data")]
+    #[test_case(""; "empty")]
+    fn invalid_json(document: &str) {
+        let result = validate(&json!({"items": {"type": "integer"}}), document);
+        assert!(matches!(result, Err(ReaderError::Json(_))));
+    }
+}
@@ -1,8 +1,25 @@
 //! Logic for retrieving external resources.
-use referencing::{Retrieve, Uri};
+#[cfg(feature = "resolve-async")]
+use referencing::AsyncRetrieve;
+use referencing::{RetrievalPolicy, Retrieve, Uri};
 use serde_json::Value;
+#[cfg(feature = "resolve-async")]
+use std::sync::Arc;
 
-pub(crate) struct DefaultRetriever;
+pub(crate) struct DefaultRetriever {
+    /// Checked for each redirect, as the registry only checks the URIs it retrieves.
+    #[cfg_attr(
+        any(target_arch = "wasm32", not(any(feature = "resolve-http", test))),
+        allow(dead_code)
+    )]
+    policy: RetrievalPolicy,
+}
+
+impl DefaultRetriever {
+    pub(crate) fn new(policy: RetrievalPolicy) -> Self {
+        DefaultRetriever { policy }
+    }
+}
 
 impl Retrieve for DefaultRetriever {
     #[allow(unused)]
@@ -16,7 +33,14 @@ impl Retrieve for DefaultRetriever {
             "http" | "https" => {
                 #[cfg(any(feature = "resolve-http", test))]
                 {
-                    Ok(reqwest::blocking::get(uri.as_str())?.json()?)
+                    let client = reqwest::blocking::Client::builder()
+                        .redirect(redirect_policy(self.policy.clone()))
+                        .build()?;
+                    Ok(client
+                        .get(uri.as_str())
+                        .send()
+                        .map_err(denied_redirect)?
+                        .json()?)
                 }
                 #[cfg(not(any(feature = "resolve-http", test)))]
                 Err("`resolve-http` feature or a custom resolver is required to resolve external schemas via HTTP".into())
@@ -24,20 +48,7 @@ impl Retrieve for DefaultRetriever {
             "file" => {
                 #[cfg(any(feature = "resolve-file", test))]
                 {
-                    let path = uri.path().as_str();
-                    let path = {
-                        #[cfg(windows)]
-                        {
-                            // Remove the leading slash and replace forward slashes with backslashes
-                            let path = path.trim_start_matches('/').replace('/', "\\");
-                            std::path::PathBuf::from(path)
-                        }
-                        #[cfg(not(windows))]
-                        {
-                            std::path::PathBuf::from(path)
-                        }
-                    };
-                    let file = std::fs::File::open(path)?;
+                    let file = std::fs::File::open(path_from_uri(uri))?;
                     Ok(serde_json::from_reader(file)?)
                 }
                 #[cfg(not(any(feature = "resolve-file", test)))]
@@ -50,6 +61,138 @@ impl Retrieve for DefaultRetriever {
     }
 }
 
+#[cfg(feature = "resolve-async")]
+#[async_trait::async_trait]
+impl AsyncRetrieve for DefaultRetriever {
+    #[allow(unused)]
+    async fn retrieve(
+        &self,
+        uri: &Uri<&str>,
+    ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+        #[cfg(target_arch = "wasm32")]
+        {
+            Err("External references are not supported in WASM".into())
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        match uri.scheme().as_str() {
+            "http" | "https" => {
+                #[cfg(any(feature = "resolve-http", test))]
+                {
+                    let client = reqwest::Client::builder()
+                        .redirect(redirect_policy(self.policy.clone()))
+                        .build()?;
+                    Ok(client
+                        .get(uri.as_str())
+                        .send()
+                        .await
+                        .map_err(denied_redirect)?
+                        .json()
+                        .await?)
+                }
+                #[cfg(not(any(feature = "resolve-http", test)))]
+                Err("`resolve-http` feature or a custom resolver is required to resolve external schemas via HTTP".into())
+            }
+            "file" => {
+                #[cfg(any(feature = "resolve-file", test))]
+                {
+                    let contents = tokio::fs::read(path_from_uri(uri)).await?;
+                    Ok(serde_json::from_slice(&contents)?)
+                }
+                #[cfg(not(any(feature = "resolve-file", test)))]
+                {
+                    Err("`resolve-file` feature or a custom resolver is required to resolve external schemas via files".into())
+                }
+            }
+            scheme => Err(format!("Unknown scheme {scheme}").into()),
+        }
+    }
+}
+
+/// Runs a synchronous retriever without blocking the async runtime.
+#[cfg(feature = "resolve-async")]
+pub(crate) struct BlockingRetriever {
+    inner: Arc<dyn Retrieve>,
+}
+
+#[cfg(feature = "resolve-async")]
+impl BlockingRetriever {
+    pub(crate) fn new(inner: Arc<dyn Retrieve>) -> Self {
+        BlockingRetriever { inner }
+    }
+}
+
+#[cfg(feature = "resolve-async")]
+#[async_trait::async_trait]
+impl AsyncRetrieve for BlockingRetriever {
+    async fn retrieve(
+        &self,
+        uri: &Uri<&str>,
+    ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+        #[cfg(target_arch = "wasm32")]
+        {
+            self.inner.retrieve(uri)
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let inner = Arc::clone(&self.inner);
+            let uri = referencing::uri::from_str(uri.as_str())?;
+            tokio::task::spawn_blocking(move || inner.retrieve(&uri.borrow())).await?
+        }
+    }
+}
+
+/// Follow at most 10 redirects, like `reqwest` does by default, to targets allowed by `policy`.
+#[cfg(all(not(target_arch = "wasm32"), any(feature = "resolve-http", test)))]
+fn redirect_policy(policy: RetrievalPolicy) -> reqwest::redirect::Policy {
+    reqwest::redirect::Policy::custom(move |attempt| {
+        if attempt.previous().len() > 10 {
+            return attempt.error("too many redirects");
+        }
+        let target = attempt.url().as_str();
+        let checked = referencing::uri::from_str(target).and_then(|uri| {
+            policy
+                .check(&uri.borrow())
+                .map_err(|violation| referencing::Error::PolicyViolation {
+                    uri: target.to_string(),
+                    violation,
+                })
+        });
+        match checked {
+            Ok(()) => attempt.follow(),
+            Err(error) => attempt.error(error),
+        }
+    })
+}
+
+/// Report redirects denied by the retrieval policy as policy violations.
+#[cfg(all(not(target_arch = "wasm32"), any(feature = "resolve-http", test)))]
+fn denied_redirect(error: reqwest::Error) -> Box<dyn std::error::Error + Send + Sync> {
+    if let Some(referencing::Error::PolicyViolation { uri, violation }) =
+        std::error::Error::source(&error).and_then(|source| source.downcast_ref())
+    {
+        return Box::new(referencing::Error::PolicyViolation {
+            uri: uri.clone(),
+            violation: violation.clone(),
+        });
+    }
+    error.into()
+}
+
+#[cfg(all(not(target_arch = "wasm32"), any(feature = "resolve-file", test)))]
+fn path_from_uri(uri: &Uri<&str>) -> std::path::PathBuf {
+    let path = uri.path().as_str();
+    #[cfg(windows)]
+    {
+        // Remove the leading slash and replace forward slashes with backslashes
+        let path = path.trim_start_matches('/').replace('/', "\\");
+        std::path::PathBuf::from(path)
+    }
+    #[cfg(not(windows))]
+    {
+        std::path::PathBuf::from(path)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use serde_json::json;
@@ -149,6 +292,85 @@ mod tests {
         assert!(!validator.is_valid(&invalid));
     }
 
+    #[tokio::test]
+    #[cfg(all(not(target_arch = "wasm32"), feature = "resolve-async"))]
+    async fn test_retrieve_from_file_async() {
+        let mut temp_file = tempfile::NamedTempFile::new().expect("Failed to create temp file");
+        let external_schema = json!({"type": "string"});
+        write!(temp_file, "{}", external_schema).expect("Failed to write to temp file");
+
+        let uri = path_to_uri(temp_file.path());
+
+        let schema = json!({"$ref": uri});
+
+        let validator = crate::options()
+            .build_async(&schema)
+            .await
+            .expect("Schema compilation failed");
+
+        assert!(validator.is_valid(&json!("John Doe")));
+        assert!(!validator.is_valid(&json!(42)));
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn test_redirect_denied_by_policy() {
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", "/schema.json")
+            .with_status(302)
+            .with_header("location", "/internal/secret.json")
+            .create();
+        let secret = server
+            .mock("GET", "/internal/secret.json")
+            .with_body(r#"{"type": "string"}"#)
+            .expect(0)
+            .create();
+        let url = server.url();
+        let schema = json!({"$ref": format!("{url}/schema.json")});
+
+        let error = crate::options()
+            .with_retrieval_policy(referencing::RetrievalPolicy::new().deny_path("/internal/"))
+            .build(&schema)
+            .expect_err("Redirect should be denied");
+
+        assert_eq!(
+            error.to_string(),
+            format!("Resource '{url}/schema.json' is not present in a registry and retrieving it failed: Retrieving '{url}/internal/secret.json' is not allowed by the retrieval policy: path '/internal/secret.json' is not allowed")
+        );
+        secret.assert();
+    }
+
+    #[tokio::test]
+    #[cfg(all(not(target_arch = "wasm32"), feature = "resolve-async"))]
+    async fn test_redirect_denied_by_policy_async() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/schema.json")
+            .with_status(302)
+            .with_header("location", "/internal/secret.json")
+            .create_async()
+            .await;
+        let secret = server
+            .mock("GET", "/internal/secret.json")
+            .with_body(r#"{"type": "string"}"#)
+            .expect(0)
+            .create_async()
+            .await;
+        let schema = json!({"$ref": format!("{}/schema.json", server.url())});
+
+        let error = crate::options()
+            .with_retrieval_policy(referencing::RetrievalPolicy::new().deny_path("/internal/"))
+            .build_async(&schema)
+            .await
+            .expect_err("Redirect should be denied");
+
+        assert!(error
+            .to_string()
+            .ends_with("path '/internal/secret.json' is not allowed"));
+        secret.assert_async().await;
+    }
+
     #[test]
     fn test_unknown_scheme() {
         let schema = json!({
@@ -0,0 +1,555 @@
+//! Validation of [`Serialize`] values without converting them into a single [`Value`] first.
+//!
+//! The value is serialized into a [`Serializer`] that validates its top-level array or object
+//! element by element, so only the current element is converted into a `Value`. This works for
+//! the same keywords as validation of documents read from [`std::io::Read`] does. Any other
+//! keyword needs the whole container, so it is converted and validated as usual. Enum variants
+//! with fields are always converted as a whole.
+use crate::{
+    error::SerializeError,
+    node::SchemaNode,
+    paths::LazyLocation,
+    reader::{Container, Plan},
+    validator::Validate,
+    ValidationError,
+};
+use serde::{
+    ser::{self, Error as _},
+    Serialize, Serializer,
+};
+use serde_json::{value::Serializer as ValueSerializer, Map, Value};
+
+type ValueSerializeMap = <ValueSerializer as Serializer>::SerializeMap;
+type ValueSerializeTupleVariant = <ValueSerializer as Serializer>::SerializeTupleVariant;
+type ValueSerializeStructVariant = <ValueSerializer as Serializer>::SerializeStructVariant;
+
+pub(crate) fn validate<T>(node: &SchemaNode, instance: &T) -> Result<(), SerializeError>
+where
+    T: Serialize + ?Sized,
+{
+    let mut error = None;
+    let result = instance.serialize(RootSerializer {
+        node,
+        error: &mut error,
+    });
+    if let Some(error) = error {
+        return Err(SerializeError::Validation(error));
+    }
+    result.map_err(SerializeError::Serialize)
+}
+
+struct RootSerializer<'a> {
+    node: &'a SchemaNode,
+    error: &'a mut Option<ValidationError<'static>>,
+}
+
+impl RootSerializer<'_> {
+    fn check(self, result: Result<(), ValidationError<'_>>) -> Result<(), serde_json::Error> {
+        result.map_err(|error| {
+            *self.error = Some(error.to_owned());
+            serde_json::Error::custom("instance is not valid")
+        })
+    }
+
+    fn scalar(self, instance: &Value) -> Result<(), serde_json::Error> {
+        let result = self.node.validate(instance, &LazyLocation::new());
+        self.check(result)
+    }
+}
+
+impl<'a> Serializer for RootSerializer<'a> {
+    type Ok = ();
+    type Error = serde_json::Error;
+    type SerializeSeq = SeqSerializer<'a>;
+    type SerializeTuple = SeqSerializer<'a>;
+    type SerializeTupleStruct = SeqSerializer<'a>;
+    type SerializeTupleVariant = VariantSerializer<'a, ValueSerializeTupleVariant>;
+    type SerializeMap = MapSerializer<'a>;
+    type SerializeStruct = MapSerializer<'a>;
+    type SerializeStructVariant = VariantSerializer<'a, ValueSerializeStructVariant>;
+
+    fn serialize_bool(self, value: bool) -> Result<(), Self::Error> {
+        self.scalar(&ValueSerializer.serialize_bool(value)?)
+    }
+
+    fn serialize_i8(self, value: i8) -> Result<(), Self::Error> {
+        self.scalar(&ValueSerializer.serialize_i8(value)?)
+    }
+
+    fn serialize_i16(self, value: i16) -> Result<(), Self::Error> {
+        self.scalar(&ValueSerializer.serialize_i16(value)?)
+    }
+
+    fn serialize_i32(self, value: i32) -> Result<(), Self::Error> {
+        self.scalar(&ValueSerializer.serialize_i32(value)?)
+    }
+
+    fn serialize_i64(self, value: i64) -> Result<(), Self::Error> {
+        self.scalar(&ValueSerializer.serialize_i64(value)?)
+    }
+
+    fn serialize_i128(self, value: i128) -> Result<(), Self::Error> {
+        self.scalar(&ValueSerializer.serialize_i128(value)?)
+    }
+
+    fn serialize_u8(self, value: u8) -> Result<(), Self::Error> {
+        self.scalar(&ValueSerializer.serialize_u8(value)?)
+    }
+
+    fn serialize_u16(self, value: u16) -> Result<(), Self::Error> {
+        self.scalar(&ValueSerializer.serialize_u16(value)?)
+    }
+
+    fn serialize_u32(self, value: u32) -> Result<(), Self::Error> {
+        self.scalar(&ValueSerializer.serialize_u32(value)?)
+    }
+
+    fn serialize_u64(self, value: u64) -> Result<(), Self::Error> {
+        self.scalar(&ValueSerializer.serialize_u64(value)?)
+    }
+
+    fn serialize_u128(self, value: u128) -> Result<(), Self::Error> {
+        self.scalar(&ValueSerializer.serialize_u128(value)?)
+    }
+
+    fn serialize_f32(self, value: f32) -> Result<(), Self::Error> {
+        self.scalar(&ValueSerializer.serialize_f32(value)?)
+    }
+
+    fn serialize_f64(self, value: f64) -> Result<(), Self::Error> {
+        self.scalar(&ValueSerializer.serialize_f64(value)?)
+    }
+
+    fn serialize_char(self, value: char) -> Result<(), Self::Error> {
+        self.scalar(&ValueSerializer.serialize_char(value)?)
+    }
+
+    fn serialize_str(self, value: &str) -> Result<(), Self::Error> {
+        self.scalar(&ValueSerializer.serialize_str(value)?)
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<(), Self::Error> {
+        self.scalar(&ValueSerializer.serialize_bytes(value)?)
+    }
+
+    fn serialize_none(self) -> Result<(), Self::Error> {
+        self.scalar(&Value::Null)
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Self::Error> {
+        self.scalar(&Value::Null)
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<(), Self::Error> {
+        self.scalar(&Value::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> Result<(), Self::Error> {
+        self.scalar(&Value::from(variant))
+    }
+
+    fn serialize_newtype_struct<T>(self, _: &'static str, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        // An object with the variant name as its only property
+        let mut map = self.serialize_map(Some(1))?;
+        ser::SerializeMap::serialize_entry(&mut map, variant, value)?;
+        ser::SerializeMap::end(map)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        let plan = Plan::new(self.node, Container::Array);
+        let items = if plan.is_some() {
+            Vec::new()
+        } else {
+            Vec::with_capacity(len.unwrap_or(0))
+        };
+        Ok(SeqSerializer {
+            root: self,
+            plan,
+            items,
+            idx: 0,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Ok(VariantSerializer {
+            root: self,
+            inner: ValueSerializer.serialize_tuple_variant(name, variant_index, variant, len)?,
+        })
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        let plan = Plan::new(self.node, Container::Object);
+        let entries = ValueSerializer.serialize_map(None)?;
+        Ok(MapSerializer {
+            root: self,
+            plan,
+            entries,
+            invalid: Map::new(),
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        _: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Ok(VariantSerializer {
+            root: self,
+            inner: ValueSerializer.serialize_struct_variant(name, variant_index, variant, len)?,
+        })
+    }
+}
+
+/// Validates each item as it is serialized, or collects them if the schema needs the whole array.
+struct SeqSerializer<'a> {
+    root: RootSerializer<'a>,
+    plan: Option<Plan<'a>>,
+    items: Vec<Value>,
+    idx: usize,
+}
+
+impl ser::SerializeSeq for SeqSerializer<'_> {
+    type Ok = ();
+    type Error = serde_json::Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let item = serde_json::to_value(value)?;
+        if let Some(plan) = &self.plan {
+            if let Err(error) = plan.validate_item(self.idx, &item) {
+                *self.root.error = Some(error.to_owned());
+                return Err(serde_json::Error::custom("instance is not valid"));
+            }
+        } else {
+            self.items.push(item);
+        }
+        self.idx += 1;
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        if self.plan.is_some() {
+            Ok(())
+        } else {
+            self.root.scalar(&Value::Array(self.items))
+        }
+    }
+}
+
+impl ser::SerializeTuple for SeqSerializer<'_> {
+    type Ok = ();
+    type Error = serde_json::Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for SeqSerializer<'_> {
+    type Ok = ();
+    type Error = serde_json::Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+/// Validates each property as it is serialized, or collects them if the schema needs the whole
+/// object.
+struct MapSerializer<'a> {
+    root: RootSerializer<'a>,
+    plan: Option<Plan<'a>>,
+    // The current property if there is a plan, all properties otherwise. Keys are converted the
+    // same way as `serde_json::to_value` does
+    entries: ValueSerializeMap,
+    // Invalid properties seen so far if there is a plan
+    invalid: Map<String, Value>,
+}
+
+impl ser::SerializeMap for MapSerializer<'_> {
+    type Ok = ();
+    type Error = serde_json::Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.entries.serialize_key(key)
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.entries.serialize_value(value)?;
+        if let Some(plan) = &self.plan {
+            let entries =
+                std::mem::replace(&mut self.entries, ValueSerializer.serialize_map(None)?);
+            let property = ser::SerializeMap::end(entries)?;
+            plan.check_property(property, &mut self.invalid);
+        }
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        if let Some(plan) = &self.plan {
+            let invalid = Value::Object(self.invalid);
+            let result = plan.validate_properties(&invalid);
+            self.root.check(result)
+        } else {
+            let object = ser::SerializeMap::end(self.entries)?;
+            self.root.scalar(&object)
+        }
+    }
+}
+
+impl ser::SerializeStruct for MapSerializer<'_> {
+    type Ok = ();
+    type Error = serde_json::Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        ser::SerializeMap::serialize_entry(self, key, value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        ser::SerializeMap::end(self)
+    }
+}
+
+/// Converts enum variants with fields into a `Value` and validates it as a whole.
+struct VariantSerializer<'a, S> {
+    root: RootSerializer<'a>,
+    inner: S,
+}
+
+impl<S> ser::SerializeTupleVariant for VariantSerializer<'_, S>
+where
+    S: ser::SerializeTupleVariant<Ok = Value, Error = serde_json::Error>,
+{
+    type Ok = ();
+    type Error = serde_json::Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.inner.serialize_field(value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        let instance = self.inner.end()?;
+        self.root.scalar(&instance)
+    }
+}
+
+impl<S> ser::SerializeStructVariant for VariantSerializer<'_, S>
+where
+    S: ser::SerializeStructVariant<Ok = Value, Error = serde_json::Error>,
+{
+    type Ok = ();
+    type Error = serde_json::Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.inner.serialize_field(key, value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        let instance = self.inner.end()?;
+        self.root.scalar(&instance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SerializeError;
+    use serde::Serialize;
+    use serde_json::{json, Value};
+    use std::collections::{BTreeMap, HashMap};
+    use test_case::test_case;
+
+    #[derive(Serialize)]
+    struct Item {
+        name: &'static str,
+        tags: Vec<&'static str>,
+    }
+
+    #[derive(Serialize)]
+    struct Pair {
+        b: u32,
+        a: u32,
+    }
+
+    #[derive(Serialize)]
+    enum Shape {
+        Circle(u32),
+        Rectangle { width: u32, height: u32 },
+    }
+
+    fn validate<T: Serialize>(schema: &Value, instance: &T) -> Result<(), SerializeError> {
+        crate::validator_for(schema)
+            .expect("Invalid schema")
+            .validate_serialize(instance)
+    }
+
+    #[test_case(&json!({"items": {"type": "integer"}}), &json!([1, "2", 3]), "/1"; "streamed items")]
+    #[test_case(&json!({"prefixItems": [{"type": "integer"}], "items": {"type": "string"}}), &json!([1, "2", 3]), "/2"; "prefix items")]
+    #[test_case(&json!({"properties": {"a": {"type": "integer"}}}), &json!({"b": 1, "a": "x"}), "/a"; "streamed properties")]
+    #[test_case(&json!({"items": {"type": "integer"}, "uniqueItems": true}), &json!([1, 1]), ""; "buffered array")]
+    #[test_case(&json!({"required": ["a"]}), &json!({"b": 1}), ""; "buffered object")]
+    #[test_case(&json!({"properties": {"a": {}}, "additionalProperties": false}), &json!({"a": 1, "b": 2}), ""; "additional properties false")]
+    #[test_case(&json!({"type": "object"}), &json!([1]), ""; "wrong container type")]
+    #[test_case(&json!({"type": "string"}), &json!(42), ""; "scalar")]
+    fn same_as_value(schema: &Value, instance: &Value, expected: &str) {
+        let validator = crate::validator_for(schema).expect("Invalid schema");
+        let expected_error = validator.validate(instance).expect_err("Should fail");
+        match validator.validate_serialize(instance) {
+            Err(SerializeError::Validation(error)) => {
+                assert_eq!(error.instance_path.as_str(), expected);
+                assert_eq!(error.instance_path, expected_error.instance_path);
+                assert_eq!(error.schema_path, expected_error.schema_path);
+                assert_eq!(*error.instance, *expected_error.instance);
+            }
+            other => panic!("Unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn first_error_as_value() {
+        // Fields are serialized as `b`, `a`, but `validate` reports them in map order
+        let validator = crate::validator_for(&json!({"additionalProperties": {"maximum": 0}}))
+            .expect("Invalid schema");
+        let pair = Pair { b: 1, a: 1 };
+        let instance = serde_json::to_value(&pair).expect("Should serialize");
+        let expected = validator.validate(&instance).expect_err("Should fail");
+        let Err(SerializeError::Validation(error)) = validator.validate_serialize(&pair) else {
+            panic!("Expected a validation error");
+        };
+        assert_eq!(error.instance_path, expected.instance_path);
+    }
+
+    #[test]
+    fn structs() {
+        let schema = json!({
+            "properties": {
+                "name": {"minLength": 1},
+                "tags": {"items": {"maxLength": 3}}
+            },
+            "additionalProperties": false
+        });
+        let valid = Item {
+            name: "a",
+            tags: vec!["b"],
+        };
+        validate(&schema, &valid).expect("Should be valid");
+        let invalid = Item {
+            name: "a",
+            tags: vec!["b", "long"],
+        };
+        let Err(SerializeError::Validation(error)) = validate(&schema, &invalid) else {
+            panic!("Expected a validation error");
+        };
+        assert_eq!(error.instance_path.as_str(), "/tags/1");
+        assert_eq!(*error.instance, json!("long"));
+    }
+
+    #[test_case(&Shape::Circle(5), "/Circle"; "newtype variant")]
+    #[test_case(&Shape::Rectangle { width: 5, height: 1 }, "/Rectangle/width"; "struct variant")]
+    fn enums(shape: &Shape, expected: &str) {
+        let schema = json!({
+            "properties": {
+                "Circle": {"maximum": 3},
+                "Rectangle": {"properties": {"width": {"maximum": 3}}}
+            }
+        });
+        let Err(SerializeError::Validation(error)) = validate(&schema, shape) else {
+            panic!("Expected a validation error");
+        };
+        assert_eq!(error.instance_path.as_str(), expected);
+    }
+
+    #[test]
+    fn non_string_keys() {
+        let schema = json!({"propertyNames": {"pattern": "^[0-9]+$"}});
+        validate(&schema, &BTreeMap::from([(1, 1)])).expect("Should be valid");
+        let result = validate(&schema, &HashMap::from([(vec![1], 1)]));
+        assert!(matches!(result, Err(SerializeError::Serialize(_))));
+    }
+}
@@ -0,0 +1,357 @@
+//! Mapping of locations to spans in the JSON source text.
+//!
+//! Instance and schema locations are JSON pointers, which are hard to follow in large documents.
+//! [`SourceMap`] resolves them to byte ranges and line / column positions in the original text,
+//! so errors can be reported the way compilers report diagnostics:
+//!
+//! ```rust
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use jsonschema::spans::SourceMap;
+//!
+//! let schema = serde_json::json!({"properties": {"port": {"type": "integer"}}});
+//! let validator = jsonschema::validator_for(&schema)?;
+//!
+//! let text = "{\n  \"port\": \"80\"\n}";
+//! let instance = serde_json::from_str(text)?;
+//! let source = SourceMap::new(text)?;
+//!
+//! for error in validator.iter_errors(&instance) {
+//!     let span = source.span(&error.instance_path).expect("Error location exists");
+//!     assert_eq!(&text[span.range.clone()], "\"80\"");
+//!     assert_eq!((span.start.line, span.start.column), (2, 11));
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use crate::paths::Location;
+use std::ops::Range;
+
+/// A line and column in the source text, both starting from 1.
+///
+/// Columns are counted in characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Position {
+    /// Line number.
+    pub line: usize,
+    /// Column number.
+    pub column: usize,
+}
+
+/// The part of the source text that holds a JSON value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    /// Byte range of the value.
+    pub range: Range<usize>,
+    /// Position of the first character of the value.
+    pub start: Position,
+    /// Position right after the last character of the value.
+    pub end: Position,
+}
+
+/// JSON source text that resolves locations to [`Span`]s.
+#[derive(Debug)]
+pub struct SourceMap<'a> {
+    source: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> SourceMap<'a> {
+    /// Create a source map for a JSON document.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `source` is not valid JSON.
+    pub fn new(source: &'a str) -> Result<SourceMap<'a>, serde_json::Error> {
+        serde_json::from_str::<serde::de::IgnoredAny>(source)?;
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(idx, _)| idx + 1))
+            .collect();
+        Ok(SourceMap {
+            source,
+            line_starts,
+        })
+    }
+
+    /// The span of the value at `location`, if it exists.
+    ///
+    /// Works for instance paths and for schema paths that don't go through a reference.
+    #[must_use]
+    pub fn span(&self, location: &Location) -> Option<Span> {
+        let (range, unresolved) = self.resolve(location.as_str());
+        unresolved.is_none().then(|| self.span_of(range))
+    }
+
+    /// The span of the value at `location`, or of its deepest parent that exists.
+    ///
+    /// Schema paths continue inside the referenced schema after `$ref`, which this resolves to
+    /// the `$ref` keyword itself.
+    #[must_use]
+    pub fn closest_span(&self, location: &Location) -> Span {
+        let (range, _) = self.resolve(location.as_str());
+        self.span_of(range)
+    }
+
+    /// Position of the byte at `offset`.
+    #[must_use]
+    pub fn position(&self, offset: usize) -> Position {
+        let line = self.line_starts.partition_point(|start| *start <= offset);
+        let line_start = self.line_starts[line - 1];
+        let column = self.source[line_start..offset].chars().count() + 1;
+        Position { line, column }
+    }
+
+    fn span_of(&self, range: Range<usize>) -> Span {
+        Span {
+            start: self.position(range.start),
+            end: self.position(range.end),
+            range,
+        }
+    }
+
+    /// Find the value at `pointer`. Returns the range of the deepest value found and the
+    /// segment that could not be found in it, if any.
+    fn resolve<'p>(&self, pointer: &'p str) -> (Range<usize>, Option<&'p str>) {
+        let mut start = self.skip_whitespace(0);
+        for segment in pointer.split('/').skip(1) {
+            match self.child(start, segment) {
+                Some(child) => start = child,
+                None => return (start..self.skip_value(start), Some(segment)),
+            }
+        }
+        (start..self.skip_value(start), None)
+    }
+
+    /// Start of the property or item `segment` of the value at `start`.
+    fn child(&self, start: usize, segment: &str) -> Option<usize> {
+        let bytes = self.source.as_bytes();
+        match bytes[start] {
+            b'{' => {
+                let segment = segment.replace("~1", "/").replace("~0", "~");
+                let mut found = None;
+                let mut pos = self.skip_whitespace(start + 1);
+                while bytes[pos] == b'"' {
+                    let key_end = self.skip_string(pos);
+                    let key: String = serde_json::from_str(&self.source[pos..key_end])
+                        .expect("The source is valid JSON");
+                    // Skip the colon
+                    let value = self.skip_whitespace(self.skip_whitespace(key_end) + 1);
+                    // Like `serde_json`, the last one of duplicated keys wins
+                    if key == segment {
+                        found = Some(value);
+                    }
+                    pos = self.skip_separator(self.skip_value(value));
+                }
+                found
+            }
+            b'[' => {
+                // Array indices are written without a sign or leading zeros
+                if !segment.bytes().all(|byte| byte.is_ascii_digit())
+                    || (segment.len() > 1 && segment.starts_with('0'))
+                {
+                    return None;
+                }
+                let idx = segment.parse::<usize>().ok()?;
+                let mut pos = self.skip_whitespace(start + 1);
+                for _ in 0..idx {
+                    if bytes[pos] == b']' {
+                        return None;
+                    }
+                    pos = self.skip_separator(self.skip_value(pos));
+                }
+                (bytes[pos] != b']').then_some(pos)
+            }
+            _ => None,
+        }
+    }
+
+    fn skip_whitespace(&self, mut pos: usize) -> usize {
+        let bytes = self.source.as_bytes();
+        while pos < bytes.len() && matches!(bytes[pos], b' ' | b'\t' | b'\n' | b'\r') {
+            pos += 1;
+        }
+        pos
+    }
+
+    /// Skip whitespace and a comma between members or items.
+    fn skip_separator(&self, pos: usize) -> usize {
+        let pos = self.skip_whitespace(pos);
+        if self.source.as_bytes()[pos] == b',' {
+            self.skip_whitespace(pos + 1)
+        } else {
+            pos
+        }
+    }
+
+    /// End of the string that starts at `pos`.
+    fn skip_string(&self, mut pos: usize) -> usize {
+        let bytes = self.source.as_bytes();
+        pos += 1;
+        loop {
+            match bytes[pos] {
+                b'\\' => pos += 2,
+                b'"' => return pos + 1,
+                _ => pos += 1,
+            }
+        }
+    }
+
+    /// End of the value that starts at `pos`.
+    fn skip_value(&self, mut pos: usize) -> usize {
+        let bytes = self.source.as_bytes();
+        let mut depth = 0_usize;
+        loop {
+            match bytes[pos] {
+                b'"' => pos = self.skip_string(pos),
+                b'{' | b'[' => {
+                    depth += 1;
+                    pos += 1;
+                }
+                b'}' | b']' => {
+                    depth -= 1;
+                    pos += 1;
+                }
+                _ if depth > 0 => pos += 1,
+                _ => {
+                    // Numbers and literals
+                    while pos < bytes.len()
+                        && !matches!(
+                            bytes[pos],
+                            b',' | b'}' | b']' | b' ' | b'\t' | b'\n' | b'\r'
+                        )
+                    {
+                        pos += 1;
+                    }
+                    return pos;
+                }
+            }
+            if depth == 0 {
+                return pos;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Position, SourceMap};
+    use crate::paths::Location;
+    use test_case::test_case;
+
+    const SOURCE: &str = r#"{
+    "name": "widget",
+    "tags": [ "a" , {"x/y": null, "~": [true]} ],
+    "dup": 1, "dup": 22,
+    "caf\u00e9": -1.5e3,
+    "": {},
+    "über": "ö"
+}"#;
+
+    #[test_case("", SOURCE)]
+    #[test_case("/name", r#""widget""#)]
+    #[test_case("/tags", r#"[ "a" , {"x/y": null, "~": [true]} ]"#)]
+    #[test_case("/tags/0", r#""a""#)]
+    #[test_case("/tags/1", r#"{"x/y": null, "~": [true]}"#)]
+    #[test_case("/tags/1/x~1y", "null")]
+    #[test_case("/tags/1/~0/0", "true")]
+    #[test_case("/dup", "22"; "last duplicate")]
+    #[test_case("/café", "-1.5e3"; "escaped key")]
+    #[test_case("/", "{}"; "empty key")]
+    #[test_case("/über", r#""ö""#)]
+    fn span(pointer: &str, expected: &str) {
+        let source = SourceMap::new(SOURCE).expect("Valid JSON");
+        let span = source
+            .span(&Location::from_escaped(pointer))
+            .expect("Exists");
+        assert_eq!(&SOURCE[span.range], expected);
+    }
+
+    #[test_case("/missing")]
+    #[test_case("/tags/2")]
+    #[test_case("/tags/01")]
+    #[test_case("/tags/+1")]
+    #[test_case("/tags/x")]
+    #[test_case("/name/0")]
+    #[test_case("/tags/1/x/y")]
+    fn missing(pointer: &str) {
+        let source = SourceMap::new(SOURCE).expect("Valid JSON");
+        assert!(source.span(&Location::from_escaped(pointer)).is_none());
+    }
+
+    #[test]
+    fn positions() {
+        let source = SourceMap::new(SOURCE).expect("Valid JSON");
+        let span = source
+            .span(&Location::from_escaped("/über"))
+            .expect("Exists");
+        assert_eq!(
+            span.start,
+            Position {
+                line: 7,
+                column: 13
+            }
+        );
+        assert_eq!(
+            span.end,
+            Position {
+                line: 7,
+                column: 16
+            }
+        );
+        let span = source.span(&Location::new()).expect("Exists");
+        assert_eq!(span.start, Position { line: 1, column: 1 });
+        assert_eq!(span.end, Position { line: 8, column: 2 });
+    }
+
+    #[test]
+    fn scalar_document() {
+        let source = SourceMap::new("  42 ").expect("Valid JSON");
+        let span = source.span(&Location::new()).expect("Exists");
+        assert_eq!(span.range, 2..4);
+    }
+
+    #[test]
+    fn invalid_json() {
+        assert!(SourceMap::new("{\"a\": }").is_err());
+    }
+
+    #[test]
+    fn errors() {
+        let schema_text = r##"{
+  "$defs": {"port": {"type": "integer", "maximum": 65535}},
+  "properties": {
+    "port": {"$ref": "#/$defs/port"},
+    "host": {"type": "string"}
+  }
+}"##;
+        let instance_text = "{\"host\": 1,\n \"port\": 70000}";
+        let schema = serde_json::from_str(schema_text).expect("Valid JSON");
+        let instance = serde_json::from_str(instance_text).expect("Valid JSON");
+        let validator = crate::validator_for(&schema).expect("Invalid schema");
+        let schema_source = SourceMap::new(schema_text).expect("Valid JSON");
+        let instance_source = SourceMap::new(instance_text).expect("Valid JSON");
+        let mut spans: Vec<_> = validator
+            .iter_errors(&instance)
+            .map(|error| {
+                let instance_span = instance_source.span(&error.instance_path).expect("Exists");
+                let schema_span = schema_source.closest_span(&error.schema_path);
+                (
+                    &instance_text[instance_span.range],
+                    instance_span.start.line,
+                    &schema_text[schema_span.range],
+                    schema_span.start.line,
+                )
+            })
+            .collect();
+        spans.sort_unstable();
+        assert_eq!(
+            spans,
+            [
+                ("1", 1, r#""string""#, 5),
+                ("70000", 2, r##""#/$defs/port""##, 4)
+            ]
+        );
+        assert!(schema_source
+            .span(&Location::from_escaped("/properties/port/$ref/maximum"))
+            .is_none());
+    }
+}
@@ -0,0 +1,172 @@
+//! "Did you mean" suggestions for misspelled property names and `enum` or `const` values.
+use serde_json::Value;
+use std::fmt;
+
+/// The candidate closest to `value`, if it is close enough to be a likely misspelling of it.
+///
+/// Ties go to the candidate that comes first in lexicographical order, so the result doesn't
+/// depend on the order of `candidates`.
+pub(crate) fn closest<'a>(
+    value: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let length = value.chars().count();
+    let mut best: Option<(usize, &str)> = None;
+    for candidate in candidates {
+        if candidate == value {
+            continue;
+        }
+        let candidate_length = candidate.chars().count();
+        // Anything further away is more likely a different word than a typo
+        let limit = (length.max(candidate_length) / 3).max(1);
+        if length.abs_diff(candidate_length) > limit {
+            continue;
+        }
+        let distance = distance(value, candidate);
+        if distance > limit || distance >= length.min(candidate_length) {
+            continue;
+        }
+        if best.map_or(true, |best| (distance, candidate) < best) {
+            best = Some((distance, candidate));
+        }
+    }
+    best.map(|(_, candidate)| candidate)
+}
+
+/// The closest candidate to `value` as a list of suggestions.
+pub(crate) fn suggest<'a>(
+    value: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Vec<String> {
+    closest(value, candidates)
+        .map(str::to_string)
+        .into_iter()
+        .collect()
+}
+
+/// Strings that failing values are compared against, collected once when a keyword is compiled.
+#[derive(Debug, Default)]
+pub(crate) struct Candidates(Box<[String]>);
+
+impl Candidates {
+    pub(crate) fn new<'a>(candidates: impl IntoIterator<Item = &'a str>) -> Candidates {
+        Candidates(candidates.into_iter().map(str::to_string).collect())
+    }
+    /// String values among `values`.
+    pub(crate) fn strings<'a>(values: impl IntoIterator<Item = &'a Value>) -> Candidates {
+        Candidates::new(values.into_iter().filter_map(Value::as_str))
+    }
+    fn iter(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(String::as_str)
+    }
+    /// The closest candidate to `instance` if it is a string.
+    pub(crate) fn suggest_for(&self, instance: &Value) -> Vec<String> {
+        match instance {
+            Value::String(value) => suggest(value, self.iter()),
+            _ => Vec::new(),
+        }
+    }
+    /// The closest candidates for each of `values`, without duplicates.
+    pub(crate) fn suggest_each(&self, values: &[String]) -> Vec<String> {
+        let mut suggestions: Vec<String> = Vec::new();
+        for value in values {
+            if let Some(suggestion) = closest(value, self.iter()) {
+                if !suggestions.iter().any(|existing| existing == suggestion) {
+                    suggestions.push(suggestion.to_string());
+                }
+            }
+        }
+        suggestions
+    }
+}
+
+/// Write `suggestions` joined with "or" between `prefix` and `suffix`, if there are any.
+pub(crate) fn write<T>(
+    f: &mut fmt::Formatter<'_>,
+    prefix: &str,
+    suggestions: &[T],
+    suffix: &str,
+    write: impl Fn(&mut fmt::Formatter<'_>, &T) -> fmt::Result,
+) -> fmt::Result {
+    let Some((last, rest)) = suggestions.split_last() else {
+        return Ok(());
+    };
+    f.write_str(prefix)?;
+    for (idx, suggestion) in rest.iter().enumerate() {
+        if idx > 0 {
+            f.write_str(", ")?;
+        }
+        write(f, suggestion)?;
+    }
+    if !rest.is_empty() {
+        f.write_str(" or ")?;
+    }
+    write(f, last)?;
+    f.write_str(suffix)
+}
+
+/// The optimal string alignment distance between `left` and `right`: the number of inserted,
+/// deleted or replaced characters and swapped adjacent characters needed to turn one into the
+/// other.
+fn distance(left: &str, right: &str) -> usize {
+    let left: Vec<char> = left.chars().collect();
+    let right: Vec<char> = right.chars().collect();
+    // Three rows of the distance matrix: two rows back (for swaps), the previous and the current
+    let mut before_previous = vec![0; right.len() + 1];
+    let mut previous: Vec<usize> = (0..=right.len()).collect();
+    let mut current = vec![0; right.len() + 1];
+    for (i, left_char) in left.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, right_char) in right.iter().enumerate() {
+            let cost = usize::from(left_char != right_char);
+            current[j + 1] = (previous[j] + cost)
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+            if i > 0 && j > 0 && *left_char == right[j - 1] && left[i - 1] == *right_char {
+                current[j + 1] = current[j + 1].min(before_previous[j - 1] + 1);
+            }
+        }
+        std::mem::swap(&mut before_previous, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[right.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{closest, distance, Candidates};
+    use test_case::test_case;
+
+    #[test_case("", "", 0)]
+    #[test_case("abc", "", 3)]
+    #[test_case("", "abc", 3)]
+    #[test_case("email", "email", 0)]
+    #[test_case("emial", "email", 1; "swap")]
+    #[test_case("Pendng", "Pending", 1; "insert")]
+    #[test_case("kitten", "sitting", 3)]
+    #[test_case("café", "cafe", 1; "multibyte")]
+    fn distances(left: &str, right: &str, expected: usize) {
+        assert_eq!(distance(left, right), expected);
+        assert_eq!(distance(right, left), expected);
+    }
+
+    #[test_case("emial", &["name", "email", "age"], Some("email"))]
+    #[test_case("Pendng", &["Done", "Pending"], Some("Pending"))]
+    #[test_case("cat", &["bat", "hat"], Some("bat"); "ties go to the first in order")]
+    #[test_case("cat", &["hat", "bat"], Some("bat"); "ties do not depend on candidate order")]
+    #[test_case("x", &["y"], None; "too short")]
+    #[test_case("foo", &["bar"], None; "too different")]
+    #[test_case("id", &["identifier"], None; "too different length")]
+    #[test_case("name", &["name"], None; "same")]
+    #[test_case("name", &[], None; "no candidates")]
+    fn closest_candidate(value: &str, candidates: &[&str], expected: Option<&str>) {
+        assert_eq!(closest(value, candidates.iter().copied()), expected);
+    }
+
+    #[test]
+    fn deduplicated() {
+        let values = vec!["emial".to_string(), "emal".to_string(), "foo".to_string()];
+        let candidates = Candidates::new(["email", "name"]);
+        assert_eq!(candidates.suggest_each(&values), ["email"]);
+    }
+}
@@ -2,14 +2,20 @@
 //! The main idea is to create a tree from the input JSON Schema. This tree will contain
 //! everything needed to perform such validation in runtime.
 use crate::{
+    coercion::{Coercer, Coercion},
     error::{error, no_error, ErrorIterator},
+    formatter::ErrorFormatter,
     node::SchemaNode,
-    output::{Annotations, ErrorDescription, Output, OutputUnit},
-    paths::LazyLocation,
-    Draft, ValidationError, ValidationOptions,
+    output::{Annotations, CollectedAnnotations, ErrorDescription, HierarchicalOutput, Output},
+    paths::{LazyLocation, Location},
+    reader::{self, StreamedSubschemas},
+    serializer, Draft, ReaderError, SerializeError, ValidationError, ValidationOptions,
 };
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use serde::Serialize;
 use serde_json::Value;
-use std::{collections::VecDeque, sync::Arc};
+use std::{io::Read, sync::Arc};
 
 /// The Validate trait represents a predicate over some JSON value. Some validators are very simple
 /// predicates such as "a value which is a string", whereas others may be much more complex,
@@ -61,35 +67,41 @@ pub(crate) trait Validate: Send + Sync {
     /// If you are writing a validator which is composed of other validators then your validator will
     /// need to store references to the `SchemaNode`s which contain those other validators.
     /// `SchemaNode` stores information about where it is in the schema tree and therefore provides an
-    /// `apply_rooted` method which returns a full `BasicOutput`. `BasicOutput` implements `AddAssign`
-    /// so a typical pattern is to compose results from sub validators using `+=` and then use the
-    /// `From<BasicOutput> for PartialApplication` impl to convert the composed outputs into a
-    /// `PartialApplication` to return. For example, here is the implementation of
+    /// `apply_rooted` method which returns a complete `HierarchicalOutput` node. A typical pattern
+    /// is to collect the nodes of sub validators into a `Vec` and then use the
+    /// `From<Vec<HierarchicalOutput>> for PartialApplication` impl to convert them into a
+    /// `PartialApplication` to return, which is valid if all of the nodes are valid. The nodes
+    /// become the children of the node of this validator, so the output formats can tell which
+    /// subschema produced each error or annotation. For example, here is the implementation of
     /// `IfThenValidator`
     ///
     /// ```rust,ignore
-    /// // Note that self.schema is a `SchemaNode` and we use `apply_rooted` to return a `BasicOutput`
-    /// let mut if_result = self.schema.apply_rooted(instance, instance_path);
+    /// // Note that self.schema is a `SchemaNode` and we use `apply_rooted` to return a node
+    /// let if_result = self.schema.apply_rooted(instance, instance_path, formatter);
     /// if if_result.is_valid() {
-    ///     // here we use the `AddAssign` implementation to combine the results of subschemas
-    ///     if_result += self
-    ///         .then_schema
-    ///         .apply_rooted(instance, instance_path);
-    ///     // Here we use the `From<BasicOutput> for PartialApplication impl
-    ///     if_result.into()
+    ///     let then_result = self.then_schema.apply_rooted(instance, instance_path, formatter);
+    ///     // Here we use the `From<Vec<HierarchicalOutput>> for PartialApplication` impl
+    ///     vec![if_result, then_result].into()
     /// } else {
-    ///     self.else_schema
-    ///         .apply_rooted(instance, instance_path)
-    ///         .into()
+    ///     PartialApplication::valid_empty()
     /// }
     /// ```
     ///
-    /// `BasicOutput` also implements `Sum<BasicOutput>` and `FromIterator<BasicOutput<'a>> for PartialApplication<'a>`
-    /// so you can use `sum()` and `collect()` in simple cases.
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    /// `PartialApplication` also implements `FromIterator<HierarchicalOutput<'a>>` so you can use
+    /// `collect()` in simple cases.
+    ///
+    /// `formatter` is the error formatter of the validator, if one is configured. Errors are
+    /// rendered with it as soon as they are turned into an `ErrorDescription`, so it must be
+    /// passed on to the subschemas.
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        formatter: Option<&dyn ErrorFormatter>,
+    ) -> PartialApplication<'a> {
         let errors: Vec<ErrorDescription> = self
             .iter_errors(instance, location)
-            .map(ErrorDescription::from)
+            .map(|error| ErrorDescription::new(&error, formatter))
             .collect();
         if errors.is_empty() {
             PartialApplication::valid_empty()
@@ -97,6 +109,34 @@ pub(crate) trait Validate: Send + Sync {
             PartialApplication::invalid_empty(errors)
         }
     }
+
+    /// Insert `default` values from the subschemas of this validator into `instance`.
+    ///
+    /// `original` is `instance` as it was before any defaults were inserted, branches are
+    /// selected against it. Only validators which apply subschemas to the instance or to its
+    /// children need to implement this, the default implementation does nothing. See
+    /// [`Validator::apply_defaults`] for the policy each keyword follows.
+    fn apply_defaults(&self, _instance: &mut Value, _original: &Value) {}
+
+    /// Rewrite `instance` and its children to match the types expected by this validator.
+    ///
+    /// Only `type` validators and validators which apply subschemas need to implement this. See
+    /// [`Validator::coerce`] for the policy each keyword follows.
+    fn coerce(&self, _instance: &mut Value, _location: &Location, _coercer: &mut Coercer) {}
+
+    /// Remove properties of `instance` and its children which this validator rejects as
+    /// additional or unevaluated, and record their locations in `pruned`.
+    ///
+    /// Only `additionalProperties`, `unevaluatedProperties` and validators which apply
+    /// subschemas need to implement this. See [`Validator::prune`] for the policy each keyword
+    /// follows.
+    fn prune(&self, _instance: &mut Value, _location: &Location, _pruned: &mut Vec<Location>) {}
+
+    /// Subschemas this validator applies to the instance itself or to array items, if that is
+    /// all it does. It allows validating documents item by item while they are being read.
+    fn streamed_subschemas(&self) -> Option<StreamedSubschemas<'_>> {
+        None
+    }
 }
 
 /// The result of applying a validator to an instance. As explained in the documentation for
@@ -108,13 +148,13 @@ pub(crate) enum PartialApplication<'a> {
         /// Annotations produced by this validator
         annotations: Option<Annotations<'a>>,
         /// Any outputs produced by validators which are children of this validator
-        child_results: VecDeque<OutputUnit<Annotations<'a>>>,
+        child_results: Vec<HierarchicalOutput<'a>>,
     },
     Invalid {
         /// Errors which caused this schema to be invalid
         errors: Vec<ErrorDescription>,
-        /// Any error outputs produced by child validators of this validator
-        child_results: VecDeque<OutputUnit<ErrorDescription>>,
+        /// Any outputs produced by child validators of this validator, including the valid ones
+        child_results: Vec<HierarchicalOutput<'a>>,
     },
 }
 
@@ -123,7 +163,7 @@ impl<'a> PartialApplication<'a> {
     pub(crate) fn valid_empty() -> PartialApplication<'static> {
         PartialApplication::Valid {
             annotations: None,
-            child_results: VecDeque::new(),
+            child_results: Vec::new(),
         }
     }
 
@@ -131,7 +171,7 @@ impl<'a> PartialApplication<'a> {
     pub(crate) fn invalid_empty(errors: Vec<ErrorDescription>) -> PartialApplication<'static> {
         PartialApplication::Invalid {
             errors,
-            child_results: VecDeque::new(),
+            child_results: Vec::new(),
         }
     }
 
@@ -146,14 +186,14 @@ impl<'a> PartialApplication<'a> {
 
     /// Set the error that will be returned for the current validator. If this
     /// `PartialApplication` is valid then this method converts this application into
-    /// `PartialApplication::Invalid`
+    /// `PartialApplication::Invalid`, keeping the outputs of its children
     pub(crate) fn mark_errored(&mut self, error: ErrorDescription) {
         match self {
             Self::Invalid { errors, .. } => errors.push(error),
-            Self::Valid { .. } => {
+            Self::Valid { child_results, .. } => {
                 *self = Self::Invalid {
                     errors: vec![error],
-                    child_results: VecDeque::new(),
+                    child_results: std::mem::take(child_results),
                 }
             }
         }
@@ -198,6 +238,100 @@ impl Validator {
     pub fn validate<'i>(&self, instance: &'i Value) -> Result<(), ValidationError<'i>> {
         self.root.validate(instance, &LazyLocation::new())
     }
+    /// Validate any [`Serialize`] value and return the first error if any.
+    ///
+    /// A top-level sequence, map or struct is validated element by element as it is serialized,
+    /// so only the current element is converted into a [`Value`]. This works for the same
+    /// keywords as [`Validator::validate_reader`] does, otherwise the container is converted as
+    /// a whole first. Instance paths in errors are the same as for the result of
+    /// `serde_json::to_value`.
+    ///
+    /// Errors from keywords applied to each object property, like `additionalProperties`, have
+    /// an object with just the offending property as their instance.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SerializeError::Serialize`](crate::SerializeError::Serialize) if `instance` can
+    /// not be represented as JSON, e.g. a map has non-string keys, and
+    /// [`SerializeError::Validation`](crate::SerializeError::Validation) if it is not valid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use jsonschema::SerializeError;
+    /// use serde::Serialize;
+    /// use serde_json::json;
+    ///
+    /// #[derive(Serialize)]
+    /// struct User {
+    ///     name: String,
+    ///     age: i32,
+    /// }
+    ///
+    /// let schema = json!({"properties": {"age": {"minimum": 0}}});
+    /// let validator = jsonschema::validator_for(&schema).expect("Invalid schema");
+    ///
+    /// let user = User { name: "Alice".into(), age: -1 };
+    /// let Err(SerializeError::Validation(error)) = validator.validate_serialize(&user) else {
+    ///     panic!("Should fail");
+    /// };
+    /// assert_eq!(error.instance_path.as_str(), "/age");
+    /// ```
+    pub fn validate_serialize<T>(&self, instance: &T) -> Result<(), SerializeError>
+    where
+        T: Serialize + ?Sized,
+    {
+        serializer::validate(&self.root, instance)
+    }
+    /// Validate a JSON document read from `reader` and return the first error if any.
+    ///
+    /// A top-level array or object is validated element by element as it is read, so the whole
+    /// document does not have to fit in memory. This works if the root schema only uses `type`,
+    /// `$ref`, `allOf` and keywords applying a subschema to each item or property on their own,
+    /// like `items` or `additionalProperties`. Only invalid properties of an object are kept
+    /// until it ends. Otherwise, e.g. with `uniqueItems`, `required` or
+    /// `additionalProperties: false`, the container is read into memory first.
+    ///
+    /// Either way, the error is the same as [`Validator::validate`] returns for the parsed
+    /// document.
+    ///
+    /// The reader is not buffered, consider wrapping it into [`std::io::BufReader`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use jsonschema::ReaderError;
+    /// use serde_json::json;
+    ///
+    /// let schema = json!({"type": "array", "items": {"type": "integer"}});
+    /// let validator = jsonschema::validator_for(&schema).expect("Invalid schema");
+    ///
+    /// let document = "[1, 2, 3, \"four\"]";
+    /// match validator.validate_reader(document.as_bytes()) {
+    ///     Err(ReaderError::Validation(error)) => {
+    ///         assert_eq!(error.instance_path.as_str(), "/3");
+    ///     }
+    ///     _ => panic!("Should fail"),
+    /// }
+    /// assert!(matches!(
+    ///     validator.validate_reader("[1, 2".as_bytes()),
+    ///     Err(ReaderError::Json(_))
+    /// ));
+    /// ```
+    pub fn validate_reader<R: Read>(&self, reader: R) -> Result<(), ReaderError> {
+        reader::validate(&self.root, reader)
+    }
+    /// Render `error` with the formatter set via
+    /// [`ValidationOptions::with_error_formatter`](crate::ValidationOptions::with_error_formatter).
+    ///
+    /// Without a custom formatter, this is the same as `error.to_string()`.
+    #[must_use]
+    pub fn format_error(&self, error: &ValidationError<'_>) -> String {
+        match self.config.error_formatter() {
+            Some(formatter) => formatter.format(error),
+            None => error.to_string(),
+        }
+    }
     /// Run validation against `instance` and return an iterator over [`ValidationError`] in the error case.
     #[inline]
     pub fn iter_errors<'i>(&'i self, instance: &'i Value) -> ErrorIterator<'i> {
@@ -211,6 +345,64 @@ impl Validator {
     pub fn is_valid(&self, instance: &Value) -> bool {
         self.root.is_valid(instance)
     }
+    /// Validate each of `instances` in parallel and return the first error of each one.
+    ///
+    /// Results are in the same order as `instances`. Validation runs on the global `rayon`
+    /// thread pool, use [`rayon::ThreadPool::install`] to pick another one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use serde_json::json;
+    ///
+    /// let validator = jsonschema::validator_for(&json!({"type": "integer"})).expect("Invalid schema");
+    /// let instances = [json!(1), json!("2"), json!(3)];
+    ///
+    /// let results = validator.validate_batch(&instances);
+    /// assert!(results[0].is_ok());
+    /// assert!(results[1].is_err());
+    /// assert!(results[2].is_ok());
+    /// ```
+    #[cfg(feature = "parallel")]
+    pub fn validate_batch<'i>(
+        &self,
+        instances: &'i [Value],
+    ) -> Vec<Result<(), ValidationError<'i>>> {
+        instances
+            .par_iter()
+            .map(|instance| self.validate(instance))
+            .collect()
+    }
+    /// Run validation against each of `instances` in parallel and return all errors of each one.
+    ///
+    /// The returned iterator is indexed, so collecting it or zipping it with `instances` keeps
+    /// the input order. An empty `Vec` means that the corresponding instance is valid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rayon::prelude::*;
+    /// use serde_json::json;
+    ///
+    /// let validator = jsonschema::validator_for(&json!({"minimum": 2, "multipleOf": 2}))
+    ///     .expect("Invalid schema");
+    /// let instances = [json!(2), json!(1), json!(4)];
+    ///
+    /// let errors: Vec<usize> = validator
+    ///     .par_iter_errors(&instances)
+    ///     .map(|errors| errors.len())
+    ///     .collect();
+    /// assert_eq!(errors, [0, 2, 0]);
+    /// ```
+    #[cfg(feature = "parallel")]
+    pub fn par_iter_errors<'i>(
+        &'i self,
+        instances: &'i [Value],
+    ) -> impl IndexedParallelIterator<Item = Vec<ValidationError<'i>>> + 'i {
+        instances
+            .par_iter()
+            .map(move |instance| self.iter_errors(instance).collect())
+    }
     /// Apply the schema and return an [`Output`]. No actual work is done at this point, the
     /// evaluation of the schema is deferred until a method is called on the `Output`. This is
     /// because different output formats will have different performance characteristics.
@@ -256,6 +448,233 @@ impl Validator {
         Output::new(self, &self.root, instance)
     }
 
+    /// Collect the annotations which apply to `instance`, keyed by instance location.
+    ///
+    /// Annotations from subschemas that failed are dropped, so the result only describes the
+    /// parts of the schema which actually apply to each location. If `instance` is invalid,
+    /// nothing is collected.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use serde_json::json;
+    ///
+    /// let schema = json!({
+    ///     "properties": {
+    ///         "email": {"title": "E-mail", "default": "user@example.com"}
+    ///     }
+    /// });
+    /// let validator = jsonschema::validator_for(&schema).expect("Invalid schema");
+    ///
+    /// let instance = json!({"email": "admin@example.com"});
+    /// let annotations = validator.annotate(&instance);
+    ///
+    /// let email = annotations.get("/email").expect("Missing annotations");
+    /// assert_eq!(email.get("title")[0].value(), &json!("E-mail"));
+    /// assert_eq!(email.get("default")[0].keyword_location().as_str(), "/properties/email/default");
+    ///
+    /// let root = annotations.get("").expect("Missing annotations");
+    /// assert!(root.evaluated_properties().contains("email"));
+    /// ```
+    #[must_use]
+    pub fn annotate<'a>(&'a self, instance: &Value) -> CollectedAnnotations<'a> {
+        CollectedAnnotations::new(self.apply(instance).basic(), instance)
+    }
+
+    /// Insert `default` values from the schema into `instance`.
+    ///
+    /// Defaults are filled in for missing object properties from `properties`, and for missing
+    /// trailing array items from `prefixItems` (or the array form of `items`). Existing values
+    /// are never replaced, but they are descended into so that nested defaults are applied too.
+    /// Inserted defaults are used as-is, defaults from their own subschemas are not merged in.
+    ///
+    /// In-place applicators need a policy, as their subschemas may disagree:
+    ///
+    /// - `allOf` applies every branch in order, so the first branch providing a default wins;
+    /// - `anyOf` applies only the first branch that `instance` matches;
+    /// - `oneOf` applies the matching branch only if exactly one branch matches;
+    /// - `if` decides between `then` and `else`, its own defaults are never applied;
+    /// - `$ref` and friends apply the referenced schema.
+    ///
+    /// Branches are selected against `instance` as it was before any defaults were inserted, so
+    /// defaults from sibling keywords or earlier `allOf` branches don't change which branch
+    /// applies. Defaults inside `not`, `contains`, `propertyNames`, `patternProperties`,
+    /// `additionalProperties`, `dependentSchemas`, and the `unevaluated*` keywords are ignored,
+    /// as is the `default` of the root schema.
+    ///
+    /// The instance is not validated, call [`Validator::validate`] afterwards if needed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use serde_json::json;
+    ///
+    /// let schema = json!({
+    ///     "properties": {
+    ///         "port": {"type": "integer", "default": 8080},
+    ///         "tls": {
+    ///             "properties": {"enabled": {"default": false}}
+    ///         }
+    ///     }
+    /// });
+    /// let validator = jsonschema::validator_for(&schema).expect("Invalid schema");
+    ///
+    /// let mut instance = json!({"tls": {}});
+    /// validator.apply_defaults(&mut instance);
+    /// assert_eq!(instance, json!({"port": 8080, "tls": {"enabled": false}}));
+    /// ```
+    pub fn apply_defaults(&self, instance: &mut Value) {
+        let original = instance.clone();
+        self.root.apply_defaults(instance, &original);
+    }
+
+    /// Rewrite values within `instance` to match the `type` keyword and return the performed
+    /// coercions.
+    ///
+    /// Coercion is enabled with [`ValidationOptions::with_type_coercion`], otherwise this
+    /// method leaves the instance intact and returns no coercions. See
+    /// [`crate::coercion::TypeCoercion`] for the supported conversions.
+    ///
+    /// The option only affects this method and [`Validator::validate_coerced`]. Other methods,
+    /// like [`Validator::validate`] or [`Validator::is_valid`], never coerce the instance they
+    /// are given.
+    ///
+    /// Within a schema, `type` is applied first, so that the other keywords see the coerced
+    /// value. Subschemas are visited as follows:
+    ///
+    /// - `properties`, `patternProperties`, `additionalProperties`, `items`, `prefixItems` and
+    ///   `additionalItems` coerce the children they apply to;
+    /// - `allOf` and `$ref` coerce with every subschema in order;
+    /// - `anyOf` and `oneOf` keep the result of the first branch which accepts its coerced
+    ///   instance, and leave the instance intact if there is none;
+    /// - `if` coerces the instance only if it accepts the coerced instance, then `then` or
+    ///   `else` is applied depending on that.
+    ///
+    /// Other keywords, like `not`, `contains` or `dependentSchemas`, don't coerce anything.
+    /// The instance is not validated, call [`Validator::validate`] afterwards, or use
+    /// [`Validator::validate_coerced`] to do both.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use jsonschema::coercion::TypeCoercion;
+    /// use serde_json::json;
+    ///
+    /// let schema = json!({"type": "object", "properties": {"ids": {"type": "array", "items": {"type": "integer"}}}});
+    /// let validator = jsonschema::options()
+    ///     .with_type_coercion(TypeCoercion::Arrays)
+    ///     .build(&schema)
+    ///     .expect("Invalid schema");
+    ///
+    /// let mut instance = json!({"ids": "7"});
+    /// let coercions = validator.coerce(&mut instance);
+    /// assert_eq!(instance, json!({"ids": [7]}));
+    ///
+    /// let paths: Vec<_> = coercions.iter().map(|c| c.instance_path().as_str()).collect();
+    /// assert_eq!(paths, ["/ids", "/ids/0"]);
+    /// ```
+    pub fn coerce(&self, instance: &mut Value) -> Vec<Coercion> {
+        let Some(mode) = self.config.type_coercion() else {
+            return Vec::new();
+        };
+        let mut coercer = Coercer::new(mode);
+        self.root.coerce(instance, &Location::new(), &mut coercer);
+        coercer.into_coercions()
+    }
+
+    /// Coerce `instance` as [`Validator::coerce`] does, then validate it.
+    ///
+    /// Returns the performed coercions if the coerced instance is valid. The instance stays
+    /// coerced either way. Without [`ValidationOptions::with_type_coercion`], this is the same
+    /// as [`Validator::validate`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error found in the coerced instance.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use jsonschema::coercion::TypeCoercion;
+    /// use serde_json::json;
+    ///
+    /// let schema = json!({"properties": {"a": {"type": "integer"}}});
+    /// let validator = jsonschema::options()
+    ///     .with_type_coercion(TypeCoercion::Scalars)
+    ///     .build(&schema)
+    ///     .expect("Invalid schema");
+    ///
+    /// let mut instance = json!({"a": "1"});
+    /// assert!(validator.validate(&instance).is_err());
+    /// assert!(validator.validate_coerced(&mut instance).is_ok());
+    /// assert_eq!(instance, json!({"a": 1}));
+    /// ```
+    pub fn validate_coerced<'i>(
+        &self,
+        instance: &'i mut Value,
+    ) -> Result<Vec<Coercion>, ValidationError<'i>> {
+        let coercions = self.coerce(instance);
+        let instance: &'i Value = instance;
+        self.validate(instance)?;
+        Ok(coercions)
+    }
+
+    /// Remove the properties `additionalProperties` and `unevaluatedProperties` reject, and
+    /// return their locations.
+    ///
+    /// A property is removed where validation would report an "additional properties" or
+    /// "unevaluated properties" error: `additionalProperties: false`, and
+    /// `unevaluatedProperties` whose subschema rejects the property value. Values allowed by the
+    /// schema are descended into via `properties`, `patternProperties`, `additionalProperties`,
+    /// `items`, `prefixItems`, `additionalItems`, `allOf` and `$ref`.
+    ///
+    /// Branching keywords only prune along a branch the result is valid for, so that properties
+    /// one branch does not know about are not removed when another branch allows them:
+    ///
+    /// - `anyOf` prunes nothing if the instance already matches a branch. Otherwise it keeps the
+    ///   result of the first branch which accepts the instance pruned by this branch;
+    /// - `oneOf` prunes nothing if the instance already matches. Otherwise it prunes only if
+    ///   exactly one branch produces an instance that matches `oneOf`, as it is ambiguous which
+    ///   properties are extra otherwise;
+    /// - `if` is evaluated as is, and `then` or `else` prune the instance depending on it.
+    ///
+    /// If no branch accepts its pruned instance, the instance is left intact. The instance is
+    /// not validated, call [`Validator::validate`] afterwards if needed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use serde_json::json;
+    ///
+    /// let schema = json!({
+    ///     "properties": {
+    ///         "name": {"type": "string"},
+    ///         "address": {
+    ///             "properties": {"city": {"type": "string"}},
+    ///             "additionalProperties": false
+    ///         }
+    ///     },
+    ///     "additionalProperties": false
+    /// });
+    /// let validator = jsonschema::validator_for(&schema).expect("Invalid schema");
+    ///
+    /// let mut instance = json!({
+    ///     "name": "Alice",
+    ///     "address": {"city": "Berlin", "zip": "10115"},
+    ///     "debug": true
+    /// });
+    /// let pruned = validator.prune(&mut instance);
+    /// assert_eq!(instance, json!({"name": "Alice", "address": {"city": "Berlin"}}));
+    ///
+    /// let paths: Vec<_> = pruned.iter().map(|location| location.as_str()).collect();
+    /// assert_eq!(paths, ["/address/zip", "/debug"]);
+    /// ```
+    pub fn prune(&self, instance: &mut Value) -> Vec<Location> {
+        let mut pruned = Vec::new();
+        self.root.prune(instance, &Location::new(), &mut pruned);
+        pruned
+    }
+
     /// The [`Draft`] which was used to build this validator.
     #[must_use]
     pub fn draft(&self) -> Draft {
@@ -282,6 +701,7 @@ mod tests {
     use num_cmp::NumCmp;
     use once_cell::sync::Lazy;
     use serde_json::{json, Map, Value};
+    use test_case::test_case;
 
     #[cfg(not(target_arch = "wasm32"))]
     fn load(path: &str, idx: usize) -> Value {
@@ -388,6 +808,7 @@ mod tests {
                     path,
                     schema,
                     EXPECTED,
+                    Vec::new(),
                 ))
             } else {
                 Ok(Box::new(CustomObjectValidator))
@@ -571,6 +992,228 @@ mod tests {
         assert_eq!(error.to_string(), "\"foo\" is not of type \"number\"");
     }
 
+    #[test_case(
+        &json!({"properties": {"a": {"default": 1}, "b": {"default": 2}}}),
+        json!({"b": 3}),
+        &json!({"a": 1, "b": 3});
+        "missing properties only"
+    )]
+    #[test_case(
+        &json!({"properties": {"a": {"properties": {"b": {"default": 1}}, "default": {}}}}),
+        json!({}),
+        &json!({"a": {}});
+        "inserted defaults are used as-is"
+    )]
+    #[test_case(
+        &json!({"properties": {"a": {"default": 1}}, "additionalProperties": false}),
+        json!({}),
+        &json!({"a": 1});
+        "properties with additional properties"
+    )]
+    #[test_case(
+        &json!({"prefixItems": [{"default": 1}, {"default": 2}, {}, {"default": 4}]}),
+        json!([0]),
+        &json!([0, 2]);
+        "trailing items up to the first gap"
+    )]
+    #[test_case(
+        &json!({"items": {"properties": {"a": {"default": 1}}}}),
+        json!([{}, {"a": 2}, 3]),
+        &json!([{"a": 1}, {"a": 2}, 3]);
+        "items schema applies to every item"
+    )]
+    #[test_case(
+        &json!({"allOf": [{"properties": {"a": {"default": 1}}}, {"properties": {"a": {"default": 2}, "b": {"default": 3}}}]}),
+        json!({}),
+        &json!({"a": 1, "b": 3});
+        "all of with first branch winning"
+    )]
+    #[test_case(
+        &json!({"anyOf": [{"required": ["x"], "properties": {"a": {"default": 1}}}, {"properties": {"a": {"default": 2}}}]}),
+        json!({}),
+        &json!({"a": 2});
+        "any of with first matching branch"
+    )]
+    #[test_case(
+        &json!({"oneOf": [{"properties": {"a": {"default": 1}}}, {"properties": {"a": {"default": 2}}}]}),
+        json!({}),
+        &json!({});
+        "one of with ambiguous branches"
+    )]
+    #[test_case(
+        &json!({"oneOf": [{"required": ["x"], "properties": {"a": {"default": 1}}}, {"properties": {"a": {"default": 2}}}]}),
+        json!({}),
+        &json!({"a": 2});
+        "one of with single matching branch"
+    )]
+    #[test_case(
+        &json!({"if": {"required": ["x"]}, "then": {"properties": {"a": {"default": 1}}}, "else": {"properties": {"a": {"default": 2}}}}),
+        json!({"x": 0}),
+        &json!({"x": 0, "a": 1});
+        "if then"
+    )]
+    #[test_case(
+        &json!({"if": {"required": ["x"]}, "then": {"properties": {"a": {"default": 1}}}, "else": {"properties": {"a": {"default": 2}}}}),
+        json!({}),
+        &json!({"a": 2});
+        "if else"
+    )]
+    #[test_case(
+        &json!({"properties": {"x": {"default": 0}}, "if": {"required": ["x"]}, "then": {"properties": {"a": {"default": 1}}}, "else": {"properties": {"a": {"default": 2}}}}),
+        json!({}),
+        &json!({"x": 0, "a": 2});
+        "if with sibling default"
+    )]
+    #[test_case(
+        &json!({"allOf": [{"properties": {"x": {"default": 0}}}, {"if": {"required": ["x"]}, "then": {"properties": {"a": {"default": 1}}}, "else": {"properties": {"a": {"default": 2}}}}]}),
+        json!({}),
+        &json!({"x": 0, "a": 2});
+        "if after all of default"
+    )]
+    #[test_case(
+        &json!({"allOf": [{"properties": {"x": {"default": 0}}}, {"anyOf": [{"required": ["x"], "properties": {"a": {"default": 1}}}, {"properties": {"a": {"default": 2}}}]}]}),
+        json!({}),
+        &json!({"x": 0, "a": 2});
+        "any of after all of default"
+    )]
+    #[test_case(
+        &json!({"$defs": {"a": {"properties": {"a": {"default": 1}}}}, "$ref": "#/$defs/a"}),
+        json!({}),
+        &json!({"a": 1});
+        "reference"
+    )]
+    #[test_case(
+        &json!({"not": {"properties": {"a": {"default": 1}}}, "default": {"b": 2}}),
+        json!({}),
+        &json!({});
+        "ignored keywords"
+    )]
+    fn test_apply_defaults(schema: &Value, mut instance: Value, expected: &Value) {
+        let validator = crate::validator_for(schema).expect("Invalid schema");
+        validator.apply_defaults(&mut instance);
+        assert_eq!(&instance, expected);
+    }
+
+    #[test_case(
+        &json!({"properties": {"a": {}}, "patternProperties": {"^x-": {}}, "additionalProperties": false}),
+        json!({"a": 1, "x-b": 2, "c": 3}),
+        &json!({"a": 1, "x-b": 2}),
+        &["/c"];
+        "additional properties"
+    )]
+    #[test_case(
+        &json!({"additionalProperties": {"type": "integer"}}),
+        json!({"a": "b"}),
+        &json!({"a": "b"}),
+        &[];
+        "additional properties schema"
+    )]
+    #[test_case(
+        &json!({"items": {"properties": {"a": {}}, "additionalProperties": false}}),
+        json!([{"a": 1, "b": 2}, {"c": 3}]),
+        &json!([{"a": 1}, {}]),
+        &["/0/b", "/1/c"];
+        "nested in items"
+    )]
+    #[test_case(
+        &json!({"properties": {"a": {}}, "allOf": [{"properties": {"b": {}}}], "unevaluatedProperties": false}),
+        json!({"a": 1, "b": 2, "c": 3}),
+        &json!({"a": 1, "b": 2}),
+        &["/c"];
+        "unevaluated properties"
+    )]
+    #[test_case(
+        &json!({"anyOf": [
+            {"properties": {"a": {}}, "additionalProperties": false},
+            {"properties": {"a": {}, "b": {}}, "additionalProperties": false}
+        ]}),
+        json!({"a": 1, "b": 2}),
+        &json!({"a": 1, "b": 2}),
+        &[];
+        "any of with a matching branch"
+    )]
+    #[test_case(
+        &json!({"anyOf": [
+            {"required": ["x"], "properties": {"x": {}}, "additionalProperties": false},
+            {"properties": {"a": {}}, "additionalProperties": false}
+        ]}),
+        json!({"a": 1, "b": 2}),
+        &json!({"a": 1}),
+        &["/b"];
+        "any of with the first branch accepting the pruned instance"
+    )]
+    #[test_case(
+        &json!({"oneOf": [
+            {"properties": {"a": {}}, "additionalProperties": false},
+            {"properties": {"b": {}}, "additionalProperties": false}
+        ]}),
+        json!({"a": 1, "b": 2}),
+        &json!({"a": 1, "b": 2}),
+        &[];
+        "one of with ambiguous branches"
+    )]
+    #[test_case(
+        &json!({"oneOf": [
+            {"required": ["a"], "properties": {"a": {}}, "additionalProperties": false},
+            {"required": ["b"], "properties": {"b": {}}, "additionalProperties": false}
+        ]}),
+        json!({"a": 1, "c": 2}),
+        &json!({"a": 1}),
+        &["/c"];
+        "one of with a single accepting branch"
+    )]
+    #[test_case(
+        &json!({
+            "if": {"required": ["kind"]},
+            "then": {"properties": {"kind": {}}, "additionalProperties": false},
+            "else": {"properties": {"other": {}}, "additionalProperties": false}
+        }),
+        json!({"kind": 1, "other": 2}),
+        &json!({"kind": 1}),
+        &["/other"];
+        "if then"
+    )]
+    fn test_prune(schema: &Value, mut instance: Value, expected: &Value, paths: &[&str]) {
+        let validator = crate::validator_for(schema).expect("Invalid schema");
+        let pruned = validator.prune(&mut instance);
+        assert_eq!(&instance, expected);
+        assert_eq!(
+            pruned.iter().map(Location::as_str).collect::<Vec<_>>(),
+            paths
+        );
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_validate_batch() {
+        let validator =
+            crate::validator_for(&json!({"items": {"type": "integer"}})).expect("Invalid schema");
+        let instances: Vec<_> = (0..100)
+            .map(|idx| {
+                if idx % 7 == 0 {
+                    json!([idx, "x"])
+                } else {
+                    json!([idx])
+                }
+            })
+            .collect();
+        let results = validator.validate_batch(&instances);
+        assert_eq!(results.len(), instances.len());
+        for (idx, result) in results.iter().enumerate() {
+            assert_eq!(result.is_err(), idx % 7 == 0, "{idx}");
+        }
+        let errors: Vec<_> =
+            rayon::iter::ParallelIterator::collect(validator.par_iter_errors(&instances));
+        for (idx, errors) in errors.iter().enumerate() {
+            let paths: Vec<_> = errors.iter().map(|e| e.instance_path.as_str()).collect();
+            if idx % 7 == 0 {
+                assert_eq!(paths, ["/1"]);
+            } else {
+                assert!(paths.is_empty());
+            }
+        }
+    }
+
     #[test]
     fn test_validator_is_send_and_sync() {
         fn assert_send_sync<T: Send + Sync>() {}
@@ -1017,3 +1017,478 @@ fn test_additional_properties_basic_output(
         panic!("\nExpected:\n{}\n\nGot:\n{}\n", expected_str, actual_str);
     }
 }
+
+#[test_case{
+    &json!({"allOf": [{"type": "string", "typeannotation": "value"}, {"maxLength": 20}]}),
+    &json!("some string"),
+    &json!({
+        "valid": true,
+        "keywordLocation": "",
+        "instanceLocation": "",
+        "annotations": [
+            {
+                "valid": true,
+                "keywordLocation": "/allOf/0",
+                "instanceLocation": "",
+                "annotation": {
+                    "typeannotation": "value"
+                }
+            }
+        ]
+    }); "valid allOf"
+}]
+#[test_case{
+    &json!({"title": "root", "properties": {"name": {"type": "string", "some": "subannotation"}}}),
+    &json!({"name": "some name"}),
+    &json!({
+        "valid": true,
+        "keywordLocation": "",
+        "instanceLocation": "",
+        "annotation": {
+            "title": "root"
+        },
+        "annotations": [
+            {
+                "valid": true,
+                "keywordLocation": "/properties",
+                "instanceLocation": "",
+                "annotation": ["name"],
+                "annotations": [
+                    {
+                        "valid": true,
+                        "keywordLocation": "/properties/name",
+                        "instanceLocation": "/name",
+                        "annotation": {
+                            "some": "subannotation"
+                        }
+                    }
+                ]
+            }
+        ]
+    }); "valid nested annotations"
+}]
+#[test_case{
+    &json!({"properties": {"age": {"anyOf": [{"type": "integer"}, {"type": "null"}]}}}),
+    &json!({"age": "old"}),
+    &json!({
+        "valid": false,
+        "keywordLocation": "",
+        "instanceLocation": "",
+        "errors": [
+            {
+                "valid": false,
+                "keywordLocation": "/properties/age/anyOf",
+                "instanceLocation": "/age",
+                "errors": [
+                    {
+                        "valid": false,
+                        "keywordLocation": "/properties/age/anyOf/0/type",
+                        "instanceLocation": "/age",
+                        "error": "\"old\" is not of type \"integer\""
+                    },
+                    {
+                        "valid": false,
+                        "keywordLocation": "/properties/age/anyOf/1/type",
+                        "instanceLocation": "/age",
+                        "error": "\"old\" is not of type \"null\""
+                    }
+                ]
+            }
+        ]
+    }); "invalid anyOf"
+}]
+#[test_case{
+    &json!({
+        "$defs": {"positive": {"minimum": 0}},
+        "properties": {"a": {"$ref": "#/$defs/positive"}, "b": {"type": "string"}}
+    }),
+    &json!({"a": -1, "b": 1}),
+    &json!({
+        "valid": false,
+        "keywordLocation": "",
+        "instanceLocation": "",
+        "errors": [
+            {
+                "valid": false,
+                "keywordLocation": "/properties",
+                "instanceLocation": "",
+                "errors": [
+                    {
+                        "valid": false,
+                        "keywordLocation": "/properties/a/$ref/minimum",
+                        "instanceLocation": "/a",
+                        "error": "-1 is less than the minimum of 0"
+                    },
+                    {
+                        "valid": false,
+                        "keywordLocation": "/properties/b/type",
+                        "instanceLocation": "/b",
+                        "error": "1 is not of type \"string\""
+                    }
+                ]
+            }
+        ]
+    }); "invalid properties with $ref"
+}]
+#[test_case{
+    &json!({"items": {"type": "string"}}),
+    &json!([1, 2, "3"]),
+    &json!({
+        "valid": false,
+        "keywordLocation": "",
+        "instanceLocation": "",
+        "errors": [
+            {
+                "valid": false,
+                "keywordLocation": "/items",
+                "instanceLocation": "",
+                "errors": [
+                    {
+                        "valid": false,
+                        "keywordLocation": "/items/type",
+                        "instanceLocation": "/0",
+                        "error": "1 is not of type \"string\""
+                    },
+                    {
+                        "valid": false,
+                        "keywordLocation": "/items/type",
+                        "instanceLocation": "/1",
+                        "error": "2 is not of type \"string\""
+                    }
+                ]
+            }
+        ]
+    }); "invalid items"
+}]
+#[test_case{
+    &json!({"properties": {"properties": {"items": {"type": "string"}}}}),
+    &json!({"properties": [1, "2", 3]}),
+    &json!({
+        "valid": false,
+        "keywordLocation": "",
+        "instanceLocation": "",
+        "errors": [
+            {
+                "valid": false,
+                "keywordLocation": "/properties/properties/items",
+                "instanceLocation": "/properties",
+                "errors": [
+                    {
+                        "valid": false,
+                        "keywordLocation": "/properties/properties/items/type",
+                        "instanceLocation": "/properties/0",
+                        "error": "1 is not of type \"string\""
+                    },
+                    {
+                        "valid": false,
+                        "keywordLocation": "/properties/properties/items/type",
+                        "instanceLocation": "/properties/2",
+                        "error": "3 is not of type \"string\""
+                    }
+                ]
+            }
+        ]
+    }); "invalid items under a property named properties"
+}]
+fn test_detailed_output(
+    schema: &serde_json::Value,
+    instance: &serde_json::Value,
+    expected: &serde_json::Value,
+) {
+    let validator = jsonschema::validator_for(schema).unwrap();
+    let output = serde_json::to_value(validator.apply(instance).detailed()).unwrap();
+    assert_eq!(&output, expected);
+}
+
+#[test_case{
+    &json!({"type": "string", "title": "root"}),
+    &json!("some string"),
+    &json!({
+        "valid": true,
+        "keywordLocation": "",
+        "instanceLocation": "",
+        "annotation": {
+            "title": "root"
+        },
+        "annotations": [
+            {
+                "valid": true,
+                "keywordLocation": "/type",
+                "instanceLocation": ""
+            }
+        ]
+    }); "valid root annotations"
+}]
+#[test_case{
+    &json!({"properties": {"a": {"type": "string", "title": "A"}, "b": {"type": "integer"}}}),
+    &json!({"a": "x", "b": "y"}),
+    &json!({
+        "valid": false,
+        "keywordLocation": "",
+        "instanceLocation": "",
+        "errors": [
+            {
+                "valid": false,
+                "keywordLocation": "/properties",
+                "instanceLocation": "",
+                "errors": [
+                    {
+                        "valid": true,
+                        "keywordLocation": "/properties/a",
+                        "instanceLocation": "/a",
+                        "annotation": {
+                            "title": "A"
+                        },
+                        "annotations": [
+                            {
+                                "valid": true,
+                                "keywordLocation": "/properties/a/type",
+                                "instanceLocation": "/a"
+                            }
+                        ]
+                    },
+                    {
+                        "valid": false,
+                        "keywordLocation": "/properties/b",
+                        "instanceLocation": "/b",
+                        "errors": [
+                            {
+                                "valid": false,
+                                "keywordLocation": "/properties/b/type",
+                                "instanceLocation": "/b",
+                                "error": "\"y\" is not of type \"integer\""
+                            }
+                        ]
+                    }
+                ]
+            }
+        ]
+    }); "valid subschemas under an invalid instance"
+}]
+#[test_case{
+    &json!({"properties": {"age": {"anyOf": [{"type": "integer"}, {"type": "null"}]}}}),
+    &json!({"age": "old"}),
+    &json!({
+        "valid": false,
+        "keywordLocation": "",
+        "instanceLocation": "",
+        "errors": [
+            {
+                "valid": false,
+                "keywordLocation": "/properties",
+                "instanceLocation": "",
+                "errors": [
+                    {
+                        "valid": false,
+                        "keywordLocation": "/properties/age",
+                        "instanceLocation": "/age",
+                        "errors": [
+                            {
+                                "valid": false,
+                                "keywordLocation": "/properties/age/anyOf",
+                                "instanceLocation": "/age",
+                                "errors": [
+                                    {
+                                        "valid": false,
+                                        "keywordLocation": "/properties/age/anyOf/0",
+                                        "instanceLocation": "/age",
+                                        "errors": [
+                                            {
+                                                "valid": false,
+                                                "keywordLocation": "/properties/age/anyOf/0/type",
+                                                "instanceLocation": "/age",
+                                                "error": "\"old\" is not of type \"integer\""
+                                            }
+                                        ]
+                                    },
+                                    {
+                                        "valid": false,
+                                        "keywordLocation": "/properties/age/anyOf/1",
+                                        "instanceLocation": "/age",
+                                        "errors": [
+                                            {
+                                                "valid": false,
+                                                "keywordLocation": "/properties/age/anyOf/1/type",
+                                                "instanceLocation": "/age",
+                                                "error": "\"old\" is not of type \"null\""
+                                            }
+                                        ]
+                                    }
+                                ]
+                            }
+                        ]
+                    }
+                ]
+            }
+        ]
+    }); "invalid anyOf"
+}]
+#[test_case{
+    &json!({"properties": {"items": {"properties": {"id": {"type": "integer"}}}}}),
+    &json!({"items": {"id": "x"}}),
+    &json!({
+        "valid": false,
+        "keywordLocation": "",
+        "instanceLocation": "",
+        "errors": [
+            {
+                "valid": false,
+                "keywordLocation": "/properties",
+                "instanceLocation": "",
+                "errors": [
+                    {
+                        "valid": false,
+                        "keywordLocation": "/properties/items",
+                        "instanceLocation": "/items",
+                        "errors": [
+                            {
+                                "valid": false,
+                                "keywordLocation": "/properties/items/properties",
+                                "instanceLocation": "/items",
+                                "errors": [
+                                    {
+                                        "valid": false,
+                                        "keywordLocation": "/properties/items/properties/id",
+                                        "instanceLocation": "/items/id",
+                                        "errors": [
+                                            {
+                                                "valid": false,
+                                                "keywordLocation": "/properties/items/properties/id/type",
+                                                "instanceLocation": "/items/id",
+                                                "error": "\"x\" is not of type \"integer\""
+                                            }
+                                        ]
+                                    }
+                                ]
+                            }
+                        ]
+                    }
+                ]
+            }
+        ]
+    }); "invalid nested properties"
+}]
+fn test_verbose_output(
+    schema: &serde_json::Value,
+    instance: &serde_json::Value,
+    expected: &serde_json::Value,
+) {
+    let validator = jsonschema::validator_for(schema).unwrap();
+    let output = serde_json::to_value(validator.apply(instance).verbose()).unwrap();
+    if &output != expected {
+        let expected_str = serde_json::to_string_pretty(expected).unwrap();
+        let actual_str = serde_json::to_string_pretty(&output).unwrap();
+        panic!("\nExpected:\n{}\n\nGot:\n{}\n", expected_str, actual_str);
+    }
+}
+
+#[test]
+fn test_annotate_collects_by_instance_location() {
+    let schema = json!({
+        "title": "User",
+        "properties": {
+            "email": {"title": "E-mail", "readOnly": true},
+            "tags": {"items": {"type": "string"}}
+        }
+    });
+    let validator = jsonschema::validator_for(&schema).unwrap();
+    let instance = json!({"email": "a@example.com", "tags": ["a"], "extra": 1});
+    let annotations = validator.annotate(&instance);
+
+    let root = annotations.get("").unwrap();
+    assert_eq!(root.get("title")[0].value(), &json!("User"));
+    assert_eq!(
+        root.evaluated_properties().iter().collect::<Vec<_>>(),
+        vec!["email", "tags"]
+    );
+    let email = annotations.get("/email").unwrap();
+    assert_eq!(email.get("readOnly")[0].value(), &json!(true));
+    assert_eq!(
+        email.get("readOnly")[0].keyword_location().as_str(),
+        "/properties/email/readOnly"
+    );
+    assert!(email.get("description").is_empty());
+    let tags = annotations.get("/tags").unwrap();
+    assert_eq!(tags.evaluated_items().iter().collect::<Vec<_>>(), vec![&0]);
+}
+
+#[test]
+fn test_annotate_drops_failed_branches() {
+    let schema = json!({
+        "anyOf": [
+            {"type": "string", "title": "String"},
+            {"type": "integer", "title": "Integer"}
+        ]
+    });
+    let validator = jsonschema::validator_for(&schema).unwrap();
+    let annotations = validator.annotate(&json!(42));
+    let titles: Vec<_> = annotations
+        .get("")
+        .unwrap()
+        .get("title")
+        .iter()
+        .map(|annotation| annotation.value().clone())
+        .collect();
+    assert_eq!(titles, vec![json!("Integer")]);
+}
+
+#[test]
+fn test_annotate_invalid_instance() {
+    let schema = json!({"title": "Number", "type": "number"});
+    let validator = jsonschema::validator_for(&schema).unwrap();
+    assert!(validator.annotate(&json!("foo")).is_empty());
+}
+
+#[test]
+fn test_annotate_unevaluated() {
+    let schema = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "prefixItems": [{"type": "integer"}],
+        "contains": {"type": "string"},
+        "unevaluatedItems": {"type": "boolean"},
+        "properties": {"nested": {
+            "patternProperties": {"^a": true},
+            "unevaluatedProperties": {"type": "number"}
+        }}
+    });
+    let validator = jsonschema::validator_for(&schema).unwrap();
+    let instance = json!([1, "x", true]);
+    let annotations = validator.annotate(&instance);
+    let root = annotations.get("").unwrap();
+    assert_eq!(
+        root.evaluated_items().iter().collect::<Vec<_>>(),
+        vec![&0, &1, &2]
+    );
+    assert_eq!(root.get("unevaluatedItems")[0].value(), &json!(true));
+
+    let instance = json!({"nested": {"a": null, "b": 1}});
+    let annotations = validator.annotate(&instance);
+    let nested = annotations.get("/nested").unwrap();
+    assert_eq!(
+        nested.evaluated_properties().iter().collect::<Vec<_>>(),
+        vec!["a", "b"]
+    );
+    assert_eq!(
+        nested.get("unevaluatedProperties")[0].value(),
+        &json!(["b"])
+    );
+
+    // Only what adjacent keywords did not evaluate is evaluated by `unevaluated*`
+    let instance = json!({"nested": {"a": 2}});
+    let annotations = validator.annotate(&instance);
+    let nested = annotations.get("/nested").unwrap();
+    assert_eq!(nested.get("unevaluatedProperties")[0].value(), &json!([]));
+
+    let schema = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "prefixItems": [{"type": "boolean"}],
+        "unevaluatedItems": {"type": "boolean"}
+    });
+    let validator = jsonschema::validator_for(&schema).unwrap();
+    let annotations = validator.annotate(&json!([true]));
+    let root = annotations.get("").unwrap();
+    assert_eq!(root.get("unevaluatedItems")[0].value(), &json!(false));
+    let annotations = validator.annotate(&json!([true, false]));
+    let root = annotations.get("").unwrap();
+    assert_eq!(root.get("unevaluatedItems")[0].value(), &json!(true));
+}